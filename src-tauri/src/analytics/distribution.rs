@@ -0,0 +1,140 @@
+use super::export::sessions_in_range;
+use super::model::{AnalyticsState, DayOfWeek, FocusDistribution};
+use super::service::day_key;
+use crate::timer::engine::Phase;
+use chrono::{NaiveDate, Weekday};
+
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+/// Spreads the focus minutes of sessions completed from `start` to `end`
+/// over the weekday and hour they ran in. Sessions are only kept within
+/// the retention window, so older ranges come back empty.
+pub fn build_focus_distribution(
+    state: &AnalyticsState,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> FocusDistribution {
+    let boundary = state.day_boundary();
+    let mut minutes = [[0u32; 24]; 7];
+
+    for session in sessions_in_range(&state.sessions, &boundary, start, end, false) {
+        if session.phase != Phase::Focus {
+            continue;
+        }
+        let duration_ms = session
+            .duration_ms
+            .unwrap_or(session.ended_at_ms - session.started_at_ms);
+        for ((weekday, hour), share) in boundary.hour_credit(session, duration_ms) {
+            let cell = &mut minutes[weekday.num_days_from_monday() as usize][hour as usize];
+            *cell = cell.saturating_add(share);
+        }
+    }
+
+    let by_weekday: Vec<u32> = minutes.iter().map(|hours| hours.iter().sum()).collect();
+    let by_hour: Vec<u32> = (0..24)
+        .map(|hour| minutes.iter().map(|hours| hours[hour]).sum())
+        .collect();
+
+    FocusDistribution {
+        from: day_key(start),
+        to: day_key(end),
+        minutes,
+        total_minutes: by_weekday.iter().sum(),
+        best_hour: busiest(&by_hour).map(|hour| hour as u32),
+        best_weekday: busiest(&by_weekday).map(|day| DayOfWeek::from(WEEKDAYS[day])),
+    }
+}
+
+/// The index with the most minutes, the earliest on a tie, or `None` when
+/// everything is zero.
+fn busiest(totals: &[u32]) -> Option<usize> {
+    totals
+        .iter()
+        .enumerate()
+        .filter(|(_, total)| **total > 0)
+        .fold(
+            None,
+            |best: Option<(usize, u32)>, (index, total)| match best {
+                Some((_, best_total)) if best_total >= *total => best,
+                _ => Some((index, *total)),
+            },
+        )
+        .map(|(index, _)| index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytics::model::{SessionOutcome, SessionRecord};
+
+    // Monday 2026-03-02 09:00 UTC.
+    const MONDAY_NINE_MS: i64 = 1_772_442_000_000;
+    const HOUR_MS: i64 = 3_600_000;
+    const DAY_MS: i64 = 24 * HOUR_MS;
+
+    fn focus(started_at_ms: i64, minutes: i64, outcome: SessionOutcome) -> SessionRecord {
+        SessionRecord {
+            phase: Phase::Focus,
+            started_at_ms,
+            ended_at_ms: started_at_ms + minutes * 60_000,
+            outcome,
+            profile_id: None,
+            clock_jumps: Vec::new(),
+            utc_offset_minutes: Some(0),
+            duration_ms: Some(minutes * 60_000),
+            task: None,
+            project: None,
+            closed_long_cycle: false,
+        }
+    }
+
+    #[test]
+    fn distribution_buckets_completed_minutes_by_weekday_and_hour() {
+        let sessions = vec![
+            focus(MONDAY_NINE_MS + 45 * 60_000, 30, SessionOutcome::Completed),
+            focus(MONDAY_NINE_MS + DAY_MS, 50, SessionOutcome::Completed),
+            focus(
+                MONDAY_NINE_MS + DAY_MS + 5 * HOUR_MS,
+                25,
+                SessionOutcome::Completed,
+            ),
+            focus(MONDAY_NINE_MS + 2 * DAY_MS, 25, SessionOutcome::Abandoned),
+            focus(MONDAY_NINE_MS + 7 * DAY_MS, 25, SessionOutcome::Completed),
+        ];
+        let state = AnalyticsState {
+            sessions,
+            ..AnalyticsState::default()
+        };
+        let start = NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+        let end = NaiveDate::from_ymd_opt(2026, 3, 8).unwrap();
+
+        let distribution = build_focus_distribution(&state, start, end);
+
+        assert_eq!(distribution.minutes[0][9], 15);
+        assert_eq!(distribution.minutes[0][10], 15);
+        assert_eq!(distribution.minutes[1][9], 50);
+        assert_eq!(distribution.minutes[1][14], 25);
+        assert_eq!(distribution.total_minutes, 105);
+        assert_eq!(distribution.best_hour, Some(9));
+        assert_eq!(distribution.best_weekday, Some(DayOfWeek::Tuesday));
+    }
+
+    #[test]
+    fn empty_distribution_has_no_best_slot() {
+        let day = NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+
+        let distribution = build_focus_distribution(&AnalyticsState::default(), day, day);
+
+        assert_eq!(distribution.total_minutes, 0);
+        assert_eq!(distribution.best_hour, None);
+        assert_eq!(distribution.best_weekday, None);
+    }
+}
@@ -0,0 +1,384 @@
+use super::model::{AnalyticsState, HistoryExport, SessionOutcome, SessionRecord};
+use super::service::{day_key, month_key, parse_day_key};
+use super::zone::DayBoundary;
+use crate::timer::engine::{normalize_locale, phase_label, ZH_CN_LOCALE};
+use chrono::{DateTime, NaiveDate};
+use std::fmt::Write;
+
+const ICS_PRODID: &str = "-//Pomoduo//Focus Sessions//EN";
+const ICS_LINE_LIMIT: usize = 75;
+const ICS_TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+const HISTORY_CSV_HEADER: &str = "date,focus_completed,long_cycle_completed,focus_minutes";
+
+pub fn parse_export_range(from: &str, to: &str) -> Result<(NaiveDate, NaiveDate), String> {
+    let start = parse_day_key(from).ok_or_else(|| format!("invalid start date: {from}"))?;
+    let end = parse_day_key(to).ok_or_else(|| format!("invalid end date: {to}"))?;
+
+    if start > end {
+        return Err(format!("invalid date range: {from} is after {to}"));
+    }
+
+    Ok((start, end))
+}
+
+pub fn sessions_in_range<'a>(
+    sessions: &'a [SessionRecord],
+    boundary: &DayBoundary,
+    start: NaiveDate,
+    end: NaiveDate,
+    include_abandoned: bool,
+) -> Vec<&'a SessionRecord> {
+    sessions
+        .iter()
+        .filter(|session| include_abandoned || session.outcome == SessionOutcome::Completed)
+        .filter(|session| {
+            boundary
+                .session_date(session)
+                .is_some_and(|date| date >= start && date <= end)
+        })
+        .collect()
+}
+
+pub fn render_sessions_ics(
+    sessions: &[&SessionRecord],
+    locale: &str,
+    generated_at_ms: i64,
+) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:{ICS_PRODID}"),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+    ];
+
+    let stamp = ics_timestamp(generated_at_ms);
+    for session in sessions {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!(
+            "UID:{}-{}@pomoduo",
+            session.started_at_ms, session.ended_at_ms
+        ));
+        lines.push(format!("DTSTAMP:{stamp}"));
+        lines.push(format!("DTSTART:{}", ics_timestamp(session.started_at_ms)));
+        lines.push(format!("DTEND:{}", ics_timestamp(session.ended_at_ms)));
+        lines.push(format!(
+            "SUMMARY:{}",
+            escape_text(&session_summary(session, locale))
+        ));
+        lines.push(format!(
+            "DESCRIPTION:{}",
+            escape_text(&session_description(session, locale))
+        ));
+        lines.push("TRANSP:OPAQUE".to_string());
+        lines.push("END:VEVENT".to_string());
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut output = String::new();
+    for line in lines {
+        output.push_str(&fold_line(&line));
+        output.push_str("\r\n");
+    }
+    output
+}
+
+pub fn build_history_export(
+    state: &AnalyticsState,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> HistoryExport {
+    HistoryExport {
+        version: state.version,
+        from: day_key(start),
+        to: day_key(end),
+        goals: state.goals.clone(),
+        daily: state
+            .daily
+            .iter()
+            .filter(|(key, _)| parse_day_key(key).is_some_and(|date| date >= start && date <= end))
+            .map(|(key, metrics)| (key.clone(), *metrics))
+            .collect(),
+        monthly: state
+            .monthly
+            .range(month_key(start)..=month_key(end))
+            .map(|(key, metrics)| (key.clone(), *metrics))
+            .collect(),
+        sessions: sessions_in_range(&state.sessions, &state.day_boundary(), start, end, true)
+            .into_iter()
+            .cloned()
+            .collect(),
+    }
+}
+
+pub fn render_history_csv(state: &AnalyticsState, start: NaiveDate, end: NaiveDate) -> String {
+    let mut output = String::from(HISTORY_CSV_HEADER);
+    output.push('\n');
+
+    for date in start.iter_days().take_while(|date| *date <= end) {
+        let key = day_key(date);
+        let metrics = state.daily.get(&key).copied().unwrap_or_default();
+        let _ = writeln!(
+            output,
+            "{key},{},{},{}",
+            metrics.focus_completed, metrics.long_cycle_completed, metrics.focus_minutes
+        );
+    }
+
+    output
+}
+
+/// Phase, then task and project when known, e.g. `Pomoduo: Focus - Report - Thesis`.
+fn session_summary(session: &SessionRecord, locale: &str) -> String {
+    let mut summary = format!("Pomoduo: {}", phase_label(session.phase, locale));
+    for label in [&session.task, &session.project].into_iter().flatten() {
+        let _ = write!(summary, " - {label}");
+    }
+    if session.outcome == SessionOutcome::Abandoned {
+        let _ = write!(summary, " ({})", outcome_label(session.outcome, locale));
+    }
+    summary
+}
+
+fn session_description(session: &SessionRecord, locale: &str) -> String {
+    let minutes = (session.ended_at_ms - session.started_at_ms).max(0) / 60_000;
+    let phase = phase_label(session.phase, locale);
+    let outcome = outcome_label(session.outcome, locale);
+    let zh = normalize_locale(locale) == ZH_CN_LOCALE;
+
+    let mut lines = vec![if zh {
+        format!("\u{9636}\u{6BB5}\u{FF1A}{phase}")
+    } else {
+        format!("Phase: {phase}")
+    }];
+    if let Some(task) = session.task.as_ref() {
+        lines.push(if zh {
+            format!("\u{4EFB}\u{52A1}\u{FF1A}{task}")
+        } else {
+            format!("Task: {task}")
+        });
+    }
+    if let Some(project) = session.project.as_ref() {
+        lines.push(if zh {
+            format!("\u{9879}\u{76EE}\u{FF1A}{project}")
+        } else {
+            format!("Project: {project}")
+        });
+    }
+    if zh {
+        lines.push(format!("\u{7ED3}\u{679C}\u{FF1A}{outcome}"));
+        lines.push(format!(
+            "\u{65F6}\u{957F}\u{FF1A}{minutes} \u{5206}\u{949F}"
+        ));
+    } else {
+        lines.push(format!("Outcome: {outcome}"));
+        lines.push(format!("Duration: {minutes} min"));
+    }
+    lines.join("\n")
+}
+
+fn outcome_label(outcome: SessionOutcome, locale: &str) -> &'static str {
+    if normalize_locale(locale) == ZH_CN_LOCALE {
+        match outcome {
+            SessionOutcome::Completed => "\u{5DF2}\u{5B8C}\u{6210}",
+            SessionOutcome::Abandoned => "\u{5DF2}\u{653E}\u{5F03}",
+        }
+    } else {
+        match outcome {
+            SessionOutcome::Completed => "Completed",
+            SessionOutcome::Abandoned => "Abandoned",
+        }
+    }
+}
+
+fn ics_timestamp(timestamp_ms: i64) -> String {
+    DateTime::from_timestamp_millis(timestamp_ms)
+        .unwrap_or_default()
+        .format(ICS_TIMESTAMP_FORMAT)
+        .to_string()
+}
+
+fn escape_text(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut line_octets = 0;
+
+    for ch in line.chars() {
+        let width = ch.len_utf8();
+        if line_octets + width > ICS_LINE_LIMIT {
+            folded.push_str("\r\n ");
+            line_octets = 1;
+        }
+        folded.push(ch);
+        line_octets += width;
+    }
+
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytics::model::DailyMetrics;
+    use crate::timer::engine::Phase;
+
+    // 2026-02-15T12:00:00Z, midday so the local date is stable across zones.
+    const SESSION_START_MS: i64 = 1_771_156_800_000;
+
+    fn session(outcome: SessionOutcome) -> SessionRecord {
+        SessionRecord {
+            phase: Phase::Focus,
+            started_at_ms: SESSION_START_MS,
+            ended_at_ms: SESSION_START_MS + 25 * 60_000,
+            outcome,
+            profile_id: None,
+            clock_jumps: Vec::new(),
+            utc_offset_minutes: None,
+            duration_ms: None,
+            task: None,
+            project: None,
+            closed_long_cycle: false,
+        }
+    }
+
+    #[test]
+    fn render_sessions_ics_writes_one_event_per_session_with_crlf() {
+        let completed = session(SessionOutcome::Completed);
+        let ics = render_sessions_ics(&[&completed], "en-US", SESSION_START_MS);
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 1);
+        assert!(ics.contains("DTSTART:20260215T120000Z\r\n"));
+        assert!(ics.contains("DTEND:20260215T122500Z\r\n"));
+        assert!(ics.contains("SUMMARY:Pomoduo: Focus\r\n"));
+        assert!(
+            ics.contains("DESCRIPTION:Phase: Focus\\nOutcome: Completed\\nDuration: 25 min\r\n")
+        );
+    }
+
+    #[test]
+    fn render_sessions_ics_names_task_and_project_when_known() {
+        let mut abandoned = session(SessionOutcome::Abandoned);
+        abandoned.task = Some("Draft, review".to_string());
+        abandoned.project = Some("Thesis".to_string());
+        let ics = render_sessions_ics(&[&abandoned], "en-US", SESSION_START_MS);
+        let unfolded = ics.replace("\r\n ", "");
+
+        assert!(
+            unfolded.contains("SUMMARY:Pomoduo: Focus - Draft\\, review - Thesis (Abandoned)\r\n")
+        );
+        assert!(unfolded.contains(
+            "DESCRIPTION:Phase: Focus\\nTask: Draft\\, review\\nProject: Thesis\\nOutcome: Abandoned\\n"
+        ));
+    }
+
+    #[test]
+    fn sessions_in_range_skips_abandoned_unless_requested() {
+        let sessions = vec![
+            session(SessionOutcome::Completed),
+            session(SessionOutcome::Abandoned),
+        ];
+        let day = NaiveDate::from_ymd_opt(2026, 2, 15).unwrap();
+        let next_day = NaiveDate::from_ymd_opt(2026, 2, 16).unwrap();
+
+        assert_eq!(
+            sessions_in_range(&sessions, &DayBoundary::default(), day, day, false).len(),
+            1
+        );
+        assert_eq!(
+            sessions_in_range(&sessions, &DayBoundary::default(), day, day, true).len(),
+            2
+        );
+        assert!(
+            sessions_in_range(&sessions, &DayBoundary::default(), next_day, next_day, true)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn fold_line_keeps_lines_within_octet_limit_without_splitting_chars() {
+        let line = format!("SUMMARY:{}", "\u{4E13}\u{6CE8}".repeat(40));
+        let folded = fold_line(&line);
+
+        for part in folded.split("\r\n") {
+            assert!(part.len() <= ICS_LINE_LIMIT);
+        }
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+
+    #[test]
+    fn parse_export_range_rejects_reversed_range() {
+        assert!(parse_export_range("2026-02-15", "2026-02-01").is_err());
+        assert!(parse_export_range("2026-02-01", "not-a-date").is_err());
+        assert!(parse_export_range("2026-02-01", "2026-02-15").is_ok());
+    }
+
+    #[test]
+    fn render_history_csv_writes_one_row_per_day_including_empty_days() {
+        let mut state = AnalyticsState::default();
+        state.daily.insert(
+            "2026-02-14".to_string(),
+            DailyMetrics {
+                focus_completed: 6,
+                long_cycle_completed: 1,
+                focus_minutes: 150,
+            },
+        );
+        let start = NaiveDate::from_ymd_opt(2026, 2, 13).unwrap();
+        let end = NaiveDate::from_ymd_opt(2026, 2, 15).unwrap();
+
+        let csv = render_history_csv(&state, start, end);
+
+        assert_eq!(
+            csv,
+            "date,focus_completed,long_cycle_completed,focus_minutes\n\
+             2026-02-13,0,0,0\n\
+             2026-02-14,6,1,150\n\
+             2026-02-15,0,0,0\n"
+        );
+    }
+
+    #[test]
+    fn build_history_export_keeps_goals_and_only_days_in_range() {
+        let mut state = AnalyticsState::default();
+        for key in ["2026-02-01", "2026-02-15", "2026-03-01"] {
+            state.daily.insert(
+                key.to_string(),
+                DailyMetrics {
+                    focus_completed: 1,
+                    long_cycle_completed: 0,
+                    focus_minutes: 0,
+                },
+            );
+        }
+        state.sessions.push(session(SessionOutcome::Abandoned));
+        let start = NaiveDate::from_ymd_opt(2026, 2, 10).unwrap();
+        let end = NaiveDate::from_ymd_opt(2026, 2, 28).unwrap();
+
+        let export = build_history_export(&state, start, end);
+
+        assert_eq!(export.daily.keys().collect::<Vec<_>>(), vec!["2026-02-15"]);
+        assert_eq!(export.sessions.len(), 1);
+        assert_eq!(
+            export.goals.daily.focus_target,
+            state.goals.daily.focus_target
+        );
+        assert_eq!(export.from, "2026-02-10");
+    }
+}
@@ -0,0 +1,487 @@
+use super::model::{AnalyticsState, DailyMetrics, ImportColumnMapping, ImportPreview};
+use super::service::{day_key, local_date_for_ms, month_key, prune_history};
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime};
+use std::collections::{BTreeMap, HashSet};
+
+const FALLBACK_DATE_TIME_FORMATS: [&str; 4] = [
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y/%m/%d %H:%M",
+];
+const FALLBACK_DATE_FORMATS: [&str; 4] = ["%Y-%m-%d", "%Y/%m/%d", "%m/%d/%Y", "%d.%m.%Y"];
+
+#[derive(Debug, Clone, Default)]
+pub struct ParsedImport {
+    pub daily: BTreeMap<String, DailyMetrics>,
+    /// Rows older than the retention window, already rolled up per month.
+    pub monthly: BTreeMap<String, DailyMetrics>,
+    pub rows_read: usize,
+    pub rows_imported: usize,
+    pub duplicate_rows: usize,
+    pub skipped_rows: usize,
+    pub out_of_retention_rows: usize,
+}
+
+pub fn parse_import_csv(
+    text: &str,
+    mapping: &ImportColumnMapping,
+    today: NaiveDate,
+    retention_days: Option<u32>,
+) -> Result<ParsedImport, String> {
+    let delimiter = mapping.delimiter.unwrap_or(',');
+    if !delimiter.is_ascii() {
+        return Err(format!("unsupported delimiter: {delimiter}"));
+    }
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter as u8)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(text.as_bytes());
+
+    let headers = reader
+        .headers()
+        .map_err(|err| format!("failed to read import header: {err}"))?
+        .clone();
+    let column = |name: &str| {
+        headers
+            .iter()
+            .position(|header| header.eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| format!("column not found in import file: {name}"))
+    };
+
+    let date_index = column(&mapping.date_column)?;
+    let count_index = mapping.count_column.as_deref().map(column).transpose()?;
+    let long_cycle_index = mapping
+        .long_cycle_column
+        .as_deref()
+        .map(column)
+        .transpose()?;
+    let minutes_index = mapping.minutes_column.as_deref().map(column).transpose()?;
+    let id_index = mapping.id_column.as_deref().map(column).transpose()?;
+    let filter = match (&mapping.filter_column, &mapping.filter_value) {
+        (Some(name), Some(value)) => Some((column(name)?, value.trim().to_string())),
+        _ => None,
+    };
+
+    let oldest_retained =
+        retention_days.map(|days| today - Duration::days(i64::from(days.max(1)) - 1));
+    let mut parsed = ParsedImport::default();
+    let mut seen_rows = HashSet::new();
+
+    for record in reader.records() {
+        parsed.rows_read += 1;
+
+        let Ok(record) = record else {
+            parsed.skipped_rows += 1;
+            continue;
+        };
+
+        if let Some((index, expected)) = &filter {
+            if !record
+                .get(*index)
+                .is_some_and(|value| value.eq_ignore_ascii_case(expected))
+            {
+                parsed.skipped_rows += 1;
+                continue;
+            }
+        }
+
+        let date = record
+            .get(date_index)
+            .and_then(|value| parse_import_date(value, mapping.date_format.as_deref()));
+
+        // Only an ID or a timestamp identifies a row; repeated date-only
+        // totals are kept.
+        let key_columns = match id_index {
+            Some(index) => vec![index],
+            None if date.is_some_and(|(_, has_time)| has_time) => [
+                Some(date_index),
+                count_index,
+                long_cycle_index,
+                minutes_index,
+            ]
+            .into_iter()
+            .flatten()
+            .collect(),
+            None => Vec::new(),
+        };
+        if !key_columns.is_empty() {
+            let key = key_columns
+                .iter()
+                .map(|index| record.get(*index).unwrap_or_default())
+                .collect::<Vec<_>>()
+                .join("\u{1F}");
+            if !seen_rows.insert(key) {
+                parsed.duplicate_rows += 1;
+                continue;
+            }
+        }
+        let date = date.map(|(date, _)| date);
+        let focus_completed = match count_index {
+            Some(index) => record.get(index).and_then(parse_import_count),
+            None => Some(1),
+        };
+        let long_cycle_completed = match long_cycle_index {
+            Some(index) => record.get(index).and_then(parse_import_count),
+            None => Some(0),
+        };
+
+        let focus_minutes = match minutes_index {
+            Some(index) => record.get(index).and_then(parse_import_count),
+            None => Some(0),
+        };
+
+        let (Some(date), Some(focus_completed), Some(long_cycle_completed), Some(focus_minutes)) =
+            (date, focus_completed, long_cycle_completed, focus_minutes)
+        else {
+            parsed.skipped_rows += 1;
+            continue;
+        };
+
+        if date > today {
+            parsed.out_of_retention_rows += 1;
+            continue;
+        }
+
+        let metrics = DailyMetrics {
+            focus_completed,
+            long_cycle_completed,
+            focus_minutes,
+        };
+        if oldest_retained.is_some_and(|oldest| date < oldest) {
+            *parsed.monthly.entry(month_key(date)).or_default() += metrics;
+        } else {
+            *parsed.daily.entry(day_key(date)).or_default() += metrics;
+        }
+        parsed.rows_imported += 1;
+    }
+
+    Ok(parsed)
+}
+
+/// Merges imported days by taking the larger value per metric, so importing
+/// the same export twice (or overlapping it with tracked days) never double counts.
+/// Rows past the retention window merge into the monthly rollups the same way.
+/// Periods that already had history are listed so the overlap is not silent.
+pub fn merge_imported_history(
+    state: &mut AnalyticsState,
+    parsed: &ParsedImport,
+    today: NaiveDate,
+    retention_days: Option<u32>,
+    dry_run: bool,
+) -> ImportPreview {
+    let mut preview = ImportPreview {
+        dry_run,
+        rows_read: parsed.rows_read,
+        rows_imported: parsed.rows_imported,
+        duplicate_rows: parsed.duplicate_rows,
+        skipped_rows: parsed.skipped_rows,
+        out_of_retention_rows: parsed.out_of_retention_rows,
+        first_day: parsed.daily.keys().next().cloned(),
+        last_day: parsed.daily.keys().next_back().cloned(),
+        ..ImportPreview::default()
+    };
+
+    for (key, imported) in &parsed.daily {
+        let existing = state.daily.get(key).copied();
+        let merged = merge_metrics(existing, imported);
+        if existing.is_some_and(has_activity) {
+            preview.overlap_days.push(key.clone());
+        }
+        let previous = existing.unwrap_or_default();
+
+        preview.focus_completed_added += merged.focus_completed - previous.focus_completed;
+        preview.long_cycle_completed_added +=
+            merged.long_cycle_completed - previous.long_cycle_completed;
+
+        match existing {
+            None => preview.days_added += 1,
+            Some(metrics)
+                if metrics.focus_completed != merged.focus_completed
+                    || metrics.long_cycle_completed != merged.long_cycle_completed
+                    || metrics.focus_minutes != merged.focus_minutes =>
+            {
+                preview.days_updated += 1
+            }
+            Some(_) => {}
+        }
+
+        state.daily.insert(key.clone(), merged);
+    }
+
+    for (key, imported) in &parsed.monthly {
+        let previous = state.monthly.get(key).copied().unwrap_or_default();
+        let merged = merge_metrics(Some(previous), imported);
+        if has_activity(previous) {
+            preview.overlap_months.push(key.clone());
+        }
+
+        preview.focus_completed_added += merged.focus_completed - previous.focus_completed;
+        preview.long_cycle_completed_added +=
+            merged.long_cycle_completed - previous.long_cycle_completed;
+        if merged != previous {
+            preview.months_updated += 1;
+        }
+
+        state.monthly.insert(key.clone(), merged);
+    }
+
+    prune_history(state, today, retention_days);
+
+    preview
+}
+
+fn merge_metrics(existing: Option<DailyMetrics>, imported: &DailyMetrics) -> DailyMetrics {
+    let existing = existing.unwrap_or_default();
+    DailyMetrics {
+        focus_completed: existing.focus_completed.max(imported.focus_completed),
+        long_cycle_completed: existing
+            .long_cycle_completed
+            .max(imported.long_cycle_completed),
+        focus_minutes: existing.focus_minutes.max(imported.focus_minutes),
+    }
+}
+
+fn has_activity(metrics: DailyMetrics) -> bool {
+    metrics != DailyMetrics::default()
+}
+
+/// Parses the date of a row and whether the value also carried a time.
+fn parse_import_date(value: &str, format: Option<&str>) -> Option<(NaiveDate, bool)> {
+    let value = value.trim();
+
+    if let Some(format) = format {
+        return NaiveDateTime::parse_from_str(value, format)
+            .map(|datetime| (datetime.date(), true))
+            .or_else(|_| NaiveDate::parse_from_str(value, format).map(|date| (date, false)))
+            .ok();
+    }
+
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+        return local_date_for_ms(datetime.timestamp_millis()).map(|date| (date, true));
+    }
+
+    FALLBACK_DATE_TIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .map(|datetime| (datetime.date(), true))
+        .or_else(|| {
+            FALLBACK_DATE_FORMATS
+                .iter()
+                .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+                .map(|date| (date, false))
+        })
+}
+
+fn parse_import_count(value: &str) -> Option<u32> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Some(0);
+    }
+
+    value.parse::<u32>().ok().or_else(|| {
+        value
+            .parse::<f64>()
+            .ok()
+            .filter(|count| count.is_finite() && *count >= 0.0)
+            .map(|count| count.round() as u32)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RETENTION: Option<u32> = Some(400);
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 2, 15).unwrap()
+    }
+
+    fn session_mapping() -> ImportColumnMapping {
+        ImportColumnMapping {
+            date_column: "Start".to_string(),
+            date_format: None,
+            count_column: None,
+            long_cycle_column: None,
+            minutes_column: None,
+            filter_column: Some("Type".to_string()),
+            filter_value: Some("pomodoro".to_string()),
+            id_column: None,
+            delimiter: None,
+        }
+    }
+
+    #[test]
+    fn parse_import_csv_counts_session_rows_and_skips_duplicates_and_breaks() {
+        let text = "Start,Type,Task\n\
+                    2026-02-14 09:00,Pomodoro,\"Write, review\"\n\
+                    2026-02-14 09:00,Pomodoro,\"Write, review\"\n\
+                    2026-02-14 09:25,Break,\n\
+                    2026-02-14 09:30,Pomodoro,Email\n\
+                    not a date,Pomodoro,Email\n\
+                    2020-01-01 09:00,Pomodoro,Old\n";
+
+        let parsed = parse_import_csv(text, &session_mapping(), today(), RETENTION).unwrap();
+
+        assert_eq!(parsed.rows_read, 6);
+        assert_eq!(parsed.rows_imported, 3);
+        assert_eq!(parsed.duplicate_rows, 1);
+        assert_eq!(parsed.skipped_rows, 2);
+        assert_eq!(parsed.out_of_retention_rows, 0);
+        assert_eq!(parsed.daily.get("2026-02-14").unwrap().focus_completed, 2);
+        assert!(!parsed.daily.contains_key("2020-01-01"));
+        assert_eq!(parsed.monthly.get("2020-01").unwrap().focus_completed, 1);
+    }
+
+    #[test]
+    fn parse_import_csv_reads_daily_totals_with_custom_format() {
+        let mapping = ImportColumnMapping {
+            date_column: "day".to_string(),
+            date_format: Some("%d/%m/%Y".to_string()),
+            count_column: Some("pomodoros".to_string()),
+            long_cycle_column: Some("sets".to_string()),
+            minutes_column: Some("minutes".to_string()),
+            filter_column: None,
+            filter_value: None,
+            id_column: None,
+            delimiter: Some(';'),
+        };
+        let text = "day;pomodoros;sets;minutes\n13/02/2026;7;1;175\n14/02/2026;3.0;;\n";
+
+        let parsed = parse_import_csv(text, &mapping, today(), RETENTION).unwrap();
+
+        assert_eq!(parsed.rows_imported, 2);
+        assert_eq!(parsed.daily.get("2026-02-13").unwrap().focus_completed, 7);
+        assert_eq!(
+            parsed.daily.get("2026-02-13").unwrap().long_cycle_completed,
+            1
+        );
+        assert_eq!(parsed.daily.get("2026-02-13").unwrap().focus_minutes, 175);
+        assert_eq!(parsed.daily.get("2026-02-14").unwrap().focus_completed, 3);
+    }
+
+    #[test]
+    fn parse_import_csv_dedupes_on_mapped_keys_only() {
+        let totals = ImportColumnMapping {
+            date_column: "day".to_string(),
+            count_column: Some("pomodoros".to_string()),
+            filter_column: None,
+            filter_value: None,
+            ..session_mapping()
+        };
+        let by_id = ImportColumnMapping {
+            id_column: Some("Id".to_string()),
+            ..session_mapping()
+        };
+
+        let parsed = parse_import_csv(
+            "day,pomodoros\n2026-02-14,2\n2026-02-14,2\n",
+            &totals,
+            today(),
+            RETENTION,
+        )
+        .unwrap();
+        let repeated = parse_import_csv(
+            "Id,Start,Type,Task\n\
+             a,2026-02-14 09:00,Pomodoro,Write\n\
+             a,2026-02-14 09:00,Pomodoro,Write (edited)\n\
+             b,2026-02-14 09:00,Pomodoro,Email\n",
+            &by_id,
+            today(),
+            RETENTION,
+        )
+        .unwrap();
+
+        assert_eq!(parsed.duplicate_rows, 0);
+        assert_eq!(parsed.daily["2026-02-14"].focus_completed, 4);
+        assert_eq!(repeated.duplicate_rows, 1);
+        assert_eq!(repeated.daily["2026-02-14"].focus_completed, 2);
+    }
+
+    #[test]
+    fn parse_import_csv_reports_missing_columns() {
+        let error = parse_import_csv("Date\n2026-02-14\n", &session_mapping(), today(), RETENTION)
+            .unwrap_err();
+
+        assert!(error.contains("Start"));
+    }
+
+    #[test]
+    fn merge_imported_history_is_idempotent() {
+        let mut state = AnalyticsState::default();
+        state.daily.insert(
+            "2026-02-14".to_string(),
+            DailyMetrics {
+                focus_completed: 4,
+                long_cycle_completed: 1,
+                focus_minutes: 0,
+            },
+        );
+        let mut parsed = ParsedImport::default();
+        parsed.daily.insert(
+            "2026-02-13".to_string(),
+            DailyMetrics {
+                focus_completed: 5,
+                long_cycle_completed: 0,
+                focus_minutes: 0,
+            },
+        );
+        parsed.daily.insert(
+            "2026-02-14".to_string(),
+            DailyMetrics {
+                focus_completed: 2,
+                long_cycle_completed: 0,
+                focus_minutes: 0,
+            },
+        );
+
+        let first = merge_imported_history(&mut state, &parsed, today(), RETENTION, false);
+        let second = merge_imported_history(&mut state, &parsed, today(), RETENTION, false);
+
+        assert_eq!(first.days_added, 1);
+        assert_eq!(first.days_updated, 0);
+        assert_eq!(first.overlap_days, vec!["2026-02-14".to_string()]);
+        assert_eq!(first.focus_completed_added, 5);
+        assert_eq!(second.days_added, 0);
+        assert_eq!(second.focus_completed_added, 0);
+        assert_eq!(state.daily.get("2026-02-14").unwrap().focus_completed, 4);
+        assert_eq!(state.daily.get("2026-02-13").unwrap().focus_completed, 5);
+    }
+
+    #[test]
+    fn merge_imported_history_rolls_rows_past_retention_into_months() {
+        let mut state = AnalyticsState::default();
+        state.monthly.insert(
+            "2020-01".to_string(),
+            DailyMetrics {
+                focus_completed: 2,
+                long_cycle_completed: 0,
+                focus_minutes: 50,
+            },
+        );
+        let text = "Start,Type\n\
+                    2020-01-05 09:00,Pomodoro\n\
+                    2020-01-06 09:00,Pomodoro\n\
+                    2020-01-07 09:00,Pomodoro\n\
+                    2019-12-31 09:00,Pomodoro\n\
+                    2026-02-14 09:00,Pomodoro\n";
+        let parsed = parse_import_csv(text, &session_mapping(), today(), RETENTION).unwrap();
+
+        let first = merge_imported_history(&mut state, &parsed, today(), RETENTION, false);
+        let second = merge_imported_history(&mut state, &parsed, today(), RETENTION, false);
+
+        assert_eq!(first.rows_imported, 5);
+        assert_eq!(first.months_updated, 2);
+        assert_eq!(first.overlap_months, vec!["2020-01".to_string()]);
+        assert_eq!(first.focus_completed_added, 3);
+        assert_eq!(second.months_updated, 0);
+        assert_eq!(state.monthly["2020-01"].focus_completed, 3);
+        assert_eq!(state.monthly["2020-01"].focus_minutes, 50);
+        assert_eq!(state.monthly["2019-12"].focus_completed, 1);
+        assert!(!state.daily.contains_key("2020-01-05"));
+        assert_eq!(state.daily["2026-02-14"].focus_completed, 1);
+    }
+}
@@ -1,2 +1,7 @@
+pub mod distribution;
+pub mod export;
+pub mod import;
 pub mod model;
 pub mod service;
+pub mod streaks;
+pub mod zone;
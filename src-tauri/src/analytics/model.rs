@@ -1,9 +1,14 @@
+use super::zone::{local_offset_minutes, DayBoundary, DayZone};
+use crate::timer::engine::{ClockJumpPolicy, Phase};
+use chrono::Weekday;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
+use std::ops::AddAssign;
 
-pub const ANALYTICS_VERSION: u32 = 1;
+pub const ANALYTICS_VERSION: u32 = 11;
 pub const HEATMAP_DAYS: usize = 53 * 7;
-pub const HISTORY_RETENTION_DAYS: i64 = 400;
+pub const DEFAULT_HISTORY_RETENTION_DAYS: u32 = 400;
+pub const TREND_MONTHS: u32 = 24;
 
 const DEFAULT_DAILY_FOCUS_TARGET: u32 = 8;
 const DEFAULT_DAILY_LONG_CYCLE_TARGET: u32 = 2;
@@ -11,36 +16,111 @@ const DEFAULT_WEEKLY_FOCUS_TARGET: u32 = 40;
 const DEFAULT_WEEKLY_LONG_CYCLE_TARGET: u32 = 10;
 const DEFAULT_MONTHLY_FOCUS_TARGET: u32 = 160;
 const DEFAULT_MONTHLY_LONG_CYCLE_TARGET: u32 = 40;
+const DEFAULT_POMODORO_MINUTES: u32 = 25;
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct DailyMetrics {
     pub focus_completed: u32,
     pub long_cycle_completed: u32,
+    #[serde(default)]
+    pub focus_minutes: u32,
+}
+
+impl DailyMetrics {
+    pub fn is_empty(&self) -> bool {
+        self.focus_completed == 0 && self.long_cycle_completed == 0 && self.focus_minutes == 0
+    }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+impl AddAssign for DailyMetrics {
+    fn add_assign(&mut self, other: Self) {
+        self.focus_completed = self.focus_completed.saturating_add(other.focus_completed);
+        self.long_cycle_completed = self
+            .long_cycle_completed
+            .saturating_add(other.long_cycle_completed);
+        self.focus_minutes = self.focus_minutes.saturating_add(other.focus_minutes);
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct GoalPair {
     pub focus_target: u32,
     pub long_cycle_target: u32,
+    /// What `focus_target` counts.
+    #[serde(default)]
+    pub focus_unit: GoalUnit,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum GoalUnit {
+    #[default]
+    Pomodoros,
+    Minutes,
 }
 
 impl GoalPair {
+    /// `fallback` is in pomodoros; a minute target falls back to as many
+    /// default-length pomodoros.
     pub fn sanitized(self, fallback: GoalPair) -> Self {
+        let focus_fallback = match self.focus_unit {
+            GoalUnit::Pomodoros => fallback.focus_target,
+            GoalUnit::Minutes => fallback.focus_target * DEFAULT_POMODORO_MINUTES,
+        };
+
         Self {
-            focus_target: sanitize_target(self.focus_target, fallback.focus_target),
+            focus_target: sanitize_target(self.focus_target, focus_fallback),
             long_cycle_target: sanitize_target(self.long_cycle_target, fallback.long_cycle_target),
+            focus_unit: self.focus_unit,
+        }
+    }
+
+    /// Focus done towards `focus_target`, in its unit.
+    pub fn focus_progress(&self, metrics: &DailyMetrics) -> u32 {
+        match self.focus_unit {
+            GoalUnit::Pomodoros => metrics.focus_completed,
+            GoalUnit::Minutes => metrics.focus_minutes,
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct GoalSettings {
     pub daily: GoalPair,
     pub weekly: GoalPair,
     pub monthly: GoalPair,
+    /// Weekdays that don't break a streak when no focus is done on them.
+    #[serde(default)]
+    pub rest_days: Vec<DayOfWeek>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub enum DayOfWeek {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl From<Weekday> for DayOfWeek {
+    fn from(weekday: Weekday) -> Self {
+        match weekday {
+            Weekday::Mon => Self::Monday,
+            Weekday::Tue => Self::Tuesday,
+            Weekday::Wed => Self::Wednesday,
+            Weekday::Thu => Self::Thursday,
+            Weekday::Fri => Self::Friday,
+            Weekday::Sat => Self::Saturday,
+            Weekday::Sun => Self::Sunday,
+        }
+    }
 }
 
 impl Default for GoalSettings {
@@ -49,30 +129,90 @@ impl Default for GoalSettings {
             daily: GoalPair {
                 focus_target: DEFAULT_DAILY_FOCUS_TARGET,
                 long_cycle_target: DEFAULT_DAILY_LONG_CYCLE_TARGET,
+                focus_unit: GoalUnit::Pomodoros,
             },
             weekly: GoalPair {
                 focus_target: DEFAULT_WEEKLY_FOCUS_TARGET,
                 long_cycle_target: DEFAULT_WEEKLY_LONG_CYCLE_TARGET,
+                focus_unit: GoalUnit::Pomodoros,
             },
             monthly: GoalPair {
                 focus_target: DEFAULT_MONTHLY_FOCUS_TARGET,
                 long_cycle_target: DEFAULT_MONTHLY_LONG_CYCLE_TARGET,
+                focus_unit: GoalUnit::Pomodoros,
             },
+            rest_days: Vec::new(),
         }
     }
 }
 
 impl GoalSettings {
-    pub fn sanitized(self) -> Self {
+    /// A week of rest days would leave nothing to keep a streak on, so it
+    /// is treated as none.
+    pub fn sanitized(mut self) -> Self {
         let fallback = Self::default();
+        self.rest_days.sort();
+        self.rest_days.dedup();
+        if self.rest_days.len() == 7 {
+            self.rest_days.clear();
+        }
+
         Self {
             daily: self.daily.sanitized(fallback.daily),
             weekly: self.weekly.sanitized(fallback.weekly),
             monthly: self.monthly.sanitized(fallback.monthly),
+            rest_days: self.rest_days,
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SessionOutcome {
+    Completed,
+    Abandoned,
+}
+
+/// A jump of the wall clock against monotonic time seen while a phase ran.
+/// Positive `jump_ms` means the wall clock moved ahead, as after sleep.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ClockJump {
+    pub detected_at_ms: i64,
+    pub jump_ms: i64,
+    pub policy: ClockJumpPolicy,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionRecord {
+    pub phase: Phase,
+    pub started_at_ms: i64,
+    pub ended_at_ms: i64,
+    pub outcome: SessionOutcome,
+    /// Settings profile the session ran with. Missing for sessions recorded
+    /// before profiles existed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub clock_jumps: Vec<ClockJump>,
+    /// UTC offset in minutes where the session ended.
+    #[serde(default)]
+    pub utc_offset_minutes: Option<i32>,
+    /// Focus time credited to a completed session: the length of the phase
+    /// it completed, however long that took on the wall clock.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<i64>,
+    /// What the session was spent on, when known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    /// Set on the focus session that completed a long cycle.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub closed_long_cycle: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AnalyticsState {
@@ -80,8 +220,31 @@ pub struct AnalyticsState {
     pub version: u32,
     #[serde(default)]
     pub daily: BTreeMap<String, DailyMetrics>,
+    /// Totals of days past the retention window, keyed by `YYYY-MM`.
+    #[serde(default)]
+    pub monthly: BTreeMap<String, DailyMetrics>,
     #[serde(default)]
     pub goals: GoalSettings,
+    #[serde(default)]
+    pub sessions: Vec<SessionRecord>,
+    /// The zone the `daily` keys were attributed in.
+    #[serde(default)]
+    pub day_zone: DayZone,
+    /// The hour the `daily` keys were rolled over at.
+    #[serde(default)]
+    pub day_start_hour: u32,
+    /// What the last day boundary change this run had to leave behind.
+    #[serde(skip)]
+    pub unmoved_by_day_change: Option<UnmovedDayCounts>,
+}
+
+/// Counts without a session behind them, such as imported or pruned
+/// history, which a day boundary change leaves on their old days.
+#[derive(Debug, Clone, Copy, Default, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct UnmovedDayCounts {
+    pub focus_completed: u32,
+    pub long_cycle_completed: u32,
 }
 
 impl Default for AnalyticsState {
@@ -89,7 +252,12 @@ impl Default for AnalyticsState {
         Self {
             version: default_version(),
             daily: BTreeMap::new(),
+            monthly: BTreeMap::new(),
             goals: GoalSettings::default(),
+            sessions: Vec::new(),
+            day_zone: DayZone::default(),
+            day_start_hour: 0,
+            unmoved_by_day_change: None,
         }
     }
 }
@@ -103,8 +271,35 @@ impl AnalyticsState {
                 self.version
             },
             daily: self.daily,
+            monthly: self.monthly,
             goals: self.goals.sanitized(),
+            sessions: self
+                .sessions
+                .into_iter()
+                .map(SessionRecord::with_offset)
+                .collect(),
+            day_zone: self.day_zone,
+            day_start_hour: self.day_start_hour,
+            unmoved_by_day_change: self.unmoved_by_day_change,
+        }
+    }
+
+    pub fn day_boundary(&self) -> DayBoundary {
+        DayBoundary {
+            zone: self.day_zone.clone(),
+            start_hour: self.day_start_hour,
+        }
+    }
+}
+
+impl SessionRecord {
+    /// Sessions recorded before offsets were stored were attributed with the
+    /// local rules, so they get the offset those rules give.
+    fn with_offset(mut self) -> Self {
+        if self.utc_offset_minutes.is_none() {
+            self.utc_offset_minutes = Some(local_offset_minutes(self.ended_at_ms));
         }
+        self
     }
 }
 
@@ -114,6 +309,7 @@ pub struct HeatmapDay {
     pub date: String,
     pub focus_completed: u32,
     pub long_cycle_completed: u32,
+    pub focus_minutes: u32,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -121,6 +317,8 @@ pub struct HeatmapDay {
 pub struct PeriodSummary {
     pub focus_completed: u32,
     pub long_cycle_completed: u32,
+    pub focus_minutes: u32,
+    pub focus_unit: GoalUnit,
     pub focus_target: u32,
     pub long_cycle_target: u32,
     pub focus_rate: f64,
@@ -142,6 +340,170 @@ pub struct InsightsSnapshot {
     pub heatmap: Vec<HeatmapDay>,
     pub summaries: PeriodSummaries,
     pub goals: GoalSettings,
+    /// The last `TREND_MONTHS` months, oldest first, including rolled-up
+    /// history.
+    pub monthly_trend: Vec<MonthTotal>,
+    pub streaks: Streaks,
+    pub unmoved_by_day_change: Option<UnmovedDayCounts>,
+}
+
+/// Consecutive qualifying days. `current` runs up to today, or up to
+/// yesterday while today doesn't qualify yet.
+#[derive(Debug, Clone, Copy, Default, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Streak {
+    pub current: u32,
+    pub longest: u32,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Streaks {
+    /// Days meeting the daily focus goal.
+    pub goal_met: Streak,
+    /// Days with at least one completed focus session.
+    pub active: Streak,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum StreakKind {
+    GoalMet,
+    Active,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct StreakMilestone {
+    pub kind: StreakKind,
+    pub days: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonthTotal {
+    pub month: String,
+    pub focus_completed: u32,
+    pub long_cycle_completed: u32,
+    pub focus_minutes: u32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionExportRequest {
+    pub from: String,
+    pub to: String,
+    #[serde(default)]
+    pub include_abandoned: bool,
+    pub output_path: String,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum HistoryExportFormat {
+    Csv,
+    Json,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryExportRequest {
+    pub format: HistoryExportFormat,
+    pub from: String,
+    pub to: String,
+    pub output_path: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryExport {
+    pub version: u32,
+    pub from: String,
+    pub to: String,
+    pub goals: GoalSettings,
+    pub daily: BTreeMap<String, DailyMetrics>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub monthly: BTreeMap<String, DailyMetrics>,
+    pub sessions: Vec<SessionRecord>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportColumnMapping {
+    pub date_column: String,
+    #[serde(default)]
+    pub date_format: Option<String>,
+    #[serde(default)]
+    pub count_column: Option<String>,
+    #[serde(default)]
+    pub long_cycle_column: Option<String>,
+    #[serde(default)]
+    pub minutes_column: Option<String>,
+    #[serde(default)]
+    pub filter_column: Option<String>,
+    #[serde(default)]
+    pub filter_value: Option<String>,
+    /// Rows sharing an ID count once; without one only timestamped rows dedupe.
+    #[serde(default)]
+    pub id_column: Option<String>,
+    #[serde(default)]
+    pub delimiter: Option<char>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FocusDistributionRequest {
+    pub from: String,
+    pub to: String,
+}
+
+/// When completed focus happened over a range of days.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FocusDistribution {
+    pub from: String,
+    pub to: String,
+    /// Focus minutes by weekday, Monday first, then by hour of day.
+    pub minutes: [[u32; 24]; 7],
+    pub total_minutes: u32,
+    pub best_hour: Option<u32>,
+    pub best_weekday: Option<DayOfWeek>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryImportRequest {
+    pub input_path: String,
+    pub mapping: ImportColumnMapping,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportPreview {
+    pub dry_run: bool,
+    pub rows_read: usize,
+    pub rows_imported: usize,
+    pub duplicate_rows: usize,
+    pub skipped_rows: usize,
+    pub out_of_retention_rows: usize,
+    pub days_added: usize,
+    pub days_updated: usize,
+    pub months_updated: usize,
+    pub overlap_days: Vec<String>,
+    pub overlap_months: Vec<String>,
+    pub focus_completed_added: u32,
+    pub long_cycle_completed_added: u32,
+    pub first_day: Option<String>,
+    pub last_day: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportSummary {
+    pub output_path: String,
+    pub exported: usize,
 }
 
 fn default_version() -> u32 {
@@ -1,10 +1,13 @@
 use super::model::{
-    AnalyticsState, DailyMetrics, GoalPair, GoalSettings, HeatmapDay, InsightsSnapshot,
-    PeriodSummaries, PeriodSummary, HEATMAP_DAYS, HISTORY_RETENTION_DAYS,
+    AnalyticsState, DailyMetrics, GoalPair, GoalSettings, HeatmapDay, InsightsSnapshot, MonthTotal,
+    PeriodSummaries, PeriodSummary, SessionRecord, HEATMAP_DAYS, TREND_MONTHS,
 };
-use chrono::{Datelike, Duration, Local, NaiveDate, Weekday};
+use super::streaks::compute_streaks;
+use super::zone::DayCredit;
+use chrono::{Datelike, Duration, Local, Months, NaiveDate, TimeZone, Weekday};
 
 const DAY_KEY_FORMAT: &str = "%Y-%m-%d";
+const MONTH_KEY_FORMAT: &str = "%Y-%m";
 
 pub fn build_insights_snapshot(
     state: &AnalyticsState,
@@ -21,45 +24,84 @@ pub fn build_insights_snapshot(
         heatmap: build_heatmap(state, today),
         summaries,
         goals: state.goals.clone(),
+        monthly_trend: build_monthly_trend(state, today),
+        streaks: compute_streaks(state, today),
+        unmoved_by_day_change: state.unmoved_by_day_change,
     }
 }
 
-pub fn current_local_date() -> NaiveDate {
-    Local::now().date_naive()
+pub fn local_date_for_ms(timestamp_ms: i64) -> Option<NaiveDate> {
+    Local
+        .timestamp_millis_opt(timestamp_ms)
+        .single()
+        .map(|datetime| datetime.date_naive())
 }
 
 pub fn day_key(date: NaiveDate) -> String {
     date.format(DAY_KEY_FORMAT).to_string()
 }
 
+pub fn month_key(date: NaiveDate) -> String {
+    date.format(MONTH_KEY_FORMAT).to_string()
+}
+
+/// Credits a completed focus session as `credit` describes; a long cycle it
+/// closes counts on the same day as the pomodoro.
 pub fn record_focus_completion(
     state: &mut AnalyticsState,
-    today: NaiveDate,
+    credit: &DayCredit,
     completed_long_cycle: bool,
+    retention_days: Option<u32>,
 ) {
-    let key = day_key(today);
-    let entry = state.daily.entry(key).or_default();
+    let entry = state.daily.entry(day_key(credit.completed_on)).or_default();
     entry.focus_completed = entry.focus_completed.saturating_add(1);
     if completed_long_cycle {
         entry.long_cycle_completed = entry.long_cycle_completed.saturating_add(1);
     }
 
-    prune_history(state, today, HISTORY_RETENTION_DAYS);
+    for (date, minutes) in &credit.minutes {
+        let entry = state.daily.entry(day_key(*date)).or_default();
+        entry.focus_minutes = entry.focus_minutes.saturating_add(*minutes);
+    }
+
+    prune_history(state, credit.completed_on, retention_days);
+}
+
+pub fn record_session(state: &mut AnalyticsState, session: SessionRecord) {
+    state.sessions.push(session);
 }
 
 pub fn update_goals(state: &mut AnalyticsState, goals: GoalSettings) {
     state.goals = goals.sanitized();
 }
 
-pub fn prune_history(state: &mut AnalyticsState, today: NaiveDate, retention_days: i64) {
-    if retention_days < 1 {
+/// Keeps `retention_days` days of detail, or everything when `None`. Older
+/// days are folded into monthly rollups instead of being dropped; their
+/// sessions are removed.
+pub fn prune_history(state: &mut AnalyticsState, today: NaiveDate, retention_days: Option<u32>) {
+    let Some(retention_days) = retention_days.filter(|days| *days > 0) else {
         return;
-    }
+    };
 
-    let cutoff = today - Duration::days(retention_days - 1);
-    state
+    let cutoff = today - Duration::days(i64::from(retention_days) - 1);
+    let boundary = state.day_boundary();
+    let expired: Vec<String> = state
         .daily
-        .retain(|key, _| parse_day_key(key).is_some_and(|date| date >= cutoff));
+        .keys()
+        .filter(|key| parse_day_key(key).is_none_or(|date| date < cutoff))
+        .cloned()
+        .collect();
+    for key in expired {
+        let metrics = state.daily.remove(&key).unwrap_or_default();
+        if let Some(date) = parse_day_key(&key) {
+            *state.monthly.entry(month_key(date)).or_default() += metrics;
+        }
+    }
+    state.sessions.retain(|session| {
+        boundary
+            .session_date(session)
+            .is_some_and(|date| date >= cutoff)
+    });
 }
 
 fn build_heatmap(state: &AnalyticsState, today: NaiveDate) -> Vec<HeatmapDay> {
@@ -74,6 +116,7 @@ fn build_heatmap(state: &AnalyticsState, today: NaiveDate) -> Vec<HeatmapDay> {
             date: key,
             focus_completed: metrics.focus_completed,
             long_cycle_completed: metrics.long_cycle_completed,
+            focus_minutes: metrics.focus_minutes,
         });
     }
 
@@ -120,12 +163,7 @@ fn range_metrics(state: &AnalyticsState, start: NaiveDate, end: NaiveDate) -> Da
     for (key, metrics) in &state.daily {
         if let Some(date) = parse_day_key(key) {
             if date >= start && date <= end {
-                total.focus_completed = total
-                    .focus_completed
-                    .saturating_add(metrics.focus_completed);
-                total.long_cycle_completed = total
-                    .long_cycle_completed
-                    .saturating_add(metrics.long_cycle_completed);
+                total += *metrics;
             }
         }
     }
@@ -133,21 +171,50 @@ fn range_metrics(state: &AnalyticsState, start: NaiveDate, end: NaiveDate) -> Da
     total
 }
 
+/// Month totals from rollups plus the days still kept in detail.
+fn build_monthly_trend(state: &AnalyticsState, today: NaiveDate) -> Vec<MonthTotal> {
+    let this_month = today
+        .with_day(1)
+        .expect("the first of a month is always valid");
+
+    (0..TREND_MONTHS)
+        .rev()
+        .filter_map(|offset| this_month.checked_sub_months(Months::new(offset)))
+        .map(|start| {
+            let end = start + Months::new(1) - Duration::days(1);
+            let key = month_key(start);
+            let mut metrics = state.monthly.get(&key).copied().unwrap_or_default();
+            metrics += range_metrics(state, start, end);
+
+            MonthTotal {
+                month: key,
+                focus_completed: metrics.focus_completed,
+                long_cycle_completed: metrics.long_cycle_completed,
+                focus_minutes: metrics.focus_minutes,
+            }
+        })
+        .collect()
+}
+
 fn build_period_summary(metrics: DailyMetrics, goals: GoalPair) -> PeriodSummary {
     let focus_target = goals.focus_target.max(1);
     let long_cycle_target = goals.long_cycle_target.max(1);
 
-    let focus_rate = (metrics.focus_completed as f64 / focus_target as f64).min(1.0);
+    let focus_progress = goals.focus_progress(&metrics);
+
+    let focus_rate = (focus_progress as f64 / focus_target as f64).min(1.0);
     let long_cycle_rate = (metrics.long_cycle_completed as f64 / long_cycle_target as f64).min(1.0);
 
     PeriodSummary {
         focus_completed: metrics.focus_completed,
         long_cycle_completed: metrics.long_cycle_completed,
+        focus_minutes: metrics.focus_minutes,
+        focus_unit: goals.focus_unit,
         focus_target,
         long_cycle_target,
         focus_rate,
         long_cycle_rate,
-        completed: metrics.focus_completed >= focus_target
+        completed: focus_progress >= focus_target
             && metrics.long_cycle_completed >= long_cycle_target,
     }
 }
@@ -171,34 +238,44 @@ fn days_from_week_start(day: Weekday, week_start: Weekday) -> i64 {
     (day_num - start_num).rem_euclid(7)
 }
 
-fn parse_day_key(value: &str) -> Option<NaiveDate> {
+pub fn parse_day_key(value: &str) -> Option<NaiveDate> {
     NaiveDate::parse_from_str(value, DAY_KEY_FORMAT).ok()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::analytics::model::{AnalyticsState, GoalPair, GoalSettings, HEATMAP_DAYS};
+    use crate::analytics::model::{AnalyticsState, GoalPair, GoalSettings, GoalUnit, HEATMAP_DAYS};
+    use crate::analytics::zone::DayZone;
     use std::collections::BTreeMap;
 
     fn sample_state() -> AnalyticsState {
         AnalyticsState {
             version: 1,
             daily: BTreeMap::new(),
+            monthly: BTreeMap::new(),
             goals: GoalSettings {
                 daily: GoalPair {
                     focus_target: 8,
                     long_cycle_target: 2,
+                    focus_unit: GoalUnit::Pomodoros,
                 },
                 weekly: GoalPair {
                     focus_target: 40,
                     long_cycle_target: 10,
+                    focus_unit: GoalUnit::Pomodoros,
                 },
                 monthly: GoalPair {
                     focus_target: 160,
                     long_cycle_target: 40,
+                    focus_unit: GoalUnit::Pomodoros,
                 },
+                rest_days: Vec::new(),
             },
+            sessions: Vec::new(),
+            day_zone: DayZone::Completion,
+            day_start_hour: 0,
+            unmoved_by_day_change: None,
         }
     }
 
@@ -211,6 +288,7 @@ mod tests {
             DailyMetrics {
                 focus_completed: 3,
                 long_cycle_completed: 1,
+                focus_minutes: 0,
             },
         );
 
@@ -231,6 +309,7 @@ mod tests {
             DailyMetrics {
                 focus_completed: 2,
                 long_cycle_completed: 0,
+                focus_minutes: 0,
             },
         );
         state.daily.insert(
@@ -238,6 +317,7 @@ mod tests {
             DailyMetrics {
                 focus_completed: 3,
                 long_cycle_completed: 1,
+                focus_minutes: 0,
             },
         );
 
@@ -257,6 +337,7 @@ mod tests {
             DailyMetrics {
                 focus_completed: 7,
                 long_cycle_completed: 2,
+                focus_minutes: 0,
             },
         );
         state.daily.insert(
@@ -264,6 +345,7 @@ mod tests {
             DailyMetrics {
                 focus_completed: 4,
                 long_cycle_completed: 1,
+                focus_minutes: 0,
             },
         );
 
@@ -274,7 +356,7 @@ mod tests {
     }
 
     #[test]
-    fn prune_history_keeps_only_recent_days() {
+    fn prune_history_rolls_old_days_into_months() {
         let today = NaiveDate::from_ymd_opt(2026, 2, 15).unwrap();
         let mut state = sample_state();
         state.daily.insert(
@@ -282,6 +364,7 @@ mod tests {
             DailyMetrics {
                 focus_completed: 1,
                 long_cycle_completed: 0,
+                focus_minutes: 0,
             },
         );
         state.daily.insert(
@@ -289,13 +372,77 @@ mod tests {
             DailyMetrics {
                 focus_completed: 1,
                 long_cycle_completed: 0,
+                focus_minutes: 0,
             },
         );
 
-        prune_history(&mut state, today, 400);
+        prune_history(&mut state, today, None);
+        assert_eq!(state.daily.len(), 2);
+
+        prune_history(&mut state, today, Some(400));
 
         assert_eq!(state.daily.len(), 1);
         assert!(state.daily.contains_key("2026-02-15"));
+        assert_eq!(state.monthly["2024-12"].focus_completed, 1);
+    }
+
+    #[test]
+    fn monthly_trend_combines_rollups_and_daily_detail() {
+        let today = NaiveDate::from_ymd_opt(2026, 2, 15).unwrap();
+        let mut state = sample_state();
+        let one_focus = DailyMetrics {
+            focus_completed: 1,
+            long_cycle_completed: 0,
+            focus_minutes: 25,
+        };
+        state.monthly.insert("2025-02".to_string(), one_focus);
+        state.daily.insert("2025-02-28".to_string(), one_focus);
+        state.daily.insert("2026-02-01".to_string(), one_focus);
+
+        let trend = build_insights_snapshot(&state, "en-US", today).monthly_trend;
+
+        assert_eq!(trend.len(), TREND_MONTHS as usize);
+        assert_eq!(trend.first().unwrap().month, "2024-03");
+        assert_eq!(trend.last().unwrap().month, "2026-02");
+        assert_eq!(trend.last().unwrap().focus_completed, 1);
+        let last_year = trend.iter().find(|month| month.month == "2025-02").unwrap();
+        assert_eq!(last_year.focus_completed, 2);
+        assert_eq!(last_year.focus_minutes, 50);
+    }
+
+    #[test]
+    fn minute_goals_are_measured_in_focus_minutes() {
+        let today = NaiveDate::from_ymd_opt(2026, 2, 15).unwrap();
+        let mut state = sample_state();
+        state.goals.daily = GoalPair {
+            focus_target: 120,
+            long_cycle_target: 1,
+            focus_unit: GoalUnit::Minutes,
+        };
+        state.daily.insert(
+            "2026-02-15".to_string(),
+            DailyMetrics {
+                focus_completed: 2,
+                long_cycle_completed: 1,
+                focus_minutes: 100,
+            },
+        );
+
+        let snapshot = build_insights_snapshot(&state, "en-US", today);
+        let daily = snapshot.summaries.daily;
+
+        assert_eq!(snapshot.heatmap.last().unwrap().focus_minutes, 100);
+        assert_eq!(daily.focus_unit, GoalUnit::Minutes);
+        assert_eq!(daily.focus_minutes, 100);
+        assert!((daily.focus_rate - 100.0 / 120.0).abs() < f64::EPSILON);
+        assert!(!daily.completed);
+
+        let fallback = GoalPair {
+            focus_target: 0,
+            ..state.goals.daily
+        }
+        .sanitized(GoalSettings::default().daily);
+        assert_eq!(fallback.focus_target, 8 * 25);
     }
 
     #[test]
@@ -303,10 +450,19 @@ mod tests {
         let today = NaiveDate::from_ymd_opt(2026, 2, 15).unwrap();
         let mut state = sample_state();
 
-        record_focus_completion(&mut state, today, true);
+        let credit = DayCredit {
+            completed_on: today,
+            minutes: vec![(today - Duration::days(1), 10), (today, 40)],
+        };
+
+        record_focus_completion(&mut state, &credit, true, None);
 
         let metrics = state.daily.get("2026-02-15").unwrap();
         assert_eq!(metrics.focus_completed, 1);
         assert_eq!(metrics.long_cycle_completed, 1);
+        assert_eq!(metrics.focus_minutes, 40);
+        let previous = state.daily.get("2026-02-14").unwrap();
+        assert_eq!(previous.focus_completed, 0);
+        assert_eq!(previous.focus_minutes, 10);
     }
 }
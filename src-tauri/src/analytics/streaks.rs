@@ -0,0 +1,179 @@
+use super::model::{
+    AnalyticsState, DailyMetrics, DayOfWeek, Streak, StreakKind, StreakMilestone, Streaks,
+};
+use super::service::{day_key, parse_day_key};
+use chrono::{Datelike, NaiveDate};
+
+/// Streak lengths, in days, that are announced when reached.
+pub const STREAK_MILESTONES: [u32; 8] = [3, 7, 14, 30, 60, 100, 200, 365];
+
+/// Streaks over the days kept in detail; rolled-up months are not counted.
+pub fn compute_streaks(state: &AnalyticsState, today: NaiveDate) -> Streaks {
+    let goal = state.goals.daily;
+    let focus_target = goal.focus_target.max(1);
+
+    Streaks {
+        goal_met: streak(state, today, |metrics| {
+            goal.focus_progress(metrics) >= focus_target
+        }),
+        active: streak(state, today, |metrics| metrics.focus_completed > 0),
+    }
+}
+
+/// The highest milestone each streak crossed between two computations for
+/// the same day.
+pub fn reached_milestones(before: &Streaks, after: &Streaks) -> Vec<StreakMilestone> {
+    [
+        (StreakKind::GoalMet, before.goal_met, after.goal_met),
+        (StreakKind::Active, before.active, after.active),
+    ]
+    .into_iter()
+    .filter_map(|(kind, before, after)| {
+        STREAK_MILESTONES
+            .into_iter()
+            .rev()
+            .find(|days| before.current < *days && *days <= after.current)
+            .map(|days| StreakMilestone { kind, days })
+    })
+    .collect()
+}
+
+/// A qualifying day extends the run, rest day or not. A rest day that
+/// doesn't qualify is skipped, and so is today until the day is over.
+fn streak(
+    state: &AnalyticsState,
+    today: NaiveDate,
+    qualifies: impl Fn(&DailyMetrics) -> bool,
+) -> Streak {
+    let Some(first) = state.daily.keys().find_map(|key| parse_day_key(key)) else {
+        return Streak::default();
+    };
+
+    let mut run = 0u32;
+    let mut longest = 0u32;
+    for date in first.iter_days().take_while(|date| *date <= today) {
+        if state.daily.get(&day_key(date)).is_some_and(&qualifies) {
+            run += 1;
+            longest = longest.max(run);
+        } else if date != today
+            && !state
+                .goals
+                .rest_days
+                .contains(&DayOfWeek::from(date.weekday()))
+        {
+            run = 0;
+        }
+    }
+
+    Streak {
+        current: run,
+        longest,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(value: &str) -> NaiveDate {
+        parse_day_key(value).unwrap()
+    }
+
+    fn state_with(days: &[(&str, u32)]) -> AnalyticsState {
+        let mut state = AnalyticsState::default();
+        state.goals.daily.focus_target = 4;
+        for (key, focus_completed) in days {
+            state.daily.insert(
+                key.to_string(),
+                DailyMetrics {
+                    focus_completed: *focus_completed,
+                    ..DailyMetrics::default()
+                },
+            );
+        }
+        state
+    }
+
+    #[test]
+    fn streaks_count_goal_days_and_active_days_separately() {
+        // 2026-03-02 is a Monday.
+        let state = state_with(&[
+            ("2026-03-02", 4),
+            ("2026-03-03", 5),
+            ("2026-03-04", 1),
+            ("2026-03-05", 4),
+            ("2026-03-06", 6),
+        ]);
+
+        let streaks = compute_streaks(&state, day("2026-03-06"));
+
+        assert_eq!(
+            streaks.goal_met,
+            Streak {
+                current: 2,
+                longest: 2
+            }
+        );
+        assert_eq!(
+            streaks.active,
+            Streak {
+                current: 5,
+                longest: 5
+            }
+        );
+    }
+
+    #[test]
+    fn rest_days_and_an_unfinished_today_keep_the_streak() {
+        // Friday and Monday met the goal; the weekend was rest.
+        let mut state = state_with(&[("2026-03-06", 4), ("2026-03-09", 4)]);
+
+        assert_eq!(
+            compute_streaks(&state, day("2026-03-10")).goal_met.current,
+            1
+        );
+
+        state.goals.rest_days = vec![DayOfWeek::Saturday, DayOfWeek::Sunday];
+        assert_eq!(
+            compute_streaks(&state, day("2026-03-10")).goal_met.current,
+            2
+        );
+        assert_eq!(
+            compute_streaks(&state, day("2026-03-11")).goal_met.current,
+            0
+        );
+    }
+
+    #[test]
+    fn reached_milestones_reports_the_highest_crossed() {
+        let before = Streaks {
+            goal_met: Streak {
+                current: 6,
+                longest: 9,
+            },
+            active: Streak {
+                current: 2,
+                longest: 2,
+            },
+        };
+        let after = Streaks {
+            goal_met: Streak {
+                current: 7,
+                longest: 9,
+            },
+            active: Streak {
+                current: 2,
+                longest: 2,
+            },
+        };
+
+        assert_eq!(
+            reached_milestones(&before, &after),
+            vec![StreakMilestone {
+                kind: StreakKind::GoalMet,
+                days: 7
+            }]
+        );
+        assert!(reached_milestones(&after, &after).is_empty());
+    }
+}
@@ -0,0 +1,504 @@
+use super::model::{AnalyticsState, DailyMetrics, SessionOutcome, SessionRecord, UnmovedDayCounts};
+use super::service::day_key;
+use crate::timer::engine::Phase;
+use chrono::{
+    DateTime, Datelike, Duration, DurationRound, FixedOffset, Local, NaiveDate, NaiveDateTime,
+    NaiveTime, Offset, TimeZone, Timelike, Weekday,
+};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// The zone whose calendar decides which day a completion counts towards.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", tag = "kind", content = "timeZone")]
+pub enum DayZone {
+    /// The UTC offset in effect where and when the session completed.
+    #[default]
+    Completion,
+    /// A fixed IANA zone, so travel and DST never move a session to
+    /// another day.
+    Home(String),
+}
+
+impl DayZone {
+    /// The wall-clock time of `instant_ms`. `utc_offset_minutes` is the
+    /// offset recorded at that instant; without one the current local rules
+    /// apply.
+    fn local_time(
+        &self,
+        instant_ms: i64,
+        utc_offset_minutes: Option<i32>,
+    ) -> Option<NaiveDateTime> {
+        let instant = DateTime::from_timestamp_millis(instant_ms)?;
+        match self {
+            Self::Completion => {
+                let offset_minutes =
+                    utc_offset_minutes.unwrap_or_else(|| local_offset_minutes(instant_ms));
+                let offset = FixedOffset::east_opt(offset_minutes * 60)?;
+                Some(instant.with_timezone(&offset).naive_local())
+            }
+            Self::Home(name) => {
+                let zone = parse_time_zone(name)?;
+                Some(instant.with_timezone(&zone).naive_local())
+            }
+        }
+    }
+
+    /// The inverse of `local_time`. A wall-clock time skipped by a DST change
+    /// maps to the first instant after the gap.
+    fn instant_of(&self, local: NaiveDateTime, utc_offset_minutes: Option<i32>) -> Option<i64> {
+        let earliest = |local: NaiveDateTime| -> Option<i64> {
+            match self {
+                Self::Completion => match utc_offset_minutes {
+                    Some(offset_minutes) => FixedOffset::east_opt(offset_minutes * 60)?
+                        .from_local_datetime(&local)
+                        .earliest()
+                        .map(|instant| instant.timestamp_millis()),
+                    None => Local
+                        .from_local_datetime(&local)
+                        .earliest()
+                        .map(|instant| instant.timestamp_millis()),
+                },
+                Self::Home(name) => parse_time_zone(name)?
+                    .from_local_datetime(&local)
+                    .earliest()
+                    .map(|instant| instant.timestamp_millis()),
+            }
+        };
+        earliest(local).or_else(|| earliest(local + Duration::hours(1)))
+    }
+}
+
+/// When one day ends and the next begins: the zone's wall clock reaching
+/// `start_hour`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DayBoundary {
+    pub zone: DayZone,
+    pub start_hour: u32,
+}
+
+impl DayBoundary {
+    /// The day `instant_ms` belongs to. Before `start_hour` that is the
+    /// previous calendar date.
+    pub fn date_of(&self, instant_ms: i64, utc_offset_minutes: Option<i32>) -> Option<NaiveDate> {
+        let local = self.zone.local_time(instant_ms, utc_offset_minutes)?;
+        Some((local - Duration::hours(i64::from(self.start_hour))).date())
+    }
+
+    /// The day a session counts towards: the day it ended on.
+    pub fn session_date(&self, session: &SessionRecord) -> Option<NaiveDate> {
+        self.date_of(session.ended_at_ms, session.utc_offset_minutes)
+    }
+
+    /// The day `now_ms` belongs to.
+    pub fn today(&self, now_ms: i64) -> NaiveDate {
+        self.date_of(now_ms, None).unwrap_or_default()
+    }
+
+    /// Where a completed session is counted; see `DayCredit`.
+    pub fn credit(&self, session: &SessionRecord, duration_ms: i64) -> Option<DayCredit> {
+        let completed_on = self.session_date(session)?;
+        let spans = self.split_span(
+            session.started_at_ms,
+            session.ended_at_ms,
+            session.utc_offset_minutes,
+        );
+        let minutes = if spans.is_empty() {
+            vec![(completed_on, whole_minutes(duration_ms))]
+        } else {
+            apportion_minutes(&spans, whole_minutes(duration_ms))
+        };
+
+        Some(DayCredit {
+            completed_on,
+            minutes,
+        })
+    }
+
+    /// A completed session's focus minutes split over the wall-clock hours
+    /// it ran in, keyed by weekday and hour of day. Unlike days, hours
+    /// ignore `start_hour`.
+    pub fn hour_credit(
+        &self,
+        session: &SessionRecord,
+        duration_ms: i64,
+    ) -> Vec<((Weekday, u32), u32)> {
+        let offset = session.utc_offset_minutes;
+        let mut spans = Vec::new();
+        let mut cursor = session.started_at_ms;
+
+        while cursor < session.ended_at_ms {
+            let Some(local) = self.zone.local_time(cursor, offset) else {
+                break;
+            };
+            let next_hour = local
+                .duration_trunc(Duration::hours(1))
+                .ok()
+                .and_then(|hour| self.zone.instant_of(hour + Duration::hours(1), offset))
+                .filter(|next_hour| *next_hour > cursor)
+                .map_or(session.ended_at_ms, |next_hour| {
+                    next_hour.min(session.ended_at_ms)
+                });
+            spans.push(((local.weekday(), local.hour()), next_hour - cursor));
+            cursor = next_hour;
+        }
+
+        if spans.is_empty() {
+            return self
+                .zone
+                .local_time(session.ended_at_ms, offset)
+                .map(|local| vec![((local.weekday(), local.hour()), whole_minutes(duration_ms))])
+                .unwrap_or_default();
+        }
+        apportion_minutes(&spans, whole_minutes(duration_ms))
+    }
+
+    /// The instant `date` starts at.
+    fn start_of(&self, date: NaiveDate, utc_offset_minutes: Option<i32>) -> Option<i64> {
+        let start_time = NaiveTime::from_hms_opt(self.start_hour, 0, 0)?;
+        self.zone
+            .instant_of(date.and_time(start_time), utc_offset_minutes)
+    }
+
+    /// Cuts `[started_at_ms, ended_at_ms)` at day starts, giving the time in
+    /// milliseconds that falls on each day.
+    fn split_span(
+        &self,
+        started_at_ms: i64,
+        ended_at_ms: i64,
+        utc_offset_minutes: Option<i32>,
+    ) -> Vec<(NaiveDate, i64)> {
+        let mut spans = Vec::new();
+        let mut cursor = started_at_ms;
+
+        while cursor < ended_at_ms {
+            let Some(date) = self.date_of(cursor, utc_offset_minutes) else {
+                break;
+            };
+            let next_start = date
+                .succ_opt()
+                .and_then(|next| self.start_of(next, utc_offset_minutes))
+                .filter(|next_start| *next_start > cursor)
+                .map_or(ended_at_ms, |next_start| next_start.min(ended_at_ms));
+            spans.push((date, next_start - cursor));
+            cursor = next_start;
+        }
+
+        spans
+    }
+}
+
+/// Where a completed focus session is counted. The pomodoro itself counts
+/// towards the day the session ended on, the same day its completion is
+/// attributed to everywhere else. Its focus minutes are split across every
+/// day it spanned, in proportion to the time it spent on each.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DayCredit {
+    pub completed_on: NaiveDate,
+    pub minutes: Vec<(NaiveDate, u32)>,
+}
+
+fn whole_minutes(duration_ms: i64) -> u32 {
+    u32::try_from((duration_ms.max(0) + 30_000) / 60_000).unwrap_or(u32::MAX)
+}
+
+/// Shares `total` out in proportion to `spans`, handing leftover minutes to
+/// the largest remainders so the shares always add up to `total`.
+fn apportion_minutes<K: Copy>(spans: &[(K, i64)], total: u32) -> Vec<(K, u32)> {
+    let span_total: i64 = spans.iter().map(|(_, span)| span).sum();
+    if span_total <= 0 {
+        return Vec::new();
+    }
+
+    let exact: Vec<(K, i64, i64)> = spans
+        .iter()
+        .map(|(key, span)| {
+            let scaled = span * i64::from(total);
+            (*key, scaled / span_total, scaled % span_total)
+        })
+        .collect();
+    let assigned: i64 = exact.iter().map(|(_, share, _)| share).sum();
+    let mut by_remainder: Vec<usize> = (0..exact.len()).collect();
+    by_remainder.sort_by_key(|index| std::cmp::Reverse(exact[*index].2));
+    let leftover = usize::try_from(i64::from(total) - assigned).unwrap_or(0);
+
+    let mut shares: Vec<(K, u32)> = exact
+        .iter()
+        .map(|(key, share, _)| (*key, *share as u32))
+        .collect();
+    for index in by_remainder.into_iter().take(leftover) {
+        shares[index].1 += 1;
+    }
+    shares.retain(|(_, minutes)| *minutes > 0);
+    shares
+}
+
+pub fn parse_time_zone(name: &str) -> Option<Tz> {
+    name.trim().parse().ok()
+}
+
+/// The IANA name of the system zone, when the platform reports one we know.
+pub fn system_time_zone() -> Option<String> {
+    iana_time_zone::get_timezone()
+        .ok()
+        .filter(|name| parse_time_zone(name).is_some())
+}
+
+/// The local UTC offset at `instant_ms`, in minutes east of UTC.
+pub fn local_offset_minutes(instant_ms: i64) -> i32 {
+    Local
+        .timestamp_millis_opt(instant_ms)
+        .single()
+        .map_or(0, |datetime| datetime.offset().fix().local_minus_utc() / 60)
+}
+
+/// Moves the pomodoros, long cycles and focus minutes of completed focus
+/// sessions from the days they were credited to under the state's current
+/// boundary to their days under `boundary`. Counts without a session stay
+/// where they are and are noted in `unmoved_by_day_change`.
+pub fn reattribute_days(state: &mut AnalyticsState, boundary: &DayBoundary) {
+    let current = state.day_boundary();
+    if current == *boundary {
+        return;
+    }
+
+    let credits: Vec<(DayCredit, DayCredit, bool)> = state
+        .sessions
+        .iter()
+        .filter(|session| {
+            session.phase == Phase::Focus && session.outcome == SessionOutcome::Completed
+        })
+        .filter_map(|session| {
+            let duration_ms = session.duration_ms.unwrap_or_default();
+            let from = current.credit(session, duration_ms)?;
+            let to = boundary.credit(session, duration_ms)?;
+            Some((from, to, session.closed_long_cycle))
+        })
+        .collect();
+
+    let mut backed: BTreeMap<String, DailyMetrics> = BTreeMap::new();
+    for (from, _, closed_long_cycle) in &credits {
+        let metrics = backed.entry(day_key(from.completed_on)).or_default();
+        metrics.focus_completed += 1;
+        metrics.long_cycle_completed += u32::from(*closed_long_cycle);
+    }
+    let mut unmoved = UnmovedDayCounts::default();
+    for (key, metrics) in &state.daily {
+        let backed = backed.get(key).copied().unwrap_or_default();
+        unmoved.focus_completed += metrics
+            .focus_completed
+            .saturating_sub(backed.focus_completed);
+        unmoved.long_cycle_completed += metrics
+            .long_cycle_completed
+            .saturating_sub(backed.long_cycle_completed);
+    }
+    state.unmoved_by_day_change = (unmoved != UnmovedDayCounts::default()).then_some(unmoved);
+
+    for (from, to, closed_long_cycle) in credits.into_iter().filter(|(from, to, _)| from != to) {
+        let from_key = day_key(from.completed_on);
+        let Some(held) = state.daily.get(&from_key).copied() else {
+            continue;
+        };
+        if held.focus_completed == 0 {
+            continue;
+        }
+        let moves_long_cycle = closed_long_cycle && held.long_cycle_completed > 0;
+
+        adjust_day(state, &from_key, |metrics| {
+            metrics.focus_completed -= 1;
+            metrics.long_cycle_completed -= u32::from(moves_long_cycle);
+        });
+        for (date, minutes) in &from.minutes {
+            adjust_day(state, &day_key(*date), |metrics| {
+                metrics.focus_minutes = metrics.focus_minutes.saturating_sub(*minutes);
+            });
+        }
+
+        adjust_day(state, &day_key(to.completed_on), |metrics| {
+            metrics.focus_completed = metrics.focus_completed.saturating_add(1);
+            metrics.long_cycle_completed = metrics
+                .long_cycle_completed
+                .saturating_add(u32::from(moves_long_cycle));
+        });
+        for (date, minutes) in &to.minutes {
+            adjust_day(state, &day_key(*date), |metrics| {
+                metrics.focus_minutes = metrics.focus_minutes.saturating_add(*minutes);
+            });
+        }
+    }
+
+    state.day_zone = boundary.zone.clone();
+    state.day_start_hour = boundary.start_hour;
+}
+
+/// Applies `change` to the day's metrics and drops the day once empty.
+fn adjust_day(state: &mut AnalyticsState, key: &str, change: impl FnOnce(&mut DailyMetrics)) {
+    let metrics = state.daily.entry(key.to_string()).or_default();
+    change(metrics);
+    if metrics.is_empty() {
+        state.daily.remove(key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2026-03-01T23:30:00Z
+    const LATE_UTC_MS: i64 = 1_772_407_800_000;
+
+    fn completed_focus(ended_at_ms: i64, utc_offset_minutes: i32) -> SessionRecord {
+        SessionRecord {
+            phase: Phase::Focus,
+            started_at_ms: ended_at_ms - 25 * 60_000,
+            ended_at_ms,
+            outcome: SessionOutcome::Completed,
+            profile_id: None,
+            clock_jumps: Vec::new(),
+            utc_offset_minutes: Some(utc_offset_minutes),
+            duration_ms: Some(25 * 60_000),
+            task: None,
+            project: None,
+            closed_long_cycle: false,
+        }
+    }
+
+    fn boundary(zone: DayZone, start_hour: u32) -> DayBoundary {
+        DayBoundary { zone, start_hour }
+    }
+
+    fn los_angeles() -> DayZone {
+        DayZone::Home("America/Los_Angeles".to_string())
+    }
+
+    #[test]
+    fn completion_zone_uses_recorded_offset_and_home_zone_ignores_it() {
+        let day = |y, m, d| NaiveDate::from_ymd_opt(y, m, d);
+        let completion = DayBoundary::default();
+
+        assert_eq!(
+            completion.date_of(LATE_UTC_MS, Some(-8 * 60)),
+            day(2026, 3, 1)
+        );
+        assert_eq!(completion.date_of(LATE_UTC_MS, Some(60)), day(2026, 3, 2));
+        assert_eq!(
+            boundary(los_angeles(), 0).date_of(LATE_UTC_MS, Some(60)),
+            day(2026, 3, 1)
+        );
+        assert_eq!(
+            boundary(DayZone::Home("Mars/Olympus".to_string()), 0).date_of(0, None),
+            None
+        );
+    }
+
+    #[test]
+    fn day_start_hour_keeps_early_hours_on_the_previous_day() {
+        let day = |y, m, d| NaiveDate::from_ymd_opt(y, m, d);
+        let night_owl = boundary(DayZone::Completion, 4);
+
+        // 00:30 and 03:59 local still belong to March 1st; 04:00 starts the 2nd.
+        assert_eq!(night_owl.date_of(LATE_UTC_MS, Some(60)), day(2026, 3, 1));
+        assert_eq!(
+            night_owl.date_of(LATE_UTC_MS + 209 * 60_000, Some(60)),
+            day(2026, 3, 1)
+        );
+        assert_eq!(
+            night_owl.date_of(LATE_UTC_MS + 210 * 60_000, Some(60)),
+            day(2026, 3, 2)
+        );
+    }
+
+    #[test]
+    fn credit_splits_minutes_across_midnight_and_counts_the_end_day() {
+        let day = |d| NaiveDate::from_ymd_opt(2026, 3, d).unwrap();
+        // 23:40 to 00:30 local, a 50-minute focus.
+        let mut session = completed_focus(LATE_UTC_MS, 60);
+        session.started_at_ms = LATE_UTC_MS - 50 * 60_000;
+
+        let credit = DayBoundary::default()
+            .credit(&session, 50 * 60_000)
+            .unwrap();
+
+        assert_eq!(credit.completed_on, day(2));
+        assert_eq!(credit.minutes, vec![(day(1), 20), (day(2), 30)]);
+
+        // Paused sessions span more wall time than they credit.
+        session.started_at_ms = LATE_UTC_MS - 100 * 60_000;
+        let credit = DayBoundary::default()
+            .credit(&session, 25 * 60_000)
+            .unwrap();
+        assert_eq!(credit.minutes, vec![(day(1), 18), (day(2), 7)]);
+    }
+
+    #[test]
+    fn hour_credit_splits_minutes_across_wall_clock_hours() {
+        // 23:40 Sunday to 00:30 Monday local.
+        let mut session = completed_focus(LATE_UTC_MS, 60);
+        session.started_at_ms = LATE_UTC_MS - 50 * 60_000;
+
+        assert_eq!(
+            boundary(DayZone::Completion, 4).hour_credit(&session, 50 * 60_000),
+            vec![((Weekday::Sun, 23), 20), ((Weekday::Mon, 0), 30)]
+        );
+    }
+
+    #[test]
+    fn reattribute_days_moves_session_counts_and_keeps_the_rest() {
+        let mut state = AnalyticsState::default();
+        state.sessions.push(completed_focus(LATE_UTC_MS, 60));
+        state.daily.insert(
+            "2026-03-02".to_string(),
+            DailyMetrics {
+                focus_completed: 2,
+                long_cycle_completed: 0,
+                focus_minutes: 0,
+            },
+        );
+
+        let home = boundary(los_angeles(), 0);
+        reattribute_days(&mut state, &home);
+
+        assert_eq!(state.daily["2026-03-01"].focus_completed, 1);
+        assert_eq!(state.daily["2026-03-02"].focus_completed, 1);
+        assert_eq!(state.day_boundary(), home);
+
+        reattribute_days(&mut state, &DayBoundary::default());
+        assert_eq!(state.daily["2026-03-02"].focus_completed, 2);
+        assert!(!state.daily.contains_key("2026-03-01"));
+    }
+
+    #[test]
+    fn reattribute_days_moves_long_cycles_with_their_session() {
+        let mut state = AnalyticsState::default();
+        let mut closing = completed_focus(LATE_UTC_MS, 60);
+        closing.closed_long_cycle = true;
+        state.sessions.push(closing);
+        state.daily.insert(
+            "2026-03-02".to_string(),
+            DailyMetrics {
+                focus_completed: 3,
+                long_cycle_completed: 2,
+                focus_minutes: 0,
+            },
+        );
+
+        reattribute_days(&mut state, &boundary(los_angeles(), 0));
+
+        assert_eq!(state.daily["2026-03-01"].focus_completed, 1);
+        assert_eq!(state.daily["2026-03-01"].long_cycle_completed, 1);
+        assert_eq!(state.daily["2026-03-02"].focus_completed, 2);
+        assert_eq!(state.daily["2026-03-02"].long_cycle_completed, 1);
+        assert_eq!(
+            state.unmoved_by_day_change,
+            Some(UnmovedDayCounts {
+                focus_completed: 2,
+                long_cycle_completed: 1,
+            })
+        );
+
+        reattribute_days(&mut state, &DayBoundary::default());
+        assert_eq!(state.daily["2026-03-02"].long_cycle_completed, 2);
+        assert!(!state.daily.contains_key("2026-03-01"));
+    }
+}
@@ -1,37 +1,53 @@
-use crate::analytics::model::{GoalSettings, InsightsSnapshot};
-use crate::timer::engine::{Settings, TimerEngine, TimerSnapshot};
-use tauri::State;
+use crate::analytics::model::{
+    ExportSummary, FocusDistribution, FocusDistributionRequest, GoalSettings, HistoryExportRequest,
+    HistoryImportRequest, ImportPreview, InsightsSnapshot, SessionExportRequest,
+};
+use crate::error::CommandError;
+use crate::storage::recovery::StorageIssue;
+use crate::timer::engine::{SessionLabels, Settings, TimerEngine, TimerSnapshot};
+use crate::timer::profile::ProfileState;
+use tauri::{AppHandle, State};
 
 #[tauri::command]
-pub fn timer_get_state(engine: State<'_, TimerEngine>) -> Result<TimerSnapshot, String> {
+pub fn timer_get_state(engine: State<'_, TimerEngine>) -> Result<TimerSnapshot, CommandError> {
     Ok(engine.get_state())
 }
 
 #[tauri::command]
-pub fn timer_start(engine: State<'_, TimerEngine>) -> Result<TimerSnapshot, String> {
-    engine.start()
+pub fn timer_start(
+    task: Option<String>,
+    project: Option<String>,
+    engine: State<'_, TimerEngine>,
+) -> Result<TimerSnapshot, CommandError> {
+    engine
+        .start_with_labels(SessionLabels { task, project })
+        .map_err(|error| engine.localize(error))
 }
 
 #[tauri::command]
-pub fn timer_resume(engine: State<'_, TimerEngine>) -> Result<TimerSnapshot, String> {
-    engine.resume()
+pub fn timer_resume(engine: State<'_, TimerEngine>) -> Result<TimerSnapshot, CommandError> {
+    engine.resume().map_err(|error| engine.localize(error))
 }
 
 #[tauri::command]
-pub fn timer_reset(engine: State<'_, TimerEngine>) -> Result<TimerSnapshot, String> {
-    engine.reset()
+pub fn timer_reset(engine: State<'_, TimerEngine>) -> Result<TimerSnapshot, CommandError> {
+    engine.reset().map_err(|error| engine.localize(error))
 }
 
 #[tauri::command]
 pub fn timer_update_settings(
     settings: Settings,
     engine: State<'_, TimerEngine>,
-) -> Result<TimerSnapshot, String> {
-    engine.update_settings(settings)
+) -> Result<TimerSnapshot, CommandError> {
+    engine
+        .update_settings(settings)
+        .map_err(|error| engine.localize(error))
 }
 
 #[tauri::command]
-pub fn timer_get_insights(engine: State<'_, TimerEngine>) -> Result<InsightsSnapshot, String> {
+pub fn timer_get_insights(
+    engine: State<'_, TimerEngine>,
+) -> Result<InsightsSnapshot, CommandError> {
     Ok(engine.get_insights())
 }
 
@@ -39,6 +55,122 @@ pub fn timer_get_insights(engine: State<'_, TimerEngine>) -> Result<InsightsSnap
 pub fn timer_update_goals(
     goals: GoalSettings,
     engine: State<'_, TimerEngine>,
-) -> Result<InsightsSnapshot, String> {
-    engine.update_goals(goals)
+) -> Result<InsightsSnapshot, CommandError> {
+    engine
+        .update_goals(goals)
+        .map_err(|error| engine.localize(error))
+}
+
+#[tauri::command]
+pub fn timer_get_focus_distribution(
+    request: FocusDistributionRequest,
+    engine: State<'_, TimerEngine>,
+) -> Result<FocusDistribution, CommandError> {
+    engine
+        .get_focus_distribution(request)
+        .map_err(|error| engine.localize(error))
+}
+
+#[tauri::command]
+pub fn timer_export_sessions_ics(
+    request: SessionExportRequest,
+    engine: State<'_, TimerEngine>,
+) -> Result<ExportSummary, CommandError> {
+    engine
+        .export_sessions_ics(request)
+        .map_err(|error| engine.localize(error))
+}
+
+#[tauri::command]
+pub fn timer_export_history(
+    request: HistoryExportRequest,
+    engine: State<'_, TimerEngine>,
+) -> Result<ExportSummary, CommandError> {
+    engine
+        .export_history(request)
+        .map_err(|error| engine.localize(error))
+}
+
+#[tauri::command]
+pub fn timer_import_history(
+    request: HistoryImportRequest,
+    app: AppHandle,
+    engine: State<'_, TimerEngine>,
+) -> Result<ImportPreview, CommandError> {
+    engine
+        .import_history(&app, request)
+        .map_err(|error| engine.localize(error))
+}
+
+#[tauri::command]
+pub fn timer_create_backup(
+    output_path: String,
+    engine: State<'_, TimerEngine>,
+) -> Result<(), CommandError> {
+    engine
+        .create_backup(output_path)
+        .map_err(|error| engine.localize(error))
+}
+
+#[tauri::command]
+pub fn timer_restore_backup(
+    input_path: String,
+    engine: State<'_, TimerEngine>,
+) -> Result<TimerSnapshot, CommandError> {
+    engine
+        .restore_backup(input_path)
+        .map_err(|error| engine.localize(error))
+}
+
+#[tauri::command]
+pub fn timer_get_storage_issues(
+    engine: State<'_, TimerEngine>,
+) -> Result<Vec<StorageIssue>, CommandError> {
+    Ok(engine.storage_issues())
+}
+
+#[tauri::command]
+pub fn timer_get_profiles(engine: State<'_, TimerEngine>) -> Result<ProfileState, CommandError> {
+    Ok(engine.get_profiles())
+}
+
+#[tauri::command]
+pub fn timer_create_profile(
+    name: String,
+    engine: State<'_, TimerEngine>,
+) -> Result<ProfileState, CommandError> {
+    engine
+        .create_profile(name)
+        .map_err(|error| engine.localize(error))
+}
+
+#[tauri::command]
+pub fn timer_rename_profile(
+    id: String,
+    name: String,
+    engine: State<'_, TimerEngine>,
+) -> Result<ProfileState, CommandError> {
+    engine
+        .rename_profile(id, name)
+        .map_err(|error| engine.localize(error))
+}
+
+#[tauri::command]
+pub fn timer_delete_profile(
+    id: String,
+    engine: State<'_, TimerEngine>,
+) -> Result<ProfileState, CommandError> {
+    engine
+        .delete_profile(id)
+        .map_err(|error| engine.localize(error))
+}
+
+#[tauri::command]
+pub fn timer_switch_profile(
+    id: String,
+    engine: State<'_, TimerEngine>,
+) -> Result<TimerSnapshot, CommandError> {
+    engine
+        .switch_profile(id)
+        .map_err(|error| engine.localize(error))
 }
@@ -0,0 +1,257 @@
+use crate::timer::engine::{normalize_locale, DEFAULT_LOCALE, ZH_CN_LOCALE};
+use crate::timer::validation::FieldError;
+use serde::Serialize;
+use std::error::Error;
+use std::fmt;
+
+/// Stable codes the frontend can branch on.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ErrorCode {
+    StorageIo,
+    Validation,
+    InvalidStateTransition,
+    NotFound,
+}
+
+/// What went wrong, in terms the user can act on. Each failure has a fixed
+/// code and a localized message.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Failure {
+    SaveSettings,
+    SaveRuntimeState,
+    SaveAnalytics,
+    SaveProfiles,
+    SwitchStorageBackend,
+    CreateBackup,
+    ReadBackup,
+    RestoreBackup,
+    WriteExport,
+    ReadImport,
+    ReadStateFile,
+    InvalidSettings,
+    InvalidImport,
+    InvalidBackup,
+    InvalidDateRange,
+    ProfileNameEmpty,
+    ProfileNameTooLong,
+    ProfileNameTaken,
+    DeleteActiveProfile,
+    ProfileNotFound,
+}
+
+impl Failure {
+    pub fn code(self) -> ErrorCode {
+        match self {
+            Self::SaveSettings => ErrorCode::StorageIo,
+            Self::SaveRuntimeState => ErrorCode::StorageIo,
+            Self::SaveAnalytics => ErrorCode::StorageIo,
+            Self::SaveProfiles => ErrorCode::StorageIo,
+            Self::SwitchStorageBackend => ErrorCode::StorageIo,
+            Self::CreateBackup => ErrorCode::StorageIo,
+            Self::ReadBackup => ErrorCode::StorageIo,
+            Self::RestoreBackup => ErrorCode::StorageIo,
+            Self::WriteExport => ErrorCode::StorageIo,
+            Self::ReadImport => ErrorCode::StorageIo,
+            Self::ReadStateFile => ErrorCode::StorageIo,
+            Self::InvalidSettings => ErrorCode::Validation,
+            Self::InvalidImport => ErrorCode::Validation,
+            Self::InvalidBackup => ErrorCode::Validation,
+            Self::InvalidDateRange => ErrorCode::Validation,
+            Self::ProfileNameEmpty => ErrorCode::Validation,
+            Self::ProfileNameTooLong => ErrorCode::Validation,
+            Self::ProfileNameTaken => ErrorCode::Validation,
+            Self::DeleteActiveProfile => ErrorCode::InvalidStateTransition,
+            Self::ProfileNotFound => ErrorCode::NotFound,
+        }
+    }
+
+    pub fn message(self, locale: &str) -> &'static str {
+        if normalize_locale(locale) == ZH_CN_LOCALE {
+            match self {
+                Self::SaveSettings => "\u{65E0}\u{6CD5}\u{4FDD}\u{5B58}\u{8BBE}\u{7F6E}\u{3002}",
+                Self::SaveRuntimeState => "\u{65E0}\u{6CD5}\u{4FDD}\u{5B58}\u{8BA1}\u{65F6}\u{5668}\u{72B6}\u{6001}\u{3002}",
+                Self::SaveAnalytics => "\u{65E0}\u{6CD5}\u{4FDD}\u{5B58}\u{7EDF}\u{8BA1}\u{6570}\u{636E}\u{3002}",
+                Self::SaveProfiles => "\u{65E0}\u{6CD5}\u{4FDD}\u{5B58}\u{8BBE}\u{7F6E}\u{65B9}\u{6848}\u{3002}",
+                Self::SwitchStorageBackend => "\u{65E0}\u{6CD5}\u{5207}\u{6362}\u{5B58}\u{50A8}\u{65B9}\u{5F0F}\u{3002}",
+                Self::CreateBackup => "\u{65E0}\u{6CD5}\u{521B}\u{5EFA}\u{5907}\u{4EFD}\u{3002}",
+                Self::ReadBackup => "\u{65E0}\u{6CD5}\u{8BFB}\u{53D6}\u{5907}\u{4EFD}\u{6587}\u{4EF6}\u{3002}",
+                Self::RestoreBackup => "\u{65E0}\u{6CD5}\u{6062}\u{590D}\u{5907}\u{4EFD}\u{3002}",
+                Self::WriteExport => "\u{65E0}\u{6CD5}\u{5199}\u{5165}\u{5BFC}\u{51FA}\u{6587}\u{4EF6}\u{3002}",
+                Self::ReadImport => "\u{65E0}\u{6CD5}\u{8BFB}\u{53D6}\u{5BFC}\u{5165}\u{6587}\u{4EF6}\u{3002}",
+                Self::ReadStateFile => "\u{65E0}\u{6CD5}\u{8BFB}\u{53D6}\u{4FEE}\u{6539}\u{540E}\u{7684}\u{6587}\u{4EF6}\u{3002}",
+                Self::InvalidSettings => "\u{90E8}\u{5206}\u{8BBE}\u{7F6E}\u{8D85}\u{51FA}\u{5141}\u{8BB8}\u{8303}\u{56F4}\u{3002}",
+                Self::InvalidImport => "\u{5BFC}\u{5165}\u{6587}\u{4EF6}\u{65E0}\u{6548}\u{3002}",
+                Self::InvalidBackup => "\u{8BE5}\u{6587}\u{4EF6}\u{4E0D}\u{662F}\u{6709}\u{6548}\u{7684}\u{5907}\u{4EFD}\u{3002}",
+                Self::InvalidDateRange => "\u{65E5}\u{671F}\u{8303}\u{56F4}\u{65E0}\u{6548}\u{3002}",
+                Self::ProfileNameEmpty => "\u{65B9}\u{6848}\u{540D}\u{79F0}\u{4E0D}\u{80FD}\u{4E3A}\u{7A7A}\u{3002}",
+                Self::ProfileNameTooLong => "\u{65B9}\u{6848}\u{540D}\u{79F0}\u{6700}\u{591A} 40 \u{4E2A}\u{5B57}\u{7B26}\u{3002}",
+                Self::ProfileNameTaken => "\u{5DF2}\u{5B58}\u{5728}\u{540C}\u{540D}\u{65B9}\u{6848}\u{3002}",
+                Self::DeleteActiveProfile => "\u{65E0}\u{6CD5}\u{5220}\u{9664}\u{5F53}\u{524D}\u{4F7F}\u{7528}\u{7684}\u{65B9}\u{6848}\u{3002}",
+                Self::ProfileNotFound => "\u{8BE5}\u{65B9}\u{6848}\u{4E0D}\u{5B58}\u{5728}\u{3002}",
+            }
+        } else {
+            match self {
+                Self::SaveSettings => "Could not save settings.",
+                Self::SaveRuntimeState => "Could not save the timer state.",
+                Self::SaveAnalytics => "Could not save statistics.",
+                Self::SaveProfiles => "Could not save settings profiles.",
+                Self::SwitchStorageBackend => "Could not switch the storage backend.",
+                Self::CreateBackup => "Could not create the backup.",
+                Self::ReadBackup => "Could not read the backup file.",
+                Self::RestoreBackup => "Could not restore the backup.",
+                Self::WriteExport => "Could not write the export file.",
+                Self::ReadImport => "Could not read the import file.",
+                Self::ReadStateFile => "Could not read the edited file.",
+                Self::InvalidSettings => "Some settings are out of range.",
+                Self::InvalidImport => "The import file is not valid.",
+                Self::InvalidBackup => "The file is not a valid backup.",
+                Self::InvalidDateRange => "The date range is not valid.",
+                Self::ProfileNameEmpty => "Profile names cannot be empty.",
+                Self::ProfileNameTooLong => "Profile names can have at most 40 characters.",
+                Self::ProfileNameTaken => "A profile with this name already exists.",
+                Self::DeleteActiveProfile => "The active profile cannot be deleted.",
+                Self::ProfileNotFound => "The profile no longer exists.",
+            }
+        }
+    }
+}
+
+/// Error returned by engine operations. Localized into a `CommandError` at
+/// the command boundary, where the current locale is known.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppError {
+    pub failure: Failure,
+    /// Underlying causes, outermost first. Not localized.
+    pub causes: Vec<String>,
+    pub fields: Vec<FieldError>,
+}
+
+impl AppError {
+    pub fn new(failure: Failure) -> Self {
+        Self {
+            failure,
+            causes: Vec::new(),
+            fields: Vec::new(),
+        }
+    }
+
+    /// Records `error` and every source behind it as the cause chain.
+    pub fn from_error(failure: Failure, error: &(dyn Error + 'static)) -> Self {
+        let mut causes = vec![error.to_string()];
+        let mut source = error.source();
+        while let Some(cause) = source {
+            causes.push(cause.to_string());
+            source = cause.source();
+        }
+
+        Self {
+            causes,
+            ..Self::new(failure)
+        }
+    }
+
+    pub fn caused_by(mut self, cause: impl fmt::Display) -> Self {
+        self.causes.push(cause.to_string());
+        self
+    }
+
+    pub fn with_fields(mut self, fields: Vec<FieldError>) -> Self {
+        self.fields = fields;
+        self
+    }
+
+    pub fn localize(self, locale: &str) -> CommandError {
+        CommandError {
+            code: self.failure.code(),
+            failure: self.failure,
+            message: self.failure.message(locale).to_string(),
+            causes: self.causes,
+            fields: self.fields,
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.failure.message(DEFAULT_LOCALE))?;
+        for cause in &self.causes {
+            write!(formatter, " {cause}")?;
+        }
+        for field in &self.fields {
+            write!(formatter, " {}", field.message)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for AppError {}
+
+/// What every command returns on failure.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandError {
+    pub code: ErrorCode,
+    pub failure: Failure,
+    pub message: String,
+    pub causes: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<FieldError>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+
+    #[derive(Debug)]
+    struct WriteFailed(io::Error);
+
+    impl fmt::Display for WriteFailed {
+        fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("failed to write settings.tmp")
+        }
+    }
+
+    impl Error for WriteFailed {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    #[test]
+    fn from_error_keeps_the_cause_chain() {
+        let error = WriteFailed(io::Error::new(
+            io::ErrorKind::StorageFull,
+            "no space left on device",
+        ));
+
+        let error = AppError::from_error(Failure::SaveSettings, &error);
+
+        assert_eq!(error.failure.code(), ErrorCode::StorageIo);
+        assert_eq!(
+            error.causes,
+            vec!["failed to write settings.tmp", "no space left on device"]
+        );
+    }
+
+    #[test]
+    fn localize_uses_the_requested_locale() {
+        let error = AppError::new(Failure::ProfileNotFound).caused_by("profile-1");
+
+        let english = error.clone().localize(DEFAULT_LOCALE);
+        let chinese = error.localize(ZH_CN_LOCALE);
+
+        assert_eq!(english.message, "The profile no longer exists.");
+        assert_eq!(
+            chinese.message,
+            "\u{8BE5}\u{65B9}\u{6848}\u{4E0D}\u{5B58}\u{5728}\u{3002}"
+        );
+        assert_eq!(chinese.causes, vec!["profile-1"]);
+        let json = serde_json::to_value(&chinese).unwrap();
+        assert_eq!(json["code"], "notFound");
+        assert_eq!(json["failure"], "profileNotFound");
+        assert!(json.get("fields").is_none());
+    }
+}
@@ -1,27 +1,46 @@
 mod analytics;
 mod commands;
+mod error;
 mod storage;
 mod system;
 mod timer;
 
 use commands::{
-    timer_get_insights, timer_get_state, timer_reset, timer_resume, timer_start,
-    timer_update_goals, timer_update_settings,
+    timer_create_backup, timer_create_profile, timer_delete_profile, timer_export_history,
+    timer_export_sessions_ics, timer_get_focus_distribution, timer_get_insights,
+    timer_get_profiles, timer_get_state, timer_get_storage_issues, timer_import_history,
+    timer_rename_profile, timer_reset, timer_restore_backup, timer_resume, timer_start,
+    timer_switch_profile, timer_update_goals, timer_update_settings,
 };
-use storage::state_file::StateFileStore;
+use std::sync::Arc;
+use storage::lock::DataDirLock;
+use storage::store::StateStore;
+use system::instance::{apply_launch_args, on_second_instance};
 use system::notify::Notifier;
-use tauri::Manager;
+use tauri::{Emitter, Manager};
+use timer::clock::SystemClock;
 use timer::engine::TimerEngine;
 
 fn main() {
     tauri::Builder::default()
+        .plugin(tauri_plugin_single_instance::init(on_second_instance))
         .plugin(tauri_plugin_notification::init())
         .setup(|app| {
             let app_handle = app.handle().clone();
-            let storage = StateFileStore::new(&app_handle);
+            let data_dir = StateStore::data_dir(&app_handle);
+            let data_dir_lock = DataDirLock::acquire(&data_dir)?;
+            let storage = Arc::new(StateStore::from_base_dir(data_dir.clone()));
             let notifier = Notifier;
-            let timer_engine = TimerEngine::new(storage, notifier);
+            let timer_engine = TimerEngine::new(storage, notifier, Arc::new(SystemClock));
+            let event_handle = app_handle.clone();
+            timer_engine.on_state_changed(move |change| {
+                let _ = event_handle.emit("timer_state_changed", change);
+            });
+            let launch_args: Vec<String> = std::env::args().collect();
+            apply_launch_args(&timer_engine, &launch_args);
+            timer_engine.start_file_watcher(app_handle.clone(), data_dir);
             timer_engine.start_worker(app_handle);
+            app.manage(data_dir_lock);
             app.manage(timer_engine);
             Ok(())
         })
@@ -33,6 +52,18 @@ fn main() {
             timer_update_settings,
             timer_get_insights,
             timer_update_goals,
+            timer_get_focus_distribution,
+            timer_export_sessions_ics,
+            timer_export_history,
+            timer_import_history,
+            timer_create_backup,
+            timer_restore_backup,
+            timer_get_storage_issues,
+            timer_get_profiles,
+            timer_create_profile,
+            timer_rename_profile,
+            timer_delete_profile,
+            timer_switch_profile,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
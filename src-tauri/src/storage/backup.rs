@@ -0,0 +1,467 @@
+use super::durable;
+use super::state_file::{write_json_file, StateFileStore};
+use super::store::StateStorage;
+use crate::analytics::model::AnalyticsState;
+use crate::timer::engine::{RuntimeState, Settings};
+use crate::timer::profile::ProfileState;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub const BACKUP_FORMAT: &str = "pomoduo-backup";
+pub const BACKUP_VERSION: u32 = 1;
+
+const BACKUP_DIR: &str = "backups";
+const DAILY_BACKUP_PREFIX: &str = "daily-";
+const PRE_RESTORE_BACKUP_PREFIX: &str = "pre-restore-";
+const RESTORE_EXTENSION: &str = "restore";
+const PREVIOUS_EXTENSION: &str = "previous";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupArchive {
+    pub format: String,
+    pub version: u32,
+    pub created_at_ms: i64,
+    #[serde(default)]
+    pub settings: Option<Settings>,
+    #[serde(default)]
+    pub runtime_state: Option<RuntimeState>,
+    #[serde(default)]
+    pub analytics_state: Option<AnalyticsState>,
+    /// Absent from archives written before settings profiles existed.
+    #[serde(default)]
+    pub profiles: Option<ProfileState>,
+}
+
+impl BackupArchive {
+    pub fn capture<S: StateStorage + ?Sized>(storage: &S, created_at_ms: i64) -> io::Result<Self> {
+        Ok(Self {
+            format: BACKUP_FORMAT.to_string(),
+            version: BACKUP_VERSION,
+            created_at_ms,
+            settings: storage.load_settings()?,
+            runtime_state: storage.load_runtime_state()?,
+            analytics_state: storage.load_analytics_state()?,
+            profiles: storage.load_profiles()?,
+        })
+    }
+
+    pub fn validate(&self) -> io::Result<()> {
+        if self.format != BACKUP_FORMAT {
+            return Err(invalid_backup(format!(
+                "unexpected backup format: {}",
+                self.format
+            )));
+        }
+
+        if self.version > BACKUP_VERSION {
+            return Err(invalid_backup(format!(
+                "backup version {} is newer than supported version {BACKUP_VERSION}",
+                self.version
+            )));
+        }
+
+        if self.is_empty() {
+            return Err(invalid_backup("backup contains no state files".to_string()));
+        }
+
+        Ok(())
+    }
+
+    fn is_empty(&self) -> bool {
+        self.settings.is_none()
+            && self.runtime_state.is_none()
+            && self.analytics_state.is_none()
+            && self.profiles.is_none()
+    }
+}
+
+pub fn write_backup(path: &Path, archive: &BackupArchive) -> io::Result<()> {
+    archive.validate()?;
+    write_json_file(path, archive)
+}
+
+pub fn read_backup(path: &Path) -> io::Result<BackupArchive> {
+    let text = fs::read_to_string(path)?;
+    let archive = serde_json::from_str::<BackupArchive>(&text)
+        .map_err(|error| invalid_backup(error.to_string()))?;
+    archive.validate()?;
+    Ok(archive)
+}
+
+/// Keeps the current state before a restore replaces it. Only a store with
+/// nothing saved yet has nothing to keep.
+pub(super) fn write_pre_restore_backup<S: StateStorage + ?Sized>(
+    storage: &S,
+    base_dir: &Path,
+    now_ms: i64,
+) -> io::Result<()> {
+    let archive = BackupArchive::capture(storage, now_ms)?;
+    if archive.is_empty() {
+        return Ok(());
+    }
+
+    let pre_restore =
+        backup_dir(base_dir).join(format!("{PRE_RESTORE_BACKUP_PREFIX}{now_ms}.json"));
+    write_backup(&pre_restore, &archive)
+}
+
+pub(super) fn ensure_daily_backup<S: StateStorage + ?Sized>(
+    storage: &S,
+    base_dir: &Path,
+    today: NaiveDate,
+    keep: u32,
+    created_at_ms: i64,
+) -> io::Result<()> {
+    if keep == 0 {
+        return Ok(());
+    }
+
+    let path = backup_dir(base_dir).join(format!(
+        "{DAILY_BACKUP_PREFIX}{}.json",
+        today.format("%Y-%m-%d")
+    ));
+
+    if !path.exists() {
+        let archive = BackupArchive::capture(storage, created_at_ms)?;
+        if archive.validate().is_ok() {
+            write_json_file(&path, &archive)?;
+        }
+    }
+
+    rotate_daily_backups(base_dir, keep as usize)
+}
+
+fn rotate_daily_backups(base_dir: &Path, keep: usize) -> io::Result<()> {
+    let mut daily_backups = daily_backups(base_dir)?;
+    if daily_backups.len() <= keep {
+        return Ok(());
+    }
+
+    daily_backups.sort();
+    let excess = daily_backups.len() - keep;
+    for path in daily_backups.into_iter().take(excess) {
+        fs::remove_file(path)?;
+    }
+
+    Ok(())
+}
+
+fn daily_backups(base_dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let entries = match fs::read_dir(backup_dir(base_dir)) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(error),
+    };
+
+    let mut paths = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        let is_daily_backup = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with(DAILY_BACKUP_PREFIX) && name.ends_with(".json"));
+        if is_daily_backup {
+            paths.push(path);
+        }
+    }
+
+    Ok(paths)
+}
+
+fn backup_dir(base_dir: &Path) -> PathBuf {
+    base_dir.join(BACKUP_DIR)
+}
+
+impl StateFileStore {
+    /// Every file is staged next to its target before anything is replaced, so a
+    /// failed write leaves the current data untouched. The current files are
+    /// then moved aside as the staged ones take their place, and all of them
+    /// are put back if any rename fails.
+    pub(super) fn restore_archive(&self, archive: &BackupArchive) -> io::Result<()> {
+        self.restore_archive_with(archive, || Ok(()))
+    }
+
+    /// `before` runs ahead of every rename; an error from it is handled like
+    /// a failed rename.
+    fn restore_archive_with(
+        &self,
+        archive: &BackupArchive,
+        mut before: impl FnMut() -> io::Result<()>,
+    ) -> io::Result<()> {
+        let mut staged = Vec::new();
+        let staging = (|| -> io::Result<()> {
+            if let Some(settings) = archive.settings.as_ref() {
+                staged.push(self.stage_json(self.settings_file(), settings)?);
+            }
+            if let Some(runtime_state) = archive.runtime_state.as_ref() {
+                staged.push(self.stage_json(self.runtime_state_file(), runtime_state)?);
+            }
+            if let Some(analytics_state) = archive.analytics_state.as_ref() {
+                staged.push(self.stage_json(self.analytics_state_file(), analytics_state)?);
+            }
+            if let Some(profiles) = archive.profiles.as_ref() {
+                staged.push(self.stage_json(self.profiles_file(), profiles)?);
+            }
+            Ok(())
+        })();
+
+        if let Err(error) = staging {
+            for (staged_path, _) in &staged {
+                let _ = fs::remove_file(staged_path);
+            }
+            return Err(error);
+        }
+
+        let mut swapped = Vec::new();
+        for (staged_path, target) in &staged {
+            match swap_in(staged_path, target, &mut before) {
+                Ok(previous) => swapped.push((target, previous)),
+                Err(error) => {
+                    roll_back(&swapped);
+                    for (staged_path, _) in &staged {
+                        let _ = fs::remove_file(staged_path);
+                    }
+                    return Err(error);
+                }
+            }
+        }
+        if let Some((_, target)) = staged.first() {
+            durable::sync_parent_dir(target)?;
+        }
+        for previous in swapped.into_iter().filter_map(|(_, previous)| previous) {
+            let _ = fs::remove_file(previous);
+        }
+
+        Ok(())
+    }
+
+    fn stage_json<T: Serialize>(
+        &self,
+        target: PathBuf,
+        value: &T,
+    ) -> io::Result<(PathBuf, PathBuf)> {
+        let staged_path = target.with_extension(RESTORE_EXTENSION);
+        write_json_file(&staged_path, value)?;
+        Ok((staged_path, target))
+    }
+}
+
+/// Moves `target` aside and `staged_path` into its place. Returns where the
+/// previous file went, or `None` when there was none. A failure puts the
+/// previous file back.
+fn swap_in(
+    staged_path: &Path,
+    target: &Path,
+    before: &mut impl FnMut() -> io::Result<()>,
+) -> io::Result<Option<PathBuf>> {
+    let previous = target
+        .exists()
+        .then(|| target.with_extension(PREVIOUS_EXTENSION));
+    if let Some(previous) = previous.as_ref() {
+        before()?;
+        fs::rename(target, previous)?;
+    }
+
+    let moved_in = before().and_then(|_| fs::rename(staged_path, target));
+    if let Err(error) = moved_in {
+        if let Some(previous) = previous.as_ref() {
+            let _ = fs::rename(previous, target);
+        }
+        return Err(error);
+    }
+
+    Ok(previous)
+}
+
+/// Undoes completed swaps, newest first.
+fn roll_back(swapped: &[(&PathBuf, Option<PathBuf>)]) {
+    for (target, previous) in swapped.iter().rev() {
+        let _ = match previous {
+            Some(previous) => fs::rename(previous, target),
+            None => fs::remove_file(target),
+        };
+    }
+}
+
+fn invalid_backup(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::recovery::IssueLog;
+    use crate::storage::store::StateStore;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn temp_store(name: &str) -> StateStore {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        StateStore::from_base_dir(
+            std::env::temp_dir().join(format!("pomoduo-{name}-{}-{nanos}", std::process::id())),
+        )
+    }
+
+    #[test]
+    fn ensure_daily_backup_keeps_only_newest_backups() {
+        let store = temp_store("rotate");
+        store.save_settings(&Settings::default()).unwrap();
+
+        for day in 1..=5 {
+            let today = NaiveDate::from_ymd_opt(2026, 2, day).unwrap();
+            store.ensure_daily_backup(today, 3, 0).unwrap();
+        }
+
+        let mut names: Vec<String> = daily_backups(store.base_dir())
+            .unwrap()
+            .iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+
+        assert_eq!(
+            names,
+            vec![
+                "daily-2026-02-03.json",
+                "daily-2026-02-04.json",
+                "daily-2026-02-05.json"
+            ]
+        );
+        let _ = fs::remove_dir_all(store.base_dir());
+    }
+
+    #[test]
+    fn restore_backup_round_trips_state_files() {
+        let store = temp_store("restore");
+        let mut analytics = AnalyticsState::default();
+        analytics.goals.daily.focus_target = 12;
+        store.save_analytics_state(&analytics).unwrap();
+        let archive_path = store.base_dir().join("manual.json");
+        store.create_backup(&archive_path, 1).unwrap();
+
+        store
+            .save_analytics_state(&AnalyticsState::default())
+            .unwrap();
+        let archive = read_backup(&archive_path).unwrap();
+        store.restore_backup(&archive, 2).unwrap();
+
+        let restored = store.load_analytics_state().unwrap().unwrap();
+        assert_eq!(restored.goals.daily.focus_target, 12);
+        assert!(backup_dir(store.base_dir())
+            .join(format!("{PRE_RESTORE_BACKUP_PREFIX}2.json"))
+            .exists());
+        let _ = fs::remove_dir_all(store.base_dir());
+    }
+
+    #[test]
+    fn restore_backup_is_refused_when_the_current_state_cannot_be_kept() {
+        let store = temp_store("pre-restore");
+        let mut analytics = AnalyticsState::default();
+        analytics.goals.daily.focus_target = 12;
+        store.save_analytics_state(&analytics).unwrap();
+        let archive = BackupArchive::capture(&store, 1).unwrap();
+        store
+            .save_analytics_state(&AnalyticsState::default())
+            .unwrap();
+        // A directory in the way makes the pre-restore backup unwritable.
+        fs::create_dir_all(
+            backup_dir(store.base_dir()).join(format!("{PRE_RESTORE_BACKUP_PREFIX}2.json")),
+        )
+        .unwrap();
+
+        assert!(store.restore_backup(&archive, 2).is_err());
+
+        let current = store.load_analytics_state().unwrap().unwrap();
+        assert_eq!(
+            current.goals.daily.focus_target,
+            AnalyticsState::default().goals.daily.focus_target
+        );
+        let _ = fs::remove_dir_all(store.base_dir());
+    }
+
+    #[test]
+    fn failed_rename_at_any_point_rolls_the_restore_back() {
+        let store = temp_store("restore-rollback");
+        let files =
+            StateFileStore::with_issue_log(store.base_dir().to_path_buf(), IssueLog::default());
+        let old_settings = Settings::default();
+        let mut old_analytics = AnalyticsState::default();
+        old_analytics.goals.daily.focus_target = 3;
+        let mut archive = BackupArchive::capture(&store, 1).unwrap();
+        archive.settings = Some(Settings {
+            focus_ms: 50 * 60_000,
+            ..Settings::default()
+        });
+        let mut new_analytics = AnalyticsState::default();
+        new_analytics.goals.daily.focus_target = 12;
+        archive.analytics_state = Some(new_analytics);
+
+        // Two existing files take two renames each.
+        for fail_at in 0..4 {
+            files.save_settings(&old_settings).unwrap();
+            files.save_analytics_state(&old_analytics).unwrap();
+            let mut renames = 0;
+            let failing = || {
+                renames += 1;
+                if renames > fail_at {
+                    Err(io::Error::other(format!(
+                        "simulated failure at rename {fail_at}"
+                    )))
+                } else {
+                    Ok(())
+                }
+            };
+
+            assert!(files.restore_archive_with(&archive, failing).is_err());
+
+            let settings = files.load_settings().unwrap().unwrap();
+            let analytics = files.load_analytics_state().unwrap().unwrap();
+            assert_eq!(settings.focus_ms, old_settings.focus_ms, "rename {fail_at}");
+            assert_eq!(analytics.goals.daily.focus_target, 3, "rename {fail_at}");
+            let leftovers: Vec<_> = fs::read_dir(store.base_dir())
+                .unwrap()
+                .map(|entry| entry.unwrap().path())
+                .filter(|path| {
+                    path.extension().is_some_and(|extension| {
+                        extension == RESTORE_EXTENSION || extension == PREVIOUS_EXTENSION
+                    })
+                })
+                .collect();
+            assert!(leftovers.is_empty(), "rename {fail_at} left {leftovers:?}");
+        }
+
+        files.restore_archive(&archive).unwrap();
+        assert_eq!(
+            files.load_settings().unwrap().unwrap().focus_ms,
+            50 * 60_000
+        );
+        let _ = fs::remove_dir_all(store.base_dir());
+    }
+
+    #[test]
+    fn read_backup_rejects_foreign_and_newer_archives() {
+        let store = temp_store("validate");
+        fs::create_dir_all(store.base_dir()).unwrap();
+        let path = store.base_dir().join("foreign.json");
+
+        fs::write(&path, r#"{"format":"other","version":1,"createdAtMs":0}"#).unwrap();
+        assert!(read_backup(&path).is_err());
+
+        fs::write(
+            &path,
+            format!(
+                r#"{{"format":"{BACKUP_FORMAT}","version":{},"createdAtMs":0,"settings":null}}"#,
+                BACKUP_VERSION + 1
+            ),
+        )
+        .unwrap();
+        assert!(read_backup(&path).is_err());
+        let _ = fs::remove_dir_all(store.base_dir());
+    }
+}
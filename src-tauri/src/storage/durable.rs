@@ -0,0 +1,175 @@
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::Path;
+
+const TEMP_EXTENSION: &str = "tmp";
+
+/// The points at which a crash can interrupt `write_atomic`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WriteStep {
+    CreateTemp,
+    WriteTemp,
+    SyncTemp,
+    Replace,
+    SyncDir,
+}
+
+#[cfg(test)]
+const WRITE_STEPS: [WriteStep; 5] = [
+    WriteStep::CreateTemp,
+    WriteStep::WriteTemp,
+    WriteStep::SyncTemp,
+    WriteStep::Replace,
+    WriteStep::SyncDir,
+];
+
+/// Replaces `path` with `bytes` so that after a crash at any point the file
+/// holds either its previous or its new content, never a mix or nothing.
+///
+/// The data goes to a sibling temp file that is fsynced before it is renamed
+/// over the target; the parent directory is fsynced afterwards so the rename
+/// itself survives power loss. `fs::rename` replaces an existing target
+/// atomically on both Unix and Windows, so the target is never removed first.
+pub fn write_atomic(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    write_atomic_with(path, bytes, |_| Ok(()))
+}
+
+/// Makes a completed rename durable. Call after moving files into place.
+pub fn sync_parent_dir(path: &Path) -> io::Result<()> {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => sync_dir(parent),
+        _ => Ok(()),
+    }
+}
+
+/// `before` runs ahead of every step; an error from it aborts the write on
+/// the spot without any cleanup, the way a crash would.
+fn write_atomic_with(
+    path: &Path,
+    bytes: &[u8],
+    mut before: impl FnMut(WriteStep) -> io::Result<()>,
+) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let temp_path = path.with_extension(TEMP_EXTENSION);
+
+    before(WriteStep::CreateTemp)?;
+    let mut file = File::create(&temp_path)?;
+
+    let (head, tail) = bytes.split_at(bytes.len() / 2);
+    let written = file.write_all(head).and_then(|_| {
+        before(WriteStep::WriteTemp)?;
+        file.write_all(tail)
+    });
+    if let Err(error) = written {
+        return Err(discard(&temp_path, error));
+    }
+
+    before(WriteStep::SyncTemp)?;
+    if let Err(error) = file.sync_all() {
+        return Err(discard(&temp_path, error));
+    }
+    drop(file);
+
+    before(WriteStep::Replace)?;
+    if let Err(error) = fs::rename(&temp_path, path) {
+        return Err(discard(&temp_path, error));
+    }
+
+    before(WriteStep::SyncDir)?;
+    sync_parent_dir(path)
+}
+
+fn discard(temp_path: &Path, error: io::Error) -> io::Error {
+    let _ = fs::remove_file(temp_path);
+    error
+}
+
+#[cfg(unix)]
+fn sync_dir(dir: &Path) -> io::Result<()> {
+    File::open(dir)?.sync_all()
+}
+
+/// Windows cannot open directories for syncing; NTFS journals the rename
+/// metadata itself.
+#[cfg(not(unix))]
+fn sync_dir(_dir: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir =
+            std::env::temp_dir().join(format!("pomoduo-{name}-{}-{nanos}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn crash_at(step: WriteStep) -> impl FnMut(WriteStep) -> io::Result<()> {
+        move |current| {
+            if current == step {
+                Err(io::Error::other(format!("simulated crash before {step:?}")))
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    #[test]
+    fn crash_at_any_step_leaves_old_or_new_content() {
+        let dir = temp_dir("durable");
+        let old = br#"{"focusCompleted":1}"#.as_slice();
+        let new = br#"{"focusCompleted":2,"longCycleCompleted":1}"#.as_slice();
+
+        for step in WRITE_STEPS {
+            let path = dir.join(format!("{step:?}.json"));
+            write_atomic(&path, old).unwrap();
+
+            assert!(write_atomic_with(&path, new, crash_at(step)).is_err());
+
+            let survived = fs::read(&path).unwrap();
+            assert!(
+                survived == old || survived == new,
+                "crash before {step:?} left {:?}",
+                String::from_utf8_lossy(&survived)
+            );
+            let expected = if step == WriteStep::SyncDir { new } else { old };
+            assert_eq!(survived, expected, "crash before {step:?}");
+
+            // A leftover temp file from the crash must not block the next write.
+            write_atomic(&path, new).unwrap();
+            assert_eq!(fs::read(&path).unwrap(), new);
+        }
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn crash_before_first_write_leaves_no_target() {
+        let dir = temp_dir("durable-first");
+        let path = dir.join("settings.json");
+
+        for step in WRITE_STEPS {
+            let _ = fs::remove_file(&path);
+            let _ = write_atomic_with(&path, b"{}", crash_at(step));
+
+            match fs::read(&path) {
+                Ok(content) => assert_eq!(content, b"{}", "crash before {step:?}"),
+                Err(error) => assert_eq!(error.kind(), io::ErrorKind::NotFound),
+            }
+        }
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,60 @@
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io;
+use std::path::Path;
+
+const LOCK_FILE: &str = "pomoduo.lock";
+
+/// An exclusive OS lock on the data directory, held for the lifetime of the
+/// process so two instances never run tick workers against the same files.
+/// The lock is released by the OS when the process exits, even after a crash.
+pub struct DataDirLock {
+    _file: File,
+}
+
+impl DataDirLock {
+    pub fn acquire(base_dir: &Path) -> io::Result<Self> {
+        fs::create_dir_all(base_dir)?;
+        let file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .write(true)
+            .truncate(false)
+            .open(base_dir.join(LOCK_FILE))?;
+
+        match file.try_lock() {
+            Ok(()) => Ok(Self { _file: file }),
+            Err(TryLockError::WouldBlock) => Err(io::Error::new(
+                io::ErrorKind::WouldBlock,
+                format!(
+                    "data directory {} is in use by another Pomoduo process",
+                    base_dir.display()
+                ),
+            )),
+            Err(TryLockError::Error(error)) => Err(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn second_lock_on_same_directory_is_refused_until_released() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let base_dir =
+            std::env::temp_dir().join(format!("pomoduo-lock-{}-{nanos}", std::process::id()));
+
+        let first = DataDirLock::acquire(&base_dir).unwrap();
+        let error = DataDirLock::acquire(&base_dir).err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::WouldBlock);
+
+        drop(first);
+        assert!(DataDirLock::acquire(&base_dir).is_ok());
+        let _ = fs::remove_dir_all(base_dir);
+    }
+}
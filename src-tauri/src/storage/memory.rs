@@ -0,0 +1,105 @@
+use super::store::{StateStorage, StorageBackend};
+use crate::analytics::model::AnalyticsState;
+use crate::timer::engine::{RuntimeState, Settings};
+use crate::timer::profile::ProfileState;
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+/// Keeps state in memory so the engine can be exercised without a data
+/// directory. Individual writes can be made to fail to cover error paths.
+#[derive(Default)]
+pub struct MemoryStore {
+    settings: Mutex<Option<Settings>>,
+    runtime_state: Mutex<Option<RuntimeState>>,
+    analytics_state: Mutex<Option<AnalyticsState>>,
+    profiles: Mutex<Option<ProfileState>>,
+    backend: Mutex<StorageBackend>,
+    fail_settings: AtomicBool,
+    fail_runtime_state: AtomicBool,
+    fail_analytics_state: AtomicBool,
+}
+
+impl MemoryStore {
+    pub fn fail_settings_writes(&self, fail: bool) {
+        self.fail_settings.store(fail, Ordering::SeqCst);
+    }
+
+    pub fn fail_runtime_state_writes(&self, fail: bool) {
+        self.fail_runtime_state.store(fail, Ordering::SeqCst);
+    }
+
+    pub fn fail_analytics_state_writes(&self, fail: bool) {
+        self.fail_analytics_state.store(fail, Ordering::SeqCst);
+    }
+}
+
+impl StateStorage for MemoryStore {
+    fn load_settings(&self) -> io::Result<Option<Settings>> {
+        Ok(lock(&self.settings).clone())
+    }
+
+    fn save_settings(&self, settings: &Settings) -> io::Result<()> {
+        store(&self.settings, &self.fail_settings, settings)
+    }
+
+    fn load_runtime_state(&self) -> io::Result<Option<RuntimeState>> {
+        Ok(lock(&self.runtime_state).clone())
+    }
+
+    fn save_runtime_state(&self, runtime_state: &RuntimeState) -> io::Result<()> {
+        store(&self.runtime_state, &self.fail_runtime_state, runtime_state)
+    }
+
+    fn load_analytics_state(&self) -> io::Result<Option<AnalyticsState>> {
+        Ok(lock(&self.analytics_state).clone())
+    }
+
+    fn save_analytics_state(&self, analytics_state: &AnalyticsState) -> io::Result<()> {
+        store(
+            &self.analytics_state,
+            &self.fail_analytics_state,
+            analytics_state,
+        )
+    }
+
+    fn load_profiles(&self) -> io::Result<Option<ProfileState>> {
+        Ok(lock(&self.profiles).clone())
+    }
+
+    fn save_profiles(&self, profiles: &ProfileState) -> io::Result<()> {
+        *lock(&self.profiles) = Some(profiles.clone());
+        Ok(())
+    }
+
+    fn backend(&self) -> StorageBackend {
+        *lock(&self.backend)
+    }
+
+    fn switch_backend(
+        &self,
+        backend: StorageBackend,
+        settings: &Settings,
+        runtime_state: &RuntimeState,
+        analytics_state: &AnalyticsState,
+    ) -> io::Result<()> {
+        self.save_settings(settings)?;
+        self.save_runtime_state(runtime_state)?;
+        self.save_analytics_state(analytics_state)?;
+        *lock(&self.backend) = backend;
+        Ok(())
+    }
+}
+
+fn lock<T>(slot: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    slot.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn store<T: Clone>(slot: &Mutex<Option<T>>, fail: &AtomicBool, value: &T) -> io::Result<()> {
+    if fail.load(Ordering::SeqCst) {
+        return Err(io::Error::other("simulated write failure"));
+    }
+
+    *lock(slot) = Some(value.clone());
+    Ok(())
+}
@@ -0,0 +1,412 @@
+use crate::analytics::model::{AnalyticsState, GoalUnit, ANALYTICS_VERSION};
+use crate::timer::engine::Settings;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::io;
+
+pub const SETTINGS_VERSION: u32 = 6;
+pub const RUNTIME_STATE_VERSION: u32 = 3;
+pub const PROFILES_VERSION: u32 = 6;
+
+const VERSION_KEY: &str = "version";
+
+/// Upgrades a document in place from the version just below its position in
+/// the migration list. Step `n` turns a version `n` document into version `n + 1`.
+type Migration = fn(&mut Map<String, Value>);
+
+/// Every kind needs exactly one step per version; `decode` refuses to run
+/// an incomplete list.
+const SETTINGS_MIGRATIONS: &[Migration] = &[
+    settings_v0_to_v1,
+    settings_v1_to_v2,
+    settings_v2_to_v3,
+    settings_v3_to_v4,
+    settings_v4_to_v5,
+    settings_v5_to_v6,
+];
+const RUNTIME_STATE_MIGRATIONS: &[Migration] = &[
+    unversioned_to_v1,
+    // v2 added `clockJumps`, v3 `task` and `project`; all optional.
+    optional_fields_added,
+    optional_fields_added,
+];
+const ANALYTICS_MIGRATIONS: &[Migration] = &[
+    unversioned_to_v1,
+    // v2 added `profileId` and v3 `clockJumps` to sessions; both optional.
+    optional_fields_added,
+    optional_fields_added,
+    analytics_v3_to_v4,
+    analytics_v4_to_v5,
+    analytics_v5_to_v6,
+    analytics_v6_to_v7,
+    analytics_v7_to_v8,
+    analytics_v8_to_v9,
+    // v10 added `task` and `project` and v11 `closedLongCycle` to sessions;
+    // all optional.
+    optional_fields_added,
+    optional_fields_added,
+];
+/// Profiles embed settings, so they follow the settings steps made after
+/// profiles were introduced at settings version 1.
+const PROFILES_MIGRATIONS: &[Migration] = &[
+    unversioned_to_v1,
+    profiles_v1_to_v2,
+    profiles_v2_to_v3,
+    profiles_v3_to_v4,
+    profiles_v4_to_v5,
+    profiles_v5_to_v6,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentKind {
+    Settings,
+    RuntimeState,
+    Analytics,
+    Profiles,
+}
+
+impl DocumentKind {
+    pub fn current_version(self) -> u32 {
+        match self {
+            Self::Settings => SETTINGS_VERSION,
+            Self::RuntimeState => RUNTIME_STATE_VERSION,
+            Self::Analytics => ANALYTICS_VERSION,
+            Self::Profiles => PROFILES_VERSION,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Settings => "settings",
+            Self::RuntimeState => "runtime state",
+            Self::Analytics => "analytics state",
+            Self::Profiles => "settings profiles",
+        }
+    }
+
+    fn migrations(self) -> &'static [Migration] {
+        match self {
+            Self::Settings => SETTINGS_MIGRATIONS,
+            Self::RuntimeState => RUNTIME_STATE_MIGRATIONS,
+            Self::Analytics => ANALYTICS_MIGRATIONS,
+            Self::Profiles => PROFILES_MIGRATIONS,
+        }
+    }
+}
+
+/// Serializes a value with the current version stamped on it.
+pub fn encode<T: Serialize>(kind: DocumentKind, value: &T) -> io::Result<Value> {
+    let mut document = serde_json::to_value(value).map_err(invalid_data)?;
+    document_object(kind, &mut document)?
+        .insert(VERSION_KEY.to_string(), Value::from(kind.current_version()));
+    Ok(document)
+}
+
+/// Runs every upgrade step between the stored version and the current one,
+/// then deserializes. Documents without a version predate versioning and are
+/// treated as version 0.
+pub fn decode<T: DeserializeOwned>(kind: DocumentKind, mut document: Value) -> io::Result<T> {
+    let version = document_version(kind, &document)?;
+    ensure_supported(kind, version)?;
+    let migrations = kind.migrations();
+    if migrations.len() != kind.current_version() as usize {
+        return Err(io::Error::other(format!(
+            "{} has {} upgrade steps for version {}",
+            kind.label(),
+            migrations.len(),
+            kind.current_version()
+        )));
+    }
+
+    let object = document_object(kind, &mut document)?;
+    for migration in &migrations[version as usize..] {
+        migration(object);
+    }
+    object.insert(VERSION_KEY.to_string(), Value::from(kind.current_version()));
+
+    serde_json::from_value(document).map_err(invalid_data)
+}
+
+/// Fails when a stored document was written by a newer app version, so it is
+/// never overwritten with data this version does not understand.
+pub fn ensure_writable(kind: DocumentKind, existing: &Value) -> io::Result<()> {
+    ensure_supported(kind, document_version(kind, existing)?)
+}
+
+pub fn ensure_supported(kind: DocumentKind, version: u32) -> io::Result<()> {
+    let current = kind.current_version();
+    if version > current {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!(
+                "{} version {version} is newer than supported version {current}",
+                kind.label()
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+fn document_version(kind: DocumentKind, document: &Value) -> io::Result<u32> {
+    match document.get(VERSION_KEY) {
+        None | Some(Value::Null) => Ok(0),
+        Some(value) => value
+            .as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .ok_or_else(|| {
+                invalid_data(format!("{} has an invalid version: {value}", kind.label()))
+            }),
+    }
+}
+
+fn document_object(
+    kind: DocumentKind,
+    document: &mut Value,
+) -> io::Result<&mut Map<String, Value>> {
+    document
+        .as_object_mut()
+        .ok_or_else(|| invalid_data(format!("{} is not a JSON object", kind.label())))
+}
+
+fn settings_v0_to_v1(settings: &mut Map<String, Value>) {
+    settings
+        .entry("locale")
+        .or_insert_with(|| Value::from(crate::timer::engine::DEFAULT_LOCALE));
+}
+
+/// Clock jump policy.
+fn settings_v1_to_v2(settings: &mut Map<String, Value>) {
+    fill_defaults(settings, &default_settings(), &["clockJumpPolicy"]);
+}
+
+/// Developer time scale.
+fn settings_v2_to_v3(settings: &mut Map<String, Value>) {
+    fill_defaults(settings, &default_settings(), &["devTimeScale"]);
+}
+
+/// Day attribution; the optional home zone needs no default.
+fn settings_v3_to_v4(settings: &mut Map<String, Value>) {
+    fill_defaults(settings, &default_settings(), &["dayAttribution"]);
+}
+
+/// Day start hour.
+fn settings_v4_to_v5(settings: &mut Map<String, Value>) {
+    fill_defaults(settings, &default_settings(), &["dayStartHour"]);
+}
+
+/// History retention.
+fn settings_v5_to_v6(settings: &mut Map<String, Value>) {
+    fill_defaults(settings, &default_settings(), &["historyRetentionDays"]);
+}
+
+/// Day zone of the daily keys.
+fn analytics_v3_to_v4(analytics: &mut Map<String, Value>) {
+    fill_defaults(analytics, &default_analytics(), &["dayZone"]);
+}
+
+/// Day start hour of the daily keys.
+fn analytics_v4_to_v5(analytics: &mut Map<String, Value>) {
+    fill_defaults(analytics, &default_analytics(), &["dayStartHour"]);
+}
+
+/// Focus minutes per day; sessions also gained an optional `durationMs`.
+fn analytics_v5_to_v6(analytics: &mut Map<String, Value>) {
+    for day in objects_in(analytics.get_mut("daily")) {
+        day.entry("focusMinutes").or_insert_with(|| Value::from(0));
+    }
+}
+
+/// Monthly rollups.
+fn analytics_v6_to_v7(analytics: &mut Map<String, Value>) {
+    fill_defaults(analytics, &default_analytics(), &["monthly"]);
+}
+
+/// Rest days in the goals.
+fn analytics_v7_to_v8(analytics: &mut Map<String, Value>) {
+    if let Some(goals) = analytics.get_mut("goals").and_then(Value::as_object_mut) {
+        goals
+            .entry("restDays")
+            .or_insert_with(|| Value::Array(Vec::new()));
+    }
+}
+
+/// A focus unit on each goal period.
+fn analytics_v8_to_v9(analytics: &mut Map<String, Value>) {
+    let unit = serde_json::to_value(GoalUnit::default()).unwrap_or_default();
+    if let Some(goals) = analytics.get_mut("goals").and_then(Value::as_object_mut) {
+        for period in ["daily", "weekly", "monthly"] {
+            if let Some(goal) = goals.get_mut(period).and_then(Value::as_object_mut) {
+                goal.entry("focusUnit").or_insert_with(|| unit.clone());
+            }
+        }
+    }
+}
+
+fn profiles_v1_to_v2(profiles: &mut Map<String, Value>) {
+    each_profile_settings(profiles, settings_v1_to_v2);
+}
+
+fn profiles_v2_to_v3(profiles: &mut Map<String, Value>) {
+    each_profile_settings(profiles, settings_v2_to_v3);
+}
+
+fn profiles_v3_to_v4(profiles: &mut Map<String, Value>) {
+    each_profile_settings(profiles, settings_v3_to_v4);
+}
+
+fn profiles_v4_to_v5(profiles: &mut Map<String, Value>) {
+    each_profile_settings(profiles, settings_v4_to_v5);
+}
+
+fn profiles_v5_to_v6(profiles: &mut Map<String, Value>) {
+    each_profile_settings(profiles, settings_v5_to_v6);
+}
+
+fn unversioned_to_v1(_document: &mut Map<String, Value>) {}
+
+/// For versions that only added fields which deserialize as absent.
+fn optional_fields_added(_document: &mut Map<String, Value>) {}
+
+fn each_profile_settings(profiles: &mut Map<String, Value>, step: Migration) {
+    if let Some(Value::Array(profiles)) = profiles.get_mut("profiles") {
+        for profile in profiles.iter_mut().filter_map(Value::as_object_mut) {
+            if let Some(settings) = profile.get_mut("settings").and_then(Value::as_object_mut) {
+                step(settings);
+            }
+        }
+    }
+}
+
+fn objects_in(value: Option<&mut Value>) -> impl Iterator<Item = &mut Map<String, Value>> {
+    value
+        .and_then(Value::as_object_mut)
+        .into_iter()
+        .flat_map(|entries| entries.values_mut().filter_map(Value::as_object_mut))
+}
+
+/// Writes the default of each key the document lacks.
+fn fill_defaults(document: &mut Map<String, Value>, defaults: &Value, keys: &[&str]) {
+    for key in keys {
+        if let Some(value) = defaults.get(*key) {
+            document
+                .entry(key.to_string())
+                .or_insert_with(|| value.clone());
+        }
+    }
+}
+
+fn default_settings() -> Value {
+    serde_json::to_value(Settings::default()).unwrap_or_default()
+}
+
+fn default_analytics() -> Value {
+    serde_json::to_value(AnalyticsState::default()).unwrap_or_default()
+}
+
+fn invalid_data(error: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timer::engine::Settings;
+
+    #[test]
+    fn decode_upgrades_unversioned_settings() {
+        let document = serde_json::json!({
+            "focusMs": 1_500_000,
+            "shortBreakMs": 300_000,
+            "longBreakMs": 900_000,
+            "longBreakEvery": 4,
+            "notifyEnabled": true,
+            "soundEnabled": false
+        });
+
+        let settings: Settings = decode(DocumentKind::Settings, document).unwrap();
+
+        assert_eq!(settings.locale, crate::timer::engine::DEFAULT_LOCALE);
+        assert!(!settings.sound_enabled);
+    }
+
+    #[test]
+    fn decode_and_writes_refuse_newer_versions() {
+        let document = serde_json::json!({ "version": SETTINGS_VERSION + 1 });
+
+        let error = decode::<Settings>(DocumentKind::Settings, document.clone()).unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::Unsupported);
+        assert!(ensure_writable(DocumentKind::Settings, &document).is_err());
+    }
+
+    #[test]
+    fn every_kind_has_one_step_per_version() {
+        for kind in [
+            DocumentKind::Settings,
+            DocumentKind::RuntimeState,
+            DocumentKind::Analytics,
+            DocumentKind::Profiles,
+        ] {
+            assert_eq!(
+                kind.migrations().len(),
+                kind.current_version() as usize,
+                "{}",
+                kind.label()
+            );
+        }
+    }
+
+    #[test]
+    fn decode_fills_fields_added_since_version_one() {
+        let settings = serde_json::json!({
+            "version": 1,
+            "focusMs": 1_500_000,
+            "shortBreakMs": 300_000,
+            "longBreakMs": 900_000,
+            "longBreakEvery": 4,
+            "notifyEnabled": true,
+            "soundEnabled": true,
+            "locale": "en-US"
+        });
+        let profiles = serde_json::json!({
+            "version": 1,
+            "activeProfileId": "default",
+            "profiles": [{ "id": "default", "name": "Default", "settings": settings.clone() }]
+        });
+        let analytics = serde_json::json!({
+            "version": 1,
+            "daily": { "2026-02-14": { "focusCompleted": 3, "longCycleCompleted": 0 } },
+            "goals": {
+                "daily": { "focusTarget": 8, "longCycleTarget": 1 },
+                "weekly": { "focusTarget": 40, "longCycleTarget": 5 },
+                "monthly": { "focusTarget": 160, "longCycleTarget": 20 }
+            }
+        });
+
+        let upgraded = encode(
+            DocumentKind::Settings,
+            &decode::<Settings>(DocumentKind::Settings, settings).unwrap(),
+        )
+        .unwrap();
+        let profiles: crate::timer::profile::ProfileState =
+            decode(DocumentKind::Profiles, profiles).unwrap();
+        let analytics: AnalyticsState = decode(DocumentKind::Analytics, analytics).unwrap();
+
+        assert_eq!(upgraded["version"], SETTINGS_VERSION);
+        assert_eq!(
+            profiles.profiles[0].settings.history_retention_days,
+            Settings::default().history_retention_days
+        );
+        assert_eq!(analytics.daily["2026-02-14"].focus_minutes, 0);
+        assert!(analytics.goals.rest_days.is_empty());
+    }
+
+    #[test]
+    fn encode_stamps_current_version() {
+        let document = encode(DocumentKind::Settings, &Settings::default()).unwrap();
+
+        assert_eq!(document["version"], SETTINGS_VERSION);
+    }
+}
@@ -1 +1,11 @@
+pub mod backup;
+pub mod durable;
+pub mod lock;
+#[cfg(test)]
+pub mod memory;
+pub mod migrate;
+pub mod recovery;
+pub mod sqlite;
 pub mod state_file;
+pub mod store;
+pub mod watch;
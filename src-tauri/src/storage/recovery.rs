@@ -0,0 +1,234 @@
+use crate::analytics::model::{AnalyticsState, DailyMetrics, GoalSettings, SessionRecord};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const QUARANTINE_MARKER: &str = "corrupt";
+
+/// A state file that could not be read at load time.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageIssue {
+    pub file: String,
+    pub message: String,
+    pub quarantined_path: Option<String>,
+    /// Sections that were salvaged from the unreadable file. Empty when the
+    /// state was reset to defaults.
+    pub recovered: Vec<String>,
+    /// Nothing is saved until the cause is fixed; changes made meanwhile are
+    /// lost on exit.
+    pub read_only: bool,
+    pub detected_at_ms: i64,
+}
+
+/// Issues collected by the storage backends until the engine picks them up.
+#[derive(Clone, Default)]
+pub struct IssueLog {
+    issues: Arc<Mutex<Vec<StorageIssue>>>,
+    newer_files: Arc<Mutex<BTreeSet<String>>>,
+}
+
+impl IssueLog {
+    pub fn record(&self, issue: StorageIssue) {
+        eprintln!(
+            "unreadable state file {}: {} (quarantined at {:?})",
+            issue.file, issue.message, issue.quarantined_path
+        );
+        self.lock().push(issue);
+    }
+
+    /// Reports a file written by a newer app version. It is neither loaded
+    /// nor overwritten for the rest of the run, so it is reported once.
+    pub fn record_newer_version(&self, file: String, error: &io::Error) {
+        let first_report = self
+            .newer_files
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(file.clone());
+        if !first_report {
+            return;
+        }
+
+        eprintln!("state file {file} is from a newer version: {error}");
+        self.lock().push(StorageIssue {
+            file,
+            message: format!(
+                "{error}; it is kept as is and changes are not saved until the app is updated"
+            ),
+            quarantined_path: None,
+            recovered: Vec::new(),
+            read_only: true,
+            detected_at_ms: now_ms(),
+        });
+    }
+
+    pub fn take(&self) -> Vec<StorageIssue> {
+        std::mem::take(&mut *self.lock())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<StorageIssue>> {
+        self.issues
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Moves an unreadable file aside as `<name>.corrupt-<ms>.<ext>` so the next
+/// save cannot overwrite it.
+pub fn quarantine(path: &Path, detected_at_ms: i64) -> io::Result<PathBuf> {
+    let stem = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("state");
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or("json");
+    let target = path.with_file_name(format!(
+        "{stem}.{QUARANTINE_MARKER}-{detected_at_ms}.{extension}"
+    ));
+
+    fs::rename(path, &target)?;
+    Ok(target)
+}
+
+/// Rebuilds as much of an analytics document as possible: every daily entry
+/// and session that still parses is kept, and malformed goals fall back to
+/// defaults.
+pub fn salvage_analytics(document: &Value) -> Option<(AnalyticsState, Vec<String>)> {
+    let object = document.as_object()?;
+    let mut state = AnalyticsState::default();
+    let mut recovered = Vec::new();
+
+    if let Some(daily) = object.get("daily").and_then(Value::as_object) {
+        state.daily = daily
+            .iter()
+            .filter_map(|(day, metrics)| Some((day.clone(), parse::<DailyMetrics>(metrics)?)))
+            .collect();
+        if !state.daily.is_empty() {
+            recovered.push("daily".to_string());
+        }
+    }
+
+    if let Some(goals) = object.get("goals").and_then(parse::<GoalSettings>) {
+        state.goals = goals;
+        recovered.push("goals".to_string());
+    }
+
+    if let Some(sessions) = object.get("sessions").and_then(Value::as_array) {
+        state.sessions = sessions.iter().filter_map(parse::<SessionRecord>).collect();
+        if !state.sessions.is_empty() {
+            recovered.push("sessions".to_string());
+        }
+    }
+
+    (!recovered.is_empty()).then_some((state, recovered))
+}
+
+/// Keeps every top-level field that still deserializes on top of the
+/// defaults, for flat documents such as settings.
+pub fn salvage_fields<T>(document: &Value, defaults: &T) -> Option<(T, Vec<String>)>
+where
+    T: Serialize + DeserializeOwned,
+{
+    let object = document.as_object()?;
+    let mut merged = serde_json::to_value(defaults).ok()?;
+    let mut recovered = Vec::new();
+
+    for (key, value) in object {
+        let fields = merged.as_object_mut()?;
+        if !fields.contains_key(key) {
+            continue;
+        }
+
+        let previous = fields.insert(key.clone(), value.clone());
+        if parse::<T>(&merged).is_some() {
+            recovered.push(key.clone());
+        } else if let (Some(fields), Some(previous)) = (merged.as_object_mut(), previous) {
+            fields.insert(key.clone(), previous);
+        }
+    }
+
+    let value = parse::<T>(&merged)?;
+    (!recovered.is_empty()).then_some((value, recovered))
+}
+
+pub fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+fn parse<T: DeserializeOwned>(value: &Value) -> Option<T> {
+    serde_json::from_value(value.clone()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timer::engine::Settings;
+
+    #[test]
+    fn salvage_analytics_keeps_daily_when_goals_are_malformed() {
+        let document = serde_json::json!({
+            "version": 1,
+            "daily": {
+                "2026-02-14": { "focusCompleted": 3, "longCycleCompleted": 0 },
+                "2026-02-15": { "focusCompleted": "many" }
+            },
+            "goals": { "daily": "eight" }
+        });
+
+        let (state, recovered) = salvage_analytics(&document).unwrap();
+
+        assert_eq!(recovered, vec!["daily"]);
+        assert_eq!(state.daily.len(), 1);
+        assert_eq!(state.daily.get("2026-02-14").unwrap().focus_completed, 3);
+        assert_eq!(
+            state.goals.daily.focus_target,
+            GoalSettings::default().daily.focus_target
+        );
+    }
+
+    #[test]
+    fn salvage_fields_keeps_valid_settings() {
+        let document = serde_json::json!({
+            "focusMs": 600_000,
+            "shortBreakMs": "five minutes",
+            "locale": "zh-CN"
+        });
+
+        let (settings, recovered) = salvage_fields(&document, &Settings::default()).unwrap();
+
+        assert_eq!(settings.focus_ms, 600_000);
+        assert_eq!(settings.short_break_ms, Settings::default().short_break_ms);
+        assert_eq!(settings.locale, "zh-CN");
+        assert_eq!(recovered, vec!["focusMs", "locale"]);
+    }
+
+    #[test]
+    fn quarantine_moves_file_aside_with_timestamp() {
+        let dir = std::env::temp_dir().join(format!(
+            "pomoduo-quarantine-{}-{}",
+            std::process::id(),
+            now_ms()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("settings.json");
+        fs::write(&path, "{not json").unwrap();
+
+        let target = quarantine(&path, 42).unwrap();
+
+        assert!(!path.exists());
+        assert_eq!(target, dir.join("settings.corrupt-42.json"));
+        assert_eq!(fs::read_to_string(&target).unwrap(), "{not json");
+        let _ = fs::remove_dir_all(dir);
+    }
+}
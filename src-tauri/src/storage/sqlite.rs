@@ -0,0 +1,856 @@
+use super::backup::BackupArchive;
+use super::migrate::{self, DocumentKind};
+use super::recovery::{self, IssueLog, StorageIssue};
+use super::state_file::StateFileStore;
+use crate::analytics::model::{
+    AnalyticsState, ClockJump, DailyMetrics, GoalSettings, SessionRecord, ANALYTICS_VERSION,
+};
+use crate::timer::engine::{RuntimeState, Settings};
+use crate::timer::profile::ProfileState;
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Ordered schema upgrades; entry `n` moves a database from `user_version` `n`
+/// to `n + 1`.
+const SCHEMA_MIGRATIONS: &[&str] = &[
+    SCHEMA_V1, SCHEMA_V2, SCHEMA_V3, SCHEMA_V4, SCHEMA_V5, SCHEMA_V6, SCHEMA_V7, SCHEMA_V8,
+];
+
+const SCHEMA_V1: &str = "
+    CREATE TABLE IF NOT EXISTS meta (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS documents (
+        name TEXT PRIMARY KEY,
+        body TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS daily_metrics (
+        day TEXT PRIMARY KEY,
+        focus_completed INTEGER NOT NULL,
+        long_cycle_completed INTEGER NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS sessions (
+        started_at_ms INTEGER PRIMARY KEY,
+        ended_at_ms INTEGER NOT NULL,
+        phase TEXT NOT NULL,
+        outcome TEXT NOT NULL
+    );
+";
+
+const SCHEMA_V2: &str = "
+    ALTER TABLE sessions ADD COLUMN profile_id TEXT;
+";
+
+const SCHEMA_V3: &str = "
+    ALTER TABLE sessions ADD COLUMN clock_jumps TEXT;
+";
+
+const SCHEMA_V4: &str = "
+    ALTER TABLE sessions ADD COLUMN utc_offset_minutes INTEGER;
+";
+
+const SCHEMA_V5: &str = "
+    ALTER TABLE daily_metrics ADD COLUMN focus_minutes INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE sessions ADD COLUMN duration_ms INTEGER;
+";
+
+const SCHEMA_V6: &str = "
+    CREATE TABLE IF NOT EXISTS monthly_rollups (
+        month TEXT PRIMARY KEY,
+        focus_completed INTEGER NOT NULL,
+        long_cycle_completed INTEGER NOT NULL,
+        focus_minutes INTEGER NOT NULL
+    );
+";
+
+const SCHEMA_V7: &str = "
+    ALTER TABLE sessions ADD COLUMN task TEXT;
+    ALTER TABLE sessions ADD COLUMN project TEXT;
+";
+
+const SCHEMA_V8: &str = "
+    ALTER TABLE sessions ADD COLUMN closed_long_cycle INTEGER NOT NULL DEFAULT 0;
+";
+
+const SETTINGS_DOCUMENT: &str = "settings";
+const RUNTIME_STATE_DOCUMENT: &str = "runtime_state";
+const GOALS_DOCUMENT: &str = "goals";
+const PROFILES_DOCUMENT: &str = "profiles";
+const DAY_ZONE_DOCUMENT: &str = "day_zone";
+const ANALYTICS_VERSION_KEY: &str = "analytics_version";
+const DAY_START_HOUR_KEY: &str = "day_start_hour";
+const JSON_MIGRATED_KEY: &str = "json_migrated";
+
+#[derive(Clone)]
+pub struct SqliteStore {
+    connection: Arc<Mutex<Connection>>,
+    issues: IssueLog,
+}
+
+impl SqliteStore {
+    pub fn open(path: &Path, issues: IssueLog) -> io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let connection = Connection::open(path).map_err(sqlite_error)?;
+        connection
+            .execute_batch("PRAGMA journal_mode = WAL; PRAGMA synchronous = FULL;")
+            .map_err(sqlite_error)?;
+        Self::initialize(connection, issues)
+    }
+
+    fn initialize(mut connection: Connection, issues: IssueLog) -> io::Result<Self> {
+        migrate_schema(&mut connection)?;
+
+        Ok(Self {
+            connection: Arc::new(Mutex::new(connection)),
+            issues,
+        })
+    }
+
+    /// Copies the JSON state files into the database the first time it is
+    /// opened. The files themselves are left in place as a backup.
+    pub fn migrate_from_json(&self, json_store: &StateFileStore) -> io::Result<bool> {
+        let mut connection = self.lock();
+        if read_meta(&connection, JSON_MIGRATED_KEY)?.is_some() {
+            return Ok(false);
+        }
+
+        let tx = connection.transaction().map_err(sqlite_error)?;
+        if let Some(settings) = json_store.load_settings()? {
+            put_versioned(&tx, SETTINGS_DOCUMENT, DocumentKind::Settings, &settings)?;
+        }
+        if let Some(runtime_state) = json_store.load_runtime_state()? {
+            put_versioned(
+                &tx,
+                RUNTIME_STATE_DOCUMENT,
+                DocumentKind::RuntimeState,
+                &runtime_state,
+            )?;
+        }
+        if let Some(analytics_state) = json_store.load_analytics_state()? {
+            write_analytics(&tx, &analytics_state)?;
+        }
+        if let Some(profiles) = json_store.load_profiles()? {
+            put_versioned(&tx, PROFILES_DOCUMENT, DocumentKind::Profiles, &profiles)?;
+        }
+        write_meta(&tx, JSON_MIGRATED_KEY, "1")?;
+        tx.commit().map_err(sqlite_error)?;
+
+        Ok(true)
+    }
+
+    pub fn load_settings(&self) -> io::Result<Option<Settings>> {
+        self.load_document(SETTINGS_DOCUMENT, DocumentKind::Settings, |document| {
+            recovery::salvage_fields(document, &Settings::default())
+        })
+    }
+
+    pub fn save_settings(&self, settings: &Settings) -> io::Result<()> {
+        put_versioned(
+            &self.lock(),
+            SETTINGS_DOCUMENT,
+            DocumentKind::Settings,
+            settings,
+        )
+    }
+
+    pub fn load_runtime_state(&self) -> io::Result<Option<RuntimeState>> {
+        self.load_document(RUNTIME_STATE_DOCUMENT, DocumentKind::RuntimeState, |_| None)
+    }
+
+    pub fn save_runtime_state(&self, runtime_state: &RuntimeState) -> io::Result<()> {
+        put_versioned(
+            &self.lock(),
+            RUNTIME_STATE_DOCUMENT,
+            DocumentKind::RuntimeState,
+            runtime_state,
+        )
+    }
+
+    pub fn load_profiles(&self) -> io::Result<Option<ProfileState>> {
+        self.load_document(PROFILES_DOCUMENT, DocumentKind::Profiles, |_| None)
+    }
+
+    pub fn save_profiles(&self, profiles: &ProfileState) -> io::Result<()> {
+        put_versioned(
+            &self.lock(),
+            PROFILES_DOCUMENT,
+            DocumentKind::Profiles,
+            profiles,
+        )
+    }
+
+    pub fn load_analytics_state(&self) -> io::Result<Option<AnalyticsState>> {
+        let connection = self.lock();
+        // Daily rows and sessions live in their own tables, so a malformed
+        // goals document only costs the goals.
+        let goals = match get_document::<GoalSettings>(&connection, GOALS_DOCUMENT) {
+            Ok(Some(goals)) => goals,
+            Ok(None) => return Ok(None),
+            Err(error) if error.kind() == io::ErrorKind::InvalidData => {
+                self.quarantine_document(
+                    &connection,
+                    GOALS_DOCUMENT,
+                    &error,
+                    vec!["daily".to_string(), "sessions".to_string()],
+                )?;
+                let goals = GoalSettings::default();
+                put_document(&connection, GOALS_DOCUMENT, &goals)?;
+                goals
+            }
+            Err(error) => return Err(error),
+        };
+        migrate::ensure_supported(
+            DocumentKind::Analytics,
+            read_analytics_version(&connection)?,
+        )
+        .inspect_err(|error| {
+            self.issues
+                .record_newer_version("analytics (database)".to_string(), error);
+        })?;
+
+        let daily = read_metrics(&connection, DAILY_METRICS)?;
+        let monthly = read_metrics(&connection, MONTHLY_ROLLUPS)?;
+
+        let mut session_query = connection
+            .prepare(
+                "SELECT started_at_ms, ended_at_ms, phase, outcome, profile_id, clock_jumps,
+                        utc_offset_minutes, duration_ms, task, project, closed_long_cycle
+                 FROM sessions ORDER BY started_at_ms",
+            )
+            .map_err(sqlite_error)?;
+        let sessions = session_query
+            .query_map([], |row| {
+                Ok(SessionRecord {
+                    started_at_ms: row.get(0)?,
+                    ended_at_ms: row.get(1)?,
+                    phase: decode_enum(row.get(2)?)?,
+                    outcome: decode_enum(row.get(3)?)?,
+                    profile_id: row.get(4)?,
+                    clock_jumps: decode_clock_jumps(row.get(5)?)?,
+                    utc_offset_minutes: row.get(6)?,
+                    duration_ms: row.get(7)?,
+                    task: row.get(8)?,
+                    project: row.get(9)?,
+                    closed_long_cycle: row.get(10)?,
+                })
+            })
+            .and_then(|rows| rows.collect::<Result<_, _>>())
+            .map_err(sqlite_error)?;
+
+        let day_zone = get_document(&connection, DAY_ZONE_DOCUMENT)?.unwrap_or_default();
+        let day_start_hour = read_meta(&connection, DAY_START_HOUR_KEY)?
+            .and_then(|value| value.parse().ok())
+            .unwrap_or_default();
+
+        Ok(Some(AnalyticsState {
+            version: ANALYTICS_VERSION,
+            daily,
+            monthly,
+            goals,
+            sessions,
+            day_zone,
+            day_start_hour,
+            unmoved_by_day_change: None,
+        }))
+    }
+
+    pub fn save_analytics_state(&self, analytics_state: &AnalyticsState) -> io::Result<()> {
+        let mut connection = self.lock();
+        let tx = connection.transaction().map_err(sqlite_error)?;
+        write_analytics(&tx, analytics_state)?;
+        tx.commit().map_err(sqlite_error)
+    }
+
+    pub fn restore_archive(&self, archive: &BackupArchive) -> io::Result<()> {
+        let mut connection = self.lock();
+        let tx = connection.transaction().map_err(sqlite_error)?;
+
+        if let Some(settings) = archive.settings.as_ref() {
+            put_versioned(&tx, SETTINGS_DOCUMENT, DocumentKind::Settings, settings)?;
+        }
+        if let Some(runtime_state) = archive.runtime_state.as_ref() {
+            put_versioned(
+                &tx,
+                RUNTIME_STATE_DOCUMENT,
+                DocumentKind::RuntimeState,
+                runtime_state,
+            )?;
+        }
+        if let Some(analytics_state) = archive.analytics_state.as_ref() {
+            tx.execute_batch(
+                "DELETE FROM daily_metrics; DELETE FROM monthly_rollups; DELETE FROM sessions;",
+            )
+            .map_err(sqlite_error)?;
+            write_analytics(&tx, analytics_state)?;
+        }
+        if let Some(profiles) = archive.profiles.as_ref() {
+            put_versioned(&tx, PROFILES_DOCUMENT, DocumentKind::Profiles, profiles)?;
+        }
+
+        tx.commit().map_err(sqlite_error)
+    }
+
+    fn load_document<T: Serialize + DeserializeOwned>(
+        &self,
+        name: &str,
+        kind: DocumentKind,
+        salvage: impl Fn(&serde_json::Value) -> Option<(T, Vec<String>)>,
+    ) -> io::Result<Option<T>> {
+        let connection = self.lock();
+        let (document, error) = match get_document::<serde_json::Value>(&connection, name) {
+            Ok(None) => return Ok(None),
+            Ok(Some(document)) => match migrate::decode(kind, document.clone()) {
+                Ok(value) => return Ok(Some(value)),
+                Err(error) if error.kind() == io::ErrorKind::InvalidData => (Some(document), error),
+                Err(error) => {
+                    if error.kind() == io::ErrorKind::Unsupported {
+                        self.issues
+                            .record_newer_version(format!("{name} (database)"), &error);
+                    }
+                    return Err(error);
+                }
+            },
+            Err(error) if error.kind() == io::ErrorKind::InvalidData => (None, error),
+            Err(error) => return Err(error),
+        };
+
+        let salvaged = document.as_ref().and_then(salvage);
+        let recovered = salvaged
+            .as_ref()
+            .map(|(_, sections)| sections.clone())
+            .unwrap_or_default();
+        self.quarantine_document(&connection, name, &error, recovered)?;
+
+        let Some((value, _)) = salvaged else {
+            return Ok(None);
+        };
+        put_versioned(&connection, name, kind, &value)?;
+        Ok(Some(value))
+    }
+
+    /// Renames an unreadable document to `<name>.corrupt-<ms>` so it is kept
+    /// in the database but no longer loaded or overwritten.
+    fn quarantine_document(
+        &self,
+        connection: &Connection,
+        name: &str,
+        error: &io::Error,
+        recovered: Vec<String>,
+    ) -> io::Result<()> {
+        let detected_at_ms = recovery::now_ms();
+        let quarantined_name = format!("{name}.corrupt-{detected_at_ms}");
+        connection
+            .execute(
+                "UPDATE documents SET name = ?2 WHERE name = ?1",
+                params![name, quarantined_name],
+            )
+            .map_err(sqlite_error)?;
+
+        self.issues.record(StorageIssue {
+            file: format!("{name} (database)"),
+            message: error.to_string(),
+            quarantined_path: Some(quarantined_name),
+            recovered,
+            read_only: false,
+            detected_at_ms,
+        });
+
+        Ok(())
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Connection> {
+        self.connection
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Writes only what changed since the last save: metric rows are upserted,
+/// pruned periods are deleted and sessions are reconciled by start time.
+fn write_analytics(tx: &Transaction<'_>, state: &AnalyticsState) -> io::Result<()> {
+    migrate::ensure_supported(DocumentKind::Analytics, read_analytics_version(tx)?)?;
+    put_document(tx, GOALS_DOCUMENT, &state.goals)?;
+    put_document(tx, DAY_ZONE_DOCUMENT, &state.day_zone)?;
+    write_meta(tx, DAY_START_HOUR_KEY, &state.day_start_hour.to_string())?;
+    write_meta(tx, ANALYTICS_VERSION_KEY, &ANALYTICS_VERSION.to_string())?;
+
+    write_metrics(tx, DAILY_METRICS, &state.daily)?;
+    write_metrics(tx, MONTHLY_ROLLUPS, &state.monthly)?;
+
+    write_sessions(tx, &state.sessions)?;
+
+    Ok(())
+}
+
+/// Applies any schema upgrades the database has not seen yet. A database
+/// written by a newer app version is refused rather than modified.
+fn migrate_schema(connection: &mut Connection) -> io::Result<()> {
+    let version: u32 = connection
+        .query_row("PRAGMA user_version", [], |row| row.get(0))
+        .map_err(sqlite_error)?;
+    let current = SCHEMA_MIGRATIONS.len() as u32;
+    if version > current {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("database schema version {version} is newer than supported version {current}"),
+        ));
+    }
+
+    let tx = connection.transaction().map_err(sqlite_error)?;
+    for migration in &SCHEMA_MIGRATIONS[version as usize..] {
+        tx.execute_batch(migration).map_err(sqlite_error)?;
+    }
+    tx.pragma_update(None, "user_version", current)
+        .map_err(sqlite_error)?;
+    tx.commit().map_err(sqlite_error)
+}
+
+/// A table of metrics keyed by a day or month string.
+struct MetricsTable {
+    name: &'static str,
+    key: &'static str,
+}
+
+const DAILY_METRICS: MetricsTable = MetricsTable {
+    name: "daily_metrics",
+    key: "day",
+};
+
+const MONTHLY_ROLLUPS: MetricsTable = MetricsTable {
+    name: "monthly_rollups",
+    key: "month",
+};
+
+fn read_metrics(
+    connection: &Connection,
+    table: MetricsTable,
+) -> io::Result<BTreeMap<String, DailyMetrics>> {
+    let MetricsTable { name, key } = table;
+    let mut query = connection
+        .prepare(&format!(
+            "SELECT {key}, focus_completed, long_cycle_completed, focus_minutes FROM {name}"
+        ))
+        .map_err(sqlite_error)?;
+    let metrics = query
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                DailyMetrics {
+                    focus_completed: row.get(1)?,
+                    long_cycle_completed: row.get(2)?,
+                    focus_minutes: row.get(3)?,
+                },
+            ))
+        })
+        .and_then(|rows| rows.collect::<Result<_, _>>())
+        .map_err(sqlite_error)?;
+    Ok(metrics)
+}
+
+fn write_metrics(
+    tx: &Transaction<'_>,
+    table: MetricsTable,
+    metrics: &BTreeMap<String, DailyMetrics>,
+) -> io::Result<()> {
+    let MetricsTable { name, key } = table;
+    {
+        let mut upsert = tx
+            .prepare_cached(&format!(
+                "INSERT INTO {name}
+                     ({key}, focus_completed, long_cycle_completed, focus_minutes)
+                 VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT({key}) DO UPDATE SET
+                     focus_completed = excluded.focus_completed,
+                     long_cycle_completed = excluded.long_cycle_completed,
+                     focus_minutes = excluded.focus_minutes
+                 WHERE focus_completed != excluded.focus_completed
+                     OR long_cycle_completed != excluded.long_cycle_completed
+                     OR focus_minutes != excluded.focus_minutes"
+            ))
+            .map_err(sqlite_error)?;
+        for (period, metrics) in metrics {
+            upsert
+                .execute(params![
+                    period,
+                    metrics.focus_completed,
+                    metrics.long_cycle_completed,
+                    metrics.focus_minutes
+                ])
+                .map_err(sqlite_error)?;
+        }
+    }
+
+    let stored_periods = {
+        let mut query = tx
+            .prepare_cached(&format!("SELECT {key} FROM {name}"))
+            .map_err(sqlite_error)?;
+        let periods = query
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(sqlite_error)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(sqlite_error)?;
+        periods
+    };
+    for period in stored_periods
+        .iter()
+        .filter(|period| !metrics.contains_key(*period))
+    {
+        tx.execute(
+            &format!("DELETE FROM {name} WHERE {key} = ?1"),
+            params![period],
+        )
+        .map_err(sqlite_error)?;
+    }
+
+    Ok(())
+}
+
+/// Inserts the sessions the table lacks and deletes the rows no longer kept,
+/// matching on `started_at_ms`. Sessions are never edited once recorded.
+fn write_sessions(tx: &Transaction<'_>, sessions: &[SessionRecord]) -> io::Result<()> {
+    let stored: BTreeSet<i64> = {
+        let mut query = tx
+            .prepare_cached("SELECT started_at_ms FROM sessions")
+            .map_err(sqlite_error)?;
+        let stored = query
+            .query_map([], |row| row.get::<_, i64>(0))
+            .map_err(sqlite_error)?
+            .collect::<Result<_, _>>()
+            .map_err(sqlite_error)?;
+        stored
+    };
+    let kept: BTreeSet<i64> = sessions
+        .iter()
+        .map(|session| session.started_at_ms)
+        .collect();
+
+    for started_at_ms in stored.difference(&kept) {
+        tx.execute(
+            "DELETE FROM sessions WHERE started_at_ms = ?1",
+            params![started_at_ms],
+        )
+        .map_err(sqlite_error)?;
+    }
+
+    let mut insert = tx
+        .prepare_cached(
+            "INSERT OR REPLACE INTO sessions
+                 (started_at_ms, ended_at_ms, phase, outcome, profile_id, clock_jumps,
+                  utc_offset_minutes, duration_ms, task, project, closed_long_cycle)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+        )
+        .map_err(sqlite_error)?;
+    for session in sessions
+        .iter()
+        .filter(|session| !stored.contains(&session.started_at_ms))
+    {
+        insert
+            .execute(params![
+                session.started_at_ms,
+                session.ended_at_ms,
+                encode_enum(&session.phase)?,
+                encode_enum(&session.outcome)?,
+                session.profile_id,
+                encode_clock_jumps(&session.clock_jumps)?,
+                session.utc_offset_minutes,
+                session.duration_ms,
+                session.task,
+                session.project,
+                session.closed_long_cycle
+            ])
+            .map_err(sqlite_error)?;
+    }
+
+    Ok(())
+}
+
+fn read_analytics_version(connection: &Connection) -> io::Result<u32> {
+    match read_meta(connection, ANALYTICS_VERSION_KEY)? {
+        Some(value) => value.parse().map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid analytics version: {value}"),
+            )
+        }),
+        None => Ok(ANALYTICS_VERSION),
+    }
+}
+
+fn put_versioned<T: Serialize>(
+    connection: &Connection,
+    name: &str,
+    kind: DocumentKind,
+    value: &T,
+) -> io::Result<()> {
+    if let Ok(Some(existing)) = get_document::<serde_json::Value>(connection, name) {
+        migrate::ensure_writable(kind, &existing)?;
+    }
+
+    put_document(connection, name, &migrate::encode(kind, value)?)
+}
+
+fn get_document<T: DeserializeOwned>(connection: &Connection, name: &str) -> io::Result<Option<T>> {
+    let body: Option<String> = connection
+        .query_row(
+            "SELECT body FROM documents WHERE name = ?1",
+            params![name],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(sqlite_error)?;
+
+    body.map(|body| {
+        serde_json::from_str(&body)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+    })
+    .transpose()
+}
+
+fn put_document<T: Serialize>(connection: &Connection, name: &str, value: &T) -> io::Result<()> {
+    let body = serde_json::to_string(value)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+    connection
+        .execute(
+            "INSERT INTO documents (name, body) VALUES (?1, ?2)
+             ON CONFLICT(name) DO UPDATE SET body = excluded.body",
+            params![name, body],
+        )
+        .map_err(sqlite_error)?;
+    Ok(())
+}
+
+fn read_meta(connection: &Connection, key: &str) -> io::Result<Option<String>> {
+    connection
+        .query_row(
+            "SELECT value FROM meta WHERE key = ?1",
+            params![key],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(sqlite_error)
+}
+
+fn write_meta(connection: &Connection, key: &str, value: &str) -> io::Result<()> {
+    connection
+        .execute(
+            "INSERT INTO meta (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![key, value],
+        )
+        .map_err(sqlite_error)?;
+    Ok(())
+}
+
+fn encode_enum<T: Serialize>(value: &T) -> io::Result<String> {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(text)) => Ok(text),
+        Ok(other) => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("expected a string value, got {other}"),
+        )),
+        Err(error) => Err(io::Error::new(io::ErrorKind::InvalidData, error)),
+    }
+}
+
+fn decode_enum<T: DeserializeOwned>(text: String) -> rusqlite::Result<T> {
+    serde_json::from_value(serde_json::Value::String(text)).map_err(|error| {
+        rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(error))
+    })
+}
+
+/// Clock jumps are rare, so they are kept as a JSON array next to the session
+/// and left `NULL` when there are none.
+fn encode_clock_jumps(clock_jumps: &[ClockJump]) -> io::Result<Option<String>> {
+    if clock_jumps.is_empty() {
+        return Ok(None);
+    }
+
+    serde_json::to_string(clock_jumps)
+        .map(Some)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+}
+
+fn decode_clock_jumps(text: Option<String>) -> rusqlite::Result<Vec<ClockJump>> {
+    let Some(text) = text else {
+        return Ok(Vec::new());
+    };
+
+    serde_json::from_str(&text).map_err(|error| {
+        rusqlite::Error::FromSqlConversionFailure(5, rusqlite::types::Type::Text, Box::new(error))
+    })
+}
+
+fn sqlite_error(error: rusqlite::Error) -> io::Error {
+    io::Error::other(error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytics::model::SessionOutcome;
+    use crate::timer::engine::{ClockJumpPolicy, Phase};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn memory_store() -> SqliteStore {
+        SqliteStore::initialize(Connection::open_in_memory().unwrap(), IssueLog::default()).unwrap()
+    }
+
+    fn session(started_at_ms: i64) -> SessionRecord {
+        SessionRecord {
+            phase: Phase::Focus,
+            started_at_ms,
+            ended_at_ms: started_at_ms + 25 * 60_000,
+            outcome: SessionOutcome::Completed,
+            profile_id: Some("default".to_string()),
+            clock_jumps: Vec::new(),
+            utc_offset_minutes: Some(60),
+            duration_ms: Some(25 * 60_000),
+            task: None,
+            project: None,
+            closed_long_cycle: false,
+        }
+    }
+
+    fn metrics(focus_completed: u32) -> DailyMetrics {
+        DailyMetrics {
+            focus_completed,
+            long_cycle_completed: 0,
+            focus_minutes: 0,
+        }
+    }
+
+    #[test]
+    fn analytics_state_round_trips_through_incremental_saves() {
+        let store = memory_store();
+        assert!(store.load_analytics_state().unwrap().is_none());
+
+        let mut state = AnalyticsState::default();
+        state.daily.insert("2026-02-14".to_string(), metrics(3));
+        state.daily.insert("2026-02-15".to_string(), metrics(1));
+        state.sessions.push(session(1_000));
+        state.sessions.push(session(2_000));
+        store.save_analytics_state(&state).unwrap();
+
+        state.daily.remove("2026-02-14");
+        state.monthly.insert("2026-02".to_string(), metrics(3));
+        state.daily.insert("2026-02-15".to_string(), metrics(2));
+        state.sessions.remove(0);
+        let mut interrupted = session(3_000);
+        interrupted.task = Some("Review".to_string());
+        interrupted.clock_jumps.push(ClockJump {
+            detected_at_ms: 3_500,
+            jump_ms: 60_000,
+            policy: ClockJumpPolicy::ExtendBySuspension,
+        });
+        state.sessions.push(interrupted);
+        state.goals.daily.focus_target = 10;
+        store.save_analytics_state(&state).unwrap();
+
+        // An imported older session lands before the newest stored one.
+        state.sessions.insert(0, session(1_500));
+        store.save_analytics_state(&state).unwrap();
+        state.sessions.remove(1);
+        store.save_analytics_state(&state).unwrap();
+
+        let loaded = store.load_analytics_state().unwrap().unwrap();
+        assert_eq!(loaded.daily.len(), 1);
+        assert_eq!(loaded.daily.get("2026-02-15").unwrap().focus_completed, 2);
+        assert_eq!(loaded.monthly["2026-02"].focus_completed, 3);
+        assert_eq!(
+            loaded
+                .sessions
+                .iter()
+                .map(|session| session.started_at_ms)
+                .collect::<Vec<_>>(),
+            vec![1_500, 3_000]
+        );
+        assert_eq!(loaded.sessions[0].profile_id.as_deref(), Some("default"));
+        assert!(loaded.sessions[0].clock_jumps.is_empty());
+        assert_eq!(loaded.sessions[0].utc_offset_minutes, Some(60));
+        assert_eq!(loaded.sessions[1].task.as_deref(), Some("Review"));
+        assert_eq!(loaded.sessions[1].clock_jumps[0].jump_ms, 60_000);
+        assert_eq!(loaded.goals.daily.focus_target, 10);
+    }
+
+    #[test]
+    fn migrate_from_json_runs_only_once() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let base_dir =
+            std::env::temp_dir().join(format!("pomoduo-migrate-{}-{nanos}", std::process::id()));
+        let json_store = StateFileStore::with_issue_log(base_dir.clone(), IssueLog::default());
+        let mut analytics = AnalyticsState::default();
+        analytics.daily.insert("2026-02-15".to_string(), metrics(4));
+        json_store.save_analytics_state(&analytics).unwrap();
+        json_store.save_settings(&Settings::default()).unwrap();
+
+        let store = memory_store();
+        assert!(store.migrate_from_json(&json_store).unwrap());
+        assert!(!store.migrate_from_json(&json_store).unwrap());
+
+        let loaded = store.load_analytics_state().unwrap().unwrap();
+        assert_eq!(loaded.daily.get("2026-02-15").unwrap().focus_completed, 4);
+        assert!(store.load_settings().unwrap().is_some());
+        assert!(json_store.load_analytics_state().unwrap().is_some());
+        let _ = fs::remove_dir_all(base_dir);
+    }
+
+    #[test]
+    fn newer_schema_and_documents_are_refused() {
+        let connection = Connection::open_in_memory().unwrap();
+        connection
+            .pragma_update(None, "user_version", SCHEMA_MIGRATIONS.len() as u32 + 1)
+            .unwrap();
+        assert_eq!(
+            SqliteStore::initialize(connection, IssueLog::default())
+                .err()
+                .unwrap()
+                .kind(),
+            io::ErrorKind::Unsupported
+        );
+
+        let store = memory_store();
+        put_document(
+            &store.lock(),
+            SETTINGS_DOCUMENT,
+            &serde_json::json!({ "version": migrate::SETTINGS_VERSION + 1 }),
+        )
+        .unwrap();
+        assert!(store.load_settings().is_err());
+        assert!(store.load_settings().is_err());
+        assert!(store.save_settings(&Settings::default()).is_err());
+        let issues = store.issues.take();
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].read_only);
+    }
+
+    #[test]
+    fn malformed_goals_keep_daily_rows_and_are_reported() {
+        let store = memory_store();
+        let mut state = AnalyticsState::default();
+        state.daily.insert("2026-02-14".to_string(), metrics(3));
+        store.save_analytics_state(&state).unwrap();
+        put_document(&store.lock(), GOALS_DOCUMENT, &"not goals").unwrap();
+
+        let loaded = store.load_analytics_state().unwrap().unwrap();
+
+        assert_eq!(loaded.daily.get("2026-02-14").unwrap().focus_completed, 3);
+        let issues = store.issues.take();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].recovered, vec!["daily", "sessions"]);
+        assert!(store.load_analytics_state().unwrap().is_some());
+        assert!(store.issues.take().is_empty());
+    }
+}
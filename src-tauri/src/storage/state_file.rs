@@ -1,97 +1,204 @@
+use super::durable;
+use super::migrate::{self, DocumentKind};
+use super::recovery::{self, IssueLog, StorageIssue};
 use crate::analytics::model::AnalyticsState;
 use crate::timer::engine::{RuntimeState, Settings};
+use crate::timer::profile::ProfileState;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
+use serde_json::Value;
 use std::fs;
 use std::io;
-use std::path::PathBuf;
-use tauri::{AppHandle, Manager};
+use std::path::{Path, PathBuf};
 
 #[derive(Clone)]
 pub struct StateFileStore {
     base_dir: PathBuf,
+    issues: IssueLog,
 }
 
 impl StateFileStore {
-    pub fn new(app: &AppHandle) -> Self {
-        let base_dir = app
-            .path()
-            .app_data_dir()
-            .unwrap_or_else(|_| default_base_dir())
-            .join("pomoduo");
-
-        Self { base_dir }
+    pub fn with_issue_log(base_dir: PathBuf, issues: IssueLog) -> Self {
+        Self { base_dir, issues }
     }
 
-    pub fn load_settings(&self) -> Option<Settings> {
-        self.load_json::<Settings>(self.settings_file())
+    pub fn load_settings(&self) -> io::Result<Option<Settings>> {
+        self.load_json(DocumentKind::Settings, self.settings_file(), |document| {
+            recovery::salvage_fields(document, &Settings::default())
+        })
     }
 
     pub fn save_settings(&self, settings: &Settings) -> io::Result<()> {
-        self.save_json(self.settings_file(), settings)
+        self.save_json(DocumentKind::Settings, self.settings_file(), settings)
     }
 
-    pub fn load_runtime_state(&self) -> Option<RuntimeState> {
-        self.load_json::<RuntimeState>(self.runtime_state_file())
+    pub fn load_runtime_state(&self) -> io::Result<Option<RuntimeState>> {
+        self.load_json(
+            DocumentKind::RuntimeState,
+            self.runtime_state_file(),
+            |_| None,
+        )
     }
 
     pub fn save_runtime_state(&self, runtime_state: &RuntimeState) -> io::Result<()> {
-        self.save_json(self.runtime_state_file(), runtime_state)
+        self.save_json(
+            DocumentKind::RuntimeState,
+            self.runtime_state_file(),
+            runtime_state,
+        )
     }
 
-    pub fn load_analytics_state(&self) -> Option<AnalyticsState> {
-        self.load_json::<AnalyticsState>(self.analytics_state_file())
+    pub fn load_analytics_state(&self) -> io::Result<Option<AnalyticsState>> {
+        self.load_json(
+            DocumentKind::Analytics,
+            self.analytics_state_file(),
+            recovery::salvage_analytics,
+        )
     }
 
     pub fn save_analytics_state(&self, analytics_state: &AnalyticsState) -> io::Result<()> {
-        self.save_json(self.analytics_state_file(), analytics_state)
+        self.save_json(
+            DocumentKind::Analytics,
+            self.analytics_state_file(),
+            analytics_state,
+        )
     }
 
-    fn load_json<T: DeserializeOwned>(&self, path: PathBuf) -> Option<T> {
-        if !path.exists() {
-            return None;
-        }
+    pub fn load_profiles(&self) -> io::Result<Option<ProfileState>> {
+        self.load_json(DocumentKind::Profiles, self.profiles_file(), |_| None)
+    }
+
+    pub fn save_profiles(&self, profiles: &ProfileState) -> io::Result<()> {
+        self.save_json(DocumentKind::Profiles, self.profiles_file(), profiles)
+    }
 
-        let text = fs::read_to_string(path).ok()?;
-        serde_json::from_str::<T>(&text).ok()
+    /// Reads the settings file as-is, without quarantining it when it is
+    /// unreadable. Used for external edits that may still be in progress.
+    pub fn peek_settings(&self) -> io::Result<Option<Settings>> {
+        read_json_value(&self.settings_file())?
+            .map(|document| migrate::decode(DocumentKind::Settings, document))
+            .transpose()
     }
 
-    fn save_json<T: Serialize>(&self, path: PathBuf, value: &T) -> io::Result<()> {
-        fs::create_dir_all(&self.base_dir)?;
-        let bytes = serde_json::to_vec_pretty(value)
-            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+    pub fn peek_analytics_state(&self) -> io::Result<Option<AnalyticsState>> {
+        read_json_value(&self.analytics_state_file())?
+            .map(|document| migrate::decode(DocumentKind::Analytics, document))
+            .transpose()
+    }
 
-        let tmp_path = path.with_extension("tmp");
-        fs::write(&tmp_path, bytes)?;
+    /// Unreadable files are moved aside and reported instead of being treated
+    /// as missing, so the defaults saved afterwards cannot overwrite them.
+    fn load_json<T: Serialize + DeserializeOwned>(
+        &self,
+        kind: DocumentKind,
+        path: PathBuf,
+        salvage: impl Fn(&Value) -> Option<(T, Vec<String>)>,
+    ) -> io::Result<Option<T>> {
+        let (document, error) = match read_json_value(&path) {
+            Ok(None) => return Ok(None),
+            Ok(Some(document)) => match migrate::decode(kind, document.clone()) {
+                Ok(value) => return Ok(Some(value)),
+                Err(error) if error.kind() == io::ErrorKind::InvalidData => (Some(document), error),
+                Err(error) => {
+                    if error.kind() == io::ErrorKind::Unsupported {
+                        self.issues.record_newer_version(file_name(&path), &error);
+                    }
+                    return Err(error);
+                }
+            },
+            Err(error) if error.kind() == io::ErrorKind::InvalidData => (None, error),
+            Err(error) => return Err(error),
+        };
+
+        let salvaged = document.as_ref().and_then(salvage);
+        let recovered = salvaged
+            .as_ref()
+            .map(|(_, sections)| sections.clone())
+            .unwrap_or_default();
+        self.quarantine(&path, &error, recovered)?;
+
+        let Some((value, _)) = salvaged else {
+            return Ok(None);
+        };
+        // Later loads must see the salvaged state, not an empty slot.
+        write_json_file(&path, &migrate::encode(kind, &value)?)?;
+        Ok(Some(value))
+    }
 
-        if let Err(rename_error) = fs::rename(&tmp_path, &path) {
-            if path.exists() {
-                fs::remove_file(&path)?;
-                fs::rename(&tmp_path, &path)?;
-            } else {
-                let _ = fs::remove_file(&tmp_path);
-                return Err(rename_error);
+    fn save_json<T: Serialize>(
+        &self,
+        kind: DocumentKind,
+        path: PathBuf,
+        value: &T,
+    ) -> io::Result<()> {
+        // Files from a newer version are never replaced; unreadable ones are
+        // moved aside first.
+        match read_json_value(&path) {
+            Ok(Some(existing)) => migrate::ensure_writable(kind, &existing)?,
+            Ok(None) => {}
+            Err(error) if error.kind() == io::ErrorKind::InvalidData => {
+                self.quarantine(&path, &error, Vec::new())?;
             }
+            Err(error) => return Err(error),
         }
 
+        write_json_file(&path, &migrate::encode(kind, value)?)
+    }
+
+    fn quarantine(&self, path: &Path, error: &io::Error, recovered: Vec<String>) -> io::Result<()> {
+        let detected_at_ms = recovery::now_ms();
+        let quarantined_path = recovery::quarantine(path, detected_at_ms)?;
+
+        self.issues.record(StorageIssue {
+            file: file_name(path),
+            message: error.to_string(),
+            quarantined_path: Some(quarantined_path.to_string_lossy().into_owned()),
+            recovered,
+            read_only: false,
+            detected_at_ms,
+        });
+
         Ok(())
     }
 
-    fn settings_file(&self) -> PathBuf {
+    pub(super) fn settings_file(&self) -> PathBuf {
         self.base_dir.join("settings.json")
     }
 
-    fn runtime_state_file(&self) -> PathBuf {
+    pub(super) fn runtime_state_file(&self) -> PathBuf {
         self.base_dir.join("runtime_state.json")
     }
 
-    fn analytics_state_file(&self) -> PathBuf {
+    pub(super) fn analytics_state_file(&self) -> PathBuf {
         self.base_dir.join("analytics_state.json")
     }
+
+    pub(super) fn profiles_file(&self) -> PathBuf {
+        self.base_dir.join("profiles.json")
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
 }
 
-fn default_base_dir() -> PathBuf {
-    std::env::current_dir()
-        .unwrap_or_else(|_| PathBuf::from("."))
-        .join(".pomoduo")
+fn read_json_value(path: &Path) -> io::Result<Option<Value>> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(error),
+    };
+
+    serde_json::from_str(&text)
+        .map(Some)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+}
+
+pub(super) fn write_json_file<T: Serialize>(path: &Path, value: &T) -> io::Result<()> {
+    let bytes = serde_json::to_vec_pretty(value)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+
+    durable::write_atomic(path, &bytes)
 }
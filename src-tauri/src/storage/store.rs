@@ -0,0 +1,434 @@
+use super::backup::{self, BackupArchive};
+use super::recovery::{self, IssueLog, StorageIssue};
+use super::sqlite::SqliteStore;
+use super::state_file::{write_json_file, StateFileStore};
+use crate::analytics::model::AnalyticsState;
+use crate::timer::engine::{RuntimeState, Settings};
+use crate::timer::profile::ProfileState;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use tauri::{AppHandle, Manager};
+
+const STORAGE_MARKER_FILE: &str = "storage.json";
+const SQLITE_DATABASE_FILE: &str = "pomoduo.db";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum StorageBackend {
+    #[default]
+    Json,
+    Sqlite,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StorageMarker {
+    backend: StorageBackend,
+}
+
+/// Persistence used by the timer engine. `StateStore` is the on-disk
+/// implementation; tests drive the engine against an in-memory one.
+pub trait StateStorage: Send + Sync {
+    fn load_settings(&self) -> io::Result<Option<Settings>>;
+    fn save_settings(&self, settings: &Settings) -> io::Result<()>;
+    fn load_runtime_state(&self) -> io::Result<Option<RuntimeState>>;
+    fn save_runtime_state(&self, runtime_state: &RuntimeState) -> io::Result<()>;
+    fn load_analytics_state(&self) -> io::Result<Option<AnalyticsState>>;
+    fn save_analytics_state(&self, analytics_state: &AnalyticsState) -> io::Result<()>;
+    fn load_profiles(&self) -> io::Result<Option<ProfileState>>;
+    fn save_profiles(&self, profiles: &ProfileState) -> io::Result<()>;
+
+    fn backend(&self) -> StorageBackend;
+
+    /// Copies the given state into the requested backend and makes it the
+    /// active one.
+    fn switch_backend(
+        &self,
+        backend: StorageBackend,
+        settings: &Settings,
+        runtime_state: &RuntimeState,
+        analytics_state: &AnalyticsState,
+    ) -> io::Result<()>;
+
+    fn create_backup(&self, path: &Path, created_at_ms: i64) -> io::Result<()> {
+        backup::write_backup(path, &BackupArchive::capture(self, created_at_ms)?)
+    }
+
+    fn restore_backup(&self, archive: &BackupArchive, _now_ms: i64) -> io::Result<()> {
+        archive.validate()?;
+        if let Some(settings) = archive.settings.as_ref() {
+            self.save_settings(settings)?;
+        }
+        if let Some(runtime_state) = archive.runtime_state.as_ref() {
+            self.save_runtime_state(runtime_state)?;
+        }
+        if let Some(analytics_state) = archive.analytics_state.as_ref() {
+            self.save_analytics_state(analytics_state)?;
+        }
+        if let Some(profiles) = archive.profiles.as_ref() {
+            self.save_profiles(profiles)?;
+        }
+        Ok(())
+    }
+
+    fn ensure_daily_backup(
+        &self,
+        _today: NaiveDate,
+        _keep: u32,
+        _created_at_ms: i64,
+    ) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Unreadable files found since the last call.
+    fn take_issues(&self) -> Vec<StorageIssue> {
+        Vec::new()
+    }
+}
+
+#[derive(Clone)]
+enum Backend {
+    File(StateFileStore),
+    Sqlite(SqliteStore),
+    /// The chosen database could not be opened. The leftover JSON files are
+    /// read so the app can start, and every write is refused so nothing is
+    /// saved where the database will not see it.
+    Unavailable(StateFileStore),
+}
+
+#[derive(Clone)]
+pub struct StateStore {
+    base_dir: PathBuf,
+    backend: Arc<RwLock<Backend>>,
+    issues: IssueLog,
+}
+
+impl StateStore {
+    pub fn data_dir(app: &AppHandle) -> PathBuf {
+        app.path()
+            .app_data_dir()
+            .unwrap_or_else(|_| default_base_dir())
+            .join("pomoduo")
+    }
+
+    pub fn from_base_dir(base_dir: PathBuf) -> Self {
+        let issues = IssueLog::default();
+        let requested = read_marker(&base_dir).unwrap_or_default();
+        let backend = open_backend(&base_dir, requested, &issues).unwrap_or_else(|error| {
+            issues.record(StorageIssue {
+                file: SQLITE_DATABASE_FILE.to_string(),
+                message: error.to_string(),
+                quarantined_path: None,
+                recovered: Vec::new(),
+                read_only: true,
+                detected_at_ms: recovery::now_ms(),
+            });
+            Backend::Unavailable(StateFileStore::with_issue_log(
+                base_dir.clone(),
+                issues.clone(),
+            ))
+        });
+
+        Self {
+            base_dir,
+            backend: Arc::new(RwLock::new(backend)),
+            issues,
+        }
+    }
+
+    #[cfg(test)]
+    pub fn base_dir(&self) -> &Path {
+        &self.base_dir
+    }
+
+    fn current(&self) -> Backend {
+        self.backend
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+}
+
+impl StateStorage for StateStore {
+    fn backend(&self) -> StorageBackend {
+        match self.current() {
+            Backend::File(_) => StorageBackend::Json,
+            Backend::Sqlite(_) | Backend::Unavailable(_) => StorageBackend::Sqlite,
+        }
+    }
+
+    /// The previous backend's data is left untouched. Profiles are carried
+    /// over from the current backend.
+    fn switch_backend(
+        &self,
+        backend: StorageBackend,
+        settings: &Settings,
+        runtime_state: &RuntimeState,
+        analytics_state: &AnalyticsState,
+    ) -> io::Result<()> {
+        if backend == self.backend() {
+            return Ok(());
+        }
+
+        let next = open_backend(&self.base_dir, backend, &self.issues)?;
+        save_settings_to(&next, settings)?;
+        save_runtime_state_to(&next, runtime_state)?;
+        save_analytics_state_to(&next, analytics_state)?;
+        if let Some(profiles) = self.load_profiles()? {
+            save_profiles_to(&next, &profiles)?;
+        }
+        write_json_file(
+            &self.base_dir.join(STORAGE_MARKER_FILE),
+            &StorageMarker { backend },
+        )?;
+
+        let mut active = self
+            .backend
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        *active = next;
+
+        Ok(())
+    }
+
+    fn load_settings(&self) -> io::Result<Option<Settings>> {
+        match self.current() {
+            Backend::File(store) | Backend::Unavailable(store) => store.load_settings(),
+            Backend::Sqlite(store) => store.load_settings(),
+        }
+    }
+
+    fn save_settings(&self, settings: &Settings) -> io::Result<()> {
+        save_settings_to(&self.current(), settings)
+    }
+
+    fn load_runtime_state(&self) -> io::Result<Option<RuntimeState>> {
+        match self.current() {
+            Backend::File(store) | Backend::Unavailable(store) => store.load_runtime_state(),
+            Backend::Sqlite(store) => store.load_runtime_state(),
+        }
+    }
+
+    fn save_runtime_state(&self, runtime_state: &RuntimeState) -> io::Result<()> {
+        save_runtime_state_to(&self.current(), runtime_state)
+    }
+
+    fn load_analytics_state(&self) -> io::Result<Option<AnalyticsState>> {
+        match self.current() {
+            Backend::File(store) | Backend::Unavailable(store) => store.load_analytics_state(),
+            Backend::Sqlite(store) => store.load_analytics_state(),
+        }
+    }
+
+    fn save_analytics_state(&self, analytics_state: &AnalyticsState) -> io::Result<()> {
+        save_analytics_state_to(&self.current(), analytics_state)
+    }
+
+    fn load_profiles(&self) -> io::Result<Option<ProfileState>> {
+        match self.current() {
+            Backend::File(store) | Backend::Unavailable(store) => store.load_profiles(),
+            Backend::Sqlite(store) => store.load_profiles(),
+        }
+    }
+
+    fn save_profiles(&self, profiles: &ProfileState) -> io::Result<()> {
+        save_profiles_to(&self.current(), profiles)
+    }
+
+    /// The current state is kept as a pre-restore backup in case the restored
+    /// archive turns out to be wrong; without one nothing is restored.
+    fn restore_backup(&self, archive: &BackupArchive, now_ms: i64) -> io::Result<()> {
+        archive.validate()?;
+        backup::write_pre_restore_backup(self, &self.base_dir, now_ms)?;
+
+        match self.current() {
+            Backend::File(store) => store.restore_archive(archive),
+            Backend::Sqlite(store) => store.restore_archive(archive),
+            Backend::Unavailable(_) => Err(read_only_error()),
+        }
+    }
+
+    fn ensure_daily_backup(
+        &self,
+        today: NaiveDate,
+        keep: u32,
+        created_at_ms: i64,
+    ) -> io::Result<()> {
+        backup::ensure_daily_backup(self, &self.base_dir, today, keep, created_at_ms)
+    }
+
+    fn take_issues(&self) -> Vec<StorageIssue> {
+        self.issues.take()
+    }
+}
+
+fn open_backend(
+    base_dir: &Path,
+    backend: StorageBackend,
+    issues: &IssueLog,
+) -> io::Result<Backend> {
+    let file_store = StateFileStore::with_issue_log(base_dir.to_path_buf(), issues.clone());
+    match backend {
+        StorageBackend::Json => Ok(Backend::File(file_store)),
+        StorageBackend::Sqlite => {
+            let store = SqliteStore::open(&base_dir.join(SQLITE_DATABASE_FILE), issues.clone())?;
+            store.migrate_from_json(&file_store)?;
+            Ok(Backend::Sqlite(store))
+        }
+    }
+}
+
+fn save_settings_to(backend: &Backend, settings: &Settings) -> io::Result<()> {
+    match backend {
+        Backend::File(store) => store.save_settings(settings),
+        Backend::Sqlite(store) => store.save_settings(settings),
+        Backend::Unavailable(_) => Err(read_only_error()),
+    }
+}
+
+fn save_runtime_state_to(backend: &Backend, runtime_state: &RuntimeState) -> io::Result<()> {
+    match backend {
+        Backend::File(store) => store.save_runtime_state(runtime_state),
+        Backend::Sqlite(store) => store.save_runtime_state(runtime_state),
+        Backend::Unavailable(_) => Err(read_only_error()),
+    }
+}
+
+fn save_analytics_state_to(backend: &Backend, analytics_state: &AnalyticsState) -> io::Result<()> {
+    match backend {
+        Backend::File(store) => store.save_analytics_state(analytics_state),
+        Backend::Sqlite(store) => store.save_analytics_state(analytics_state),
+        Backend::Unavailable(_) => Err(read_only_error()),
+    }
+}
+
+fn save_profiles_to(backend: &Backend, profiles: &ProfileState) -> io::Result<()> {
+    match backend {
+        Backend::File(store) => store.save_profiles(profiles),
+        Backend::Sqlite(store) => store.save_profiles(profiles),
+        Backend::Unavailable(_) => Err(read_only_error()),
+    }
+}
+
+fn read_only_error() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::ReadOnlyFilesystem,
+        format!("{SQLITE_DATABASE_FILE} could not be opened; changes are not saved"),
+    )
+}
+
+fn read_marker(base_dir: &Path) -> Option<StorageBackend> {
+    let text = fs::read_to_string(base_dir.join(STORAGE_MARKER_FILE)).ok()?;
+    serde_json::from_str::<StorageMarker>(&text)
+        .ok()
+        .map(|marker| marker.backend)
+}
+
+fn default_base_dir() -> PathBuf {
+    std::env::current_dir()
+        .unwrap_or_else(|_| PathBuf::from("."))
+        .join(".pomoduo")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn switch_backend_copies_state_and_persists_choice() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let base_dir =
+            std::env::temp_dir().join(format!("pomoduo-switch-{}-{nanos}", std::process::id()));
+        let store = StateStore::from_base_dir(base_dir.clone());
+        let settings = Settings::default();
+        let runtime_state = RuntimeState {
+            phase: crate::timer::engine::Phase::Focus,
+            is_running: false,
+            cycle_count: 2,
+            end_at_ms: None,
+            remaining_ms: settings.focus_ms,
+            started_at_ms: None,
+            clock_jumps: Vec::new(),
+            labels: Default::default(),
+        };
+        let mut analytics_state = AnalyticsState::default();
+        analytics_state.goals.weekly.focus_target = 33;
+
+        assert_eq!(store.backend(), StorageBackend::Json);
+        store
+            .switch_backend(
+                StorageBackend::Sqlite,
+                &settings,
+                &runtime_state,
+                &analytics_state,
+            )
+            .unwrap();
+
+        let reopened = StateStore::from_base_dir(base_dir.clone());
+        assert_eq!(reopened.backend(), StorageBackend::Sqlite);
+        assert_eq!(
+            reopened.load_runtime_state().unwrap().unwrap().cycle_count,
+            2
+        );
+        assert_eq!(
+            reopened
+                .load_analytics_state()
+                .unwrap()
+                .unwrap()
+                .goals
+                .weekly
+                .focus_target,
+            33
+        );
+        let _ = fs::remove_dir_all(base_dir);
+    }
+
+    #[test]
+    fn unopenable_database_is_reported_and_refuses_writes() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let base_dir =
+            std::env::temp_dir().join(format!("pomoduo-unopenable-{}-{nanos}", std::process::id()));
+        let leftover = StateFileStore::with_issue_log(base_dir.clone(), IssueLog::default());
+        leftover.save_settings(&Settings::default()).unwrap();
+        // A directory where the database file should be cannot be opened.
+        fs::create_dir_all(base_dir.join(SQLITE_DATABASE_FILE)).unwrap();
+        write_json_file(
+            &base_dir.join(STORAGE_MARKER_FILE),
+            &StorageMarker {
+                backend: StorageBackend::Sqlite,
+            },
+        )
+        .unwrap();
+
+        let store = StateStore::from_base_dir(base_dir.clone());
+        let issues = store.take_issues();
+
+        assert_eq!(store.backend(), StorageBackend::Sqlite);
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].read_only);
+        assert!(store.load_settings().unwrap().is_some());
+        let error = store
+            .save_settings(&Settings {
+                focus_ms: 1_000,
+                ..Settings::default()
+            })
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::ReadOnlyFilesystem);
+        assert_eq!(
+            leftover.load_settings().unwrap().unwrap().focus_ms,
+            Settings::default().focus_ms
+        );
+        let _ = fs::remove_dir_all(base_dir);
+    }
+}
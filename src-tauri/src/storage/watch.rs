@@ -0,0 +1,168 @@
+use super::recovery::IssueLog;
+use super::state_file::StateFileStore;
+use crate::analytics::model::GoalSettings;
+use crate::timer::engine::Settings;
+use notify::event::ModifyKind;
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use std::collections::BTreeSet;
+use std::io;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+const SETTINGS_FILE: &str = "settings.json";
+const ANALYTICS_STATE_FILE: &str = "analytics_state.json";
+
+/// Editors often save in several steps (truncate, write, rename); events are
+/// collected until the directory has been quiet this long.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum WatchedFile {
+    Settings,
+    Analytics,
+}
+
+impl WatchedFile {
+    pub fn file_name(self) -> &'static str {
+        match self {
+            Self::Settings => SETTINGS_FILE,
+            Self::Analytics => ANALYTICS_STATE_FILE,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ExternalChange {
+    Settings(io::Result<Settings>),
+    Goals(io::Result<GoalSettings>),
+}
+
+/// Watches the data directory and reports the current content of every state
+/// file that changed on disk. Our own writes are reported too; callers are
+/// expected to ignore content that matches what they already have.
+pub fn spawn_state_file_watcher(
+    base_dir: PathBuf,
+    on_change: impl Fn(ExternalChange) + Send + 'static,
+) -> io::Result<()> {
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender).map_err(io::Error::other)?;
+    watcher
+        .watch(&base_dir, RecursiveMode::NonRecursive)
+        .map_err(io::Error::other)?;
+
+    let files = StateFileStore::with_issue_log(base_dir, IssueLog::default());
+    thread::spawn(move || {
+        // The watcher stops when dropped, so it lives as long as this thread.
+        let _watcher = watcher;
+
+        while let Ok(event) = receiver.recv() {
+            let mut changed = changed_files(event);
+            loop {
+                match receiver.recv_timeout(DEBOUNCE) {
+                    Ok(event) => changed.extend(changed_files(event)),
+                    Err(RecvTimeoutError::Timeout) => break,
+                    Err(RecvTimeoutError::Disconnected) => return,
+                }
+            }
+
+            for file in changed {
+                let change = match file {
+                    WatchedFile::Settings => files
+                        .peek_settings()
+                        .transpose()
+                        .map(ExternalChange::Settings),
+                    WatchedFile::Analytics => files
+                        .peek_analytics_state()
+                        .transpose()
+                        .map(|state| ExternalChange::Goals(state.map(|state| state.goals))),
+                };
+
+                // A deleted file is not an edit to apply.
+                if let Some(change) = change {
+                    on_change(change);
+                }
+            }
+        }
+    });
+
+    Ok(())
+}
+
+fn changed_files(event: notify::Result<Event>) -> BTreeSet<WatchedFile> {
+    let Ok(event) = event else {
+        return BTreeSet::new();
+    };
+
+    // Reads (including our own) show up as access events and must not
+    // trigger a reload.
+    let is_content_change = match event.kind {
+        EventKind::Create(_) => true,
+        EventKind::Modify(ModifyKind::Metadata(_)) => false,
+        EventKind::Modify(_) => true,
+        _ => false,
+    };
+    if !is_content_change {
+        return BTreeSet::new();
+    }
+
+    event
+        .paths
+        .iter()
+        .filter_map(|path| match path.file_name()?.to_str()? {
+            SETTINGS_FILE => Some(WatchedFile::Settings),
+            ANALYTICS_STATE_FILE => Some(WatchedFile::Analytics),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{AccessKind, CreateKind, DataChange, RenameMode};
+
+    fn event(kind: EventKind, names: &[&str]) -> notify::Result<Event> {
+        let mut event = Event::new(kind);
+        for name in names {
+            event = event.add_path(PathBuf::from("/data/pomoduo").join(name));
+        }
+        Ok(event)
+    }
+
+    #[test]
+    fn changed_files_reports_writes_and_renames_of_state_files_only() {
+        let renamed = event(
+            EventKind::Modify(ModifyKind::Name(RenameMode::Both)),
+            &["settings.tmp", "settings.json"],
+        );
+        let written = event(
+            EventKind::Modify(ModifyKind::Data(DataChange::Content)),
+            &["analytics_state.json"],
+        );
+        let created = event(EventKind::Create(CreateKind::File), &["runtime_state.json"]);
+
+        assert_eq!(
+            changed_files(renamed).into_iter().collect::<Vec<_>>(),
+            vec![WatchedFile::Settings]
+        );
+        assert_eq!(
+            changed_files(written).into_iter().collect::<Vec<_>>(),
+            vec![WatchedFile::Analytics]
+        );
+        assert!(changed_files(created).is_empty());
+    }
+
+    #[test]
+    fn changed_files_ignores_reads_and_metadata() {
+        let read = event(EventKind::Access(AccessKind::Any), &["settings.json"]);
+        let touched = event(
+            EventKind::Modify(ModifyKind::Metadata(notify::event::MetadataKind::Any)),
+            &["settings.json"],
+        );
+
+        assert!(changed_files(read).is_empty());
+        assert!(changed_files(touched).is_empty());
+    }
+}
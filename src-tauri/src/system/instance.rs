@@ -0,0 +1,74 @@
+use crate::timer::engine::TimerEngine;
+use tauri::{AppHandle, Manager};
+
+const MAIN_WINDOW: &str = "main";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LaunchAction {
+    Start,
+    Reset,
+}
+
+/// Reads the actions requested on the command line. Unknown arguments,
+/// including the executable path, are ignored.
+pub fn parse_launch_actions(args: &[String]) -> Vec<LaunchAction> {
+    args.iter()
+        .filter_map(|arg| match arg.as_str() {
+            "--start" => Some(LaunchAction::Start),
+            "--reset" => Some(LaunchAction::Reset),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Applies each launch action in order. The engine publishes the resulting
+/// state changes itself.
+pub fn apply_launch_args(engine: &TimerEngine, args: &[String]) {
+    for action in parse_launch_actions(args) {
+        let result = match action {
+            LaunchAction::Start => engine.start(),
+            LaunchAction::Reset => engine.reset(),
+        };
+
+        if let Err(error) = result {
+            eprintln!("failed to apply launch argument {action:?}: {error}");
+        }
+    }
+}
+
+/// Called in the running instance when Pomoduo is launched again; the second
+/// process exits after handing over its arguments.
+pub fn on_second_instance(app: &AppHandle, args: Vec<String>, _cwd: String) {
+    focus_main_window(app);
+
+    if let Some(engine) = app.try_state::<TimerEngine>() {
+        apply_launch_args(&engine, &args);
+    }
+}
+
+fn focus_main_window(app: &AppHandle) {
+    let Some(window) = app.get_webview_window(MAIN_WINDOW) else {
+        return;
+    };
+
+    let _ = window.unminimize();
+    let _ = window.show();
+    if let Err(error) = window.set_focus() {
+        eprintln!("failed to focus main window: {error}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_launch_actions_ignores_executable_and_unknown_flags() {
+        let args = ["pomoduo", "--verbose", "--reset", "--start"].map(String::from);
+
+        assert_eq!(
+            parse_launch_actions(&args),
+            vec![LaunchAction::Reset, LaunchAction::Start]
+        );
+    }
+}
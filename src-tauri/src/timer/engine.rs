@@ -1,21 +1,51 @@
-use crate::analytics::model::{AnalyticsState, GoalSettings, InsightsSnapshot};
+use super::clock::Clock;
+use super::profile::{self, apply_profile, ProfileState};
+use super::scheduler::{next_tick_delay, ClockProbe, TickScheduler};
+use super::validation::{self, SettingsField};
+use crate::analytics::distribution::build_focus_distribution;
+use crate::analytics::export::{
+    build_history_export, parse_export_range, render_history_csv, render_sessions_ics,
+    sessions_in_range,
+};
+use crate::analytics::import::{merge_imported_history, parse_import_csv};
+use crate::analytics::model::{
+    AnalyticsState, ClockJump, ExportSummary, FocusDistribution, FocusDistributionRequest,
+    GoalSettings, HistoryExportFormat, HistoryExportRequest, HistoryImportRequest, ImportPreview,
+    InsightsSnapshot, SessionExportRequest, SessionOutcome, SessionRecord, StreakMilestone,
+    DEFAULT_HISTORY_RETENTION_DAYS,
+};
 use crate::analytics::service::{
-    build_insights_snapshot, current_local_date, record_focus_completion,
+    build_insights_snapshot, prune_history, record_focus_completion, record_session,
     update_goals as update_goal_settings,
 };
-use crate::storage::state_file::StateFileStore;
+use crate::analytics::streaks::{compute_streaks, reached_milestones};
+use crate::analytics::zone::{
+    local_offset_minutes, parse_time_zone, reattribute_days, system_time_zone, DayBoundary,
+    DayCredit, DayZone,
+};
+use crate::error::{AppError, CommandError, Failure};
+use crate::storage::backup::read_backup;
+use crate::storage::recovery::StorageIssue;
+use crate::storage::store::{StateStorage, StorageBackend};
+use crate::storage::watch::{spawn_state_file_watcher, ExternalChange, WatchedFile};
 use crate::system::notify::Notifier;
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::time::Duration;
 use tauri::{AppHandle, Emitter};
 
 const DEFAULT_FOCUS_MS: i64 = 25 * 60_000;
 const DEFAULT_SHORT_BREAK_MS: i64 = 5 * 60_000;
 const DEFAULT_LONG_BREAK_MS: i64 = 15 * 60_000;
 const DEFAULT_LONG_BREAK_EVERY: u32 = 4;
+const DEFAULT_DAILY_BACKUP_COUNT: u32 = 7;
+const DEFAULT_DEV_TIME_SCALE: u32 = 1;
 pub const DEFAULT_LOCALE: &str = "en-US";
 pub const ZH_CN_LOCALE: &str = "zh-CN";
 
@@ -27,7 +57,30 @@ pub enum Phase {
     LongBreak,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// What the worker does when wall-clock time jumps relative to monotonic time
+/// while a phase runs, e.g. after laptop sleep or a clock change.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ClockJumpPolicy {
+    /// Keep the wall-clock deadline; the phase may end as soon as it resumes.
+    #[default]
+    HonorWallClock,
+    /// Move the deadline by the jump so the phase keeps its remaining time.
+    ExtendBySuspension,
+    /// Stop the phase and log a running focus session as abandoned.
+    Abandon,
+}
+
+/// Which calendar decides the day a completed session counts towards.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum DayAttribution {
+    #[default]
+    CompletionZone,
+    HomeZone,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Settings {
     pub focus_ms: i64,
@@ -38,6 +91,29 @@ pub struct Settings {
     pub sound_enabled: bool,
     #[serde(default = "default_locale")]
     pub locale: String,
+    #[serde(default = "default_daily_backup_count")]
+    pub daily_backup_count: u32,
+    #[serde(default)]
+    pub storage_backend: StorageBackend,
+    #[serde(default)]
+    pub clock_jump_policy: ClockJumpPolicy,
+    /// Developer setting: how many times faster than real time the timer
+    /// runs, for demos and QA.
+    #[serde(default = "default_dev_time_scale")]
+    pub dev_time_scale: u32,
+    #[serde(default)]
+    pub day_attribution: DayAttribution,
+    /// IANA zone used with `DayAttribution::HomeZone`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub home_time_zone: Option<String>,
+    /// Local hour (0-23) at which a new day starts, so late sessions still
+    /// count towards the evening they belong to.
+    #[serde(default)]
+    pub day_start_hour: u32,
+    /// Days of detailed history to keep; `None` keeps everything. Older days
+    /// live on as monthly totals.
+    #[serde(default = "default_history_retention_days")]
+    pub history_retention_days: Option<u32>,
 }
 
 impl Default for Settings {
@@ -50,28 +126,94 @@ impl Default for Settings {
             notify_enabled: true,
             sound_enabled: true,
             locale: default_locale(),
+            daily_backup_count: DEFAULT_DAILY_BACKUP_COUNT,
+            storage_backend: StorageBackend::default(),
+            clock_jump_policy: ClockJumpPolicy::default(),
+            dev_time_scale: DEFAULT_DEV_TIME_SCALE,
+            day_attribution: DayAttribution::default(),
+            home_time_zone: None,
+            day_start_hour: 0,
+            history_retention_days: default_history_retention_days(),
         }
     }
 }
 
 impl Settings {
+    /// Replaces out-of-range values with defaults. Used for stored settings,
+    /// which predate validation; user input goes through `validation::validate`.
     fn sanitized(self) -> Self {
         Self {
-            focus_ms: sanitize_ms(self.focus_ms, DEFAULT_FOCUS_MS),
-            short_break_ms: sanitize_ms(self.short_break_ms, DEFAULT_SHORT_BREAK_MS),
-            long_break_ms: sanitize_ms(self.long_break_ms, DEFAULT_LONG_BREAK_MS),
-            long_break_every: if self.long_break_every == 0 {
-                DEFAULT_LONG_BREAK_EVERY
-            } else {
-                self.long_break_every
-            },
+            focus_ms: sanitize_field(SettingsField::FocusMs, self.focus_ms, DEFAULT_FOCUS_MS),
+            short_break_ms: sanitize_field(
+                SettingsField::ShortBreakMs,
+                self.short_break_ms,
+                DEFAULT_SHORT_BREAK_MS,
+            ),
+            long_break_ms: sanitize_field(
+                SettingsField::LongBreakMs,
+                self.long_break_ms,
+                DEFAULT_LONG_BREAK_MS,
+            ),
+            long_break_every: sanitize_field(
+                SettingsField::LongBreakEvery,
+                self.long_break_every,
+                DEFAULT_LONG_BREAK_EVERY,
+            ),
             notify_enabled: self.notify_enabled,
             sound_enabled: self.sound_enabled,
             locale: normalize_locale(&self.locale).to_string(),
+            daily_backup_count: sanitize_field(
+                SettingsField::DailyBackupCount,
+                self.daily_backup_count,
+                DEFAULT_DAILY_BACKUP_COUNT,
+            ),
+            storage_backend: self.storage_backend,
+            clock_jump_policy: self.clock_jump_policy,
+            dev_time_scale: sanitize_field(
+                SettingsField::DevTimeScale,
+                self.dev_time_scale,
+                DEFAULT_DEV_TIME_SCALE,
+            ),
+            day_attribution: self.day_attribution,
+            home_time_zone: sanitize_home_time_zone(self.day_attribution, self.home_time_zone),
+            day_start_hour: sanitize_field(SettingsField::DayStartHour, self.day_start_hour, 0),
+            history_retention_days: self.history_retention_days.map(|days| {
+                sanitize_field(
+                    SettingsField::HistoryRetentionDays,
+                    days,
+                    DEFAULT_HISTORY_RETENTION_DAYS,
+                )
+            }),
+        }
+    }
+
+    pub fn day_boundary(&self) -> DayBoundary {
+        let zone = match (self.day_attribution, &self.home_time_zone) {
+            (DayAttribution::HomeZone, Some(time_zone)) => DayZone::Home(time_zone.clone()),
+            _ => DayZone::Completion,
+        };
+        DayBoundary {
+            zone,
+            start_hour: self.day_start_hour,
         }
     }
 }
 
+/// Drops unknown zones. Choosing the home zone without naming one pins the
+/// current system zone.
+fn sanitize_home_time_zone(
+    attribution: DayAttribution,
+    time_zone: Option<String>,
+) -> Option<String> {
+    let time_zone = time_zone
+        .map(|name| name.trim().to_string())
+        .filter(|name| parse_time_zone(name).is_some());
+    match attribution {
+        DayAttribution::HomeZone => time_zone.or_else(system_time_zone),
+        DayAttribution::CompletionZone => time_zone,
+    }
+}
+
 pub fn normalize_locale(locale: &str) -> &'static str {
     let normalized = locale.trim().replace('_', "-").to_ascii_lowercase();
     match normalized.as_str() {
@@ -80,10 +222,38 @@ pub fn normalize_locale(locale: &str) -> &'static str {
     }
 }
 
+pub fn phase_label(phase: Phase, locale: &str) -> &'static str {
+    if normalize_locale(locale) == ZH_CN_LOCALE {
+        match phase {
+            Phase::Focus => "\u{4E13}\u{6CE8}",
+            Phase::ShortBreak => "\u{77ED}\u{4F11}\u{606F}",
+            Phase::LongBreak => "\u{957F}\u{4F11}\u{606F}",
+        }
+    } else {
+        match phase {
+            Phase::Focus => "Focus",
+            Phase::ShortBreak => "Short Break",
+            Phase::LongBreak => "Long Break",
+        }
+    }
+}
+
 fn default_locale() -> String {
     DEFAULT_LOCALE.to_string()
 }
 
+fn default_daily_backup_count() -> u32 {
+    DEFAULT_DAILY_BACKUP_COUNT
+}
+
+fn default_dev_time_scale() -> u32 {
+    DEFAULT_DEV_TIME_SCALE
+}
+
+fn default_history_retention_days() -> Option<u32> {
+    Some(DEFAULT_HISTORY_RETENTION_DAYS)
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RuntimeState {
@@ -92,6 +262,38 @@ pub struct RuntimeState {
     pub cycle_count: u32,
     pub end_at_ms: Option<i64>,
     pub remaining_ms: i64,
+    #[serde(default)]
+    pub started_at_ms: Option<i64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub clock_jumps: Vec<ClockJump>,
+    #[serde(default, flatten)]
+    pub labels: SessionLabels,
+}
+
+/// What the timer is being used for, given when a phase is started and
+/// copied into the sessions it logs.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionLabels {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+}
+
+impl SessionLabels {
+    /// Trims both labels and drops empty ones.
+    pub fn sanitized(self) -> Self {
+        let clean = |label: Option<String>| {
+            label
+                .map(|label| label.trim().to_string())
+                .filter(|label| !label.is_empty())
+        };
+        Self {
+            task: clean(self.task),
+            project: clean(self.project),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -102,6 +304,8 @@ pub struct TimerSnapshot {
     pub cycle_count: u32,
     pub end_at_ms: Option<i64>,
     pub remaining_ms: i64,
+    #[serde(flatten)]
+    pub labels: SessionLabels,
     pub settings: Settings,
 }
 
@@ -113,6 +317,13 @@ struct PhaseCompletedPayload {
     sound_enabled: bool,
 }
 
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StateFileRejectedPayload {
+    file: &'static str,
+    error: CommandError,
+}
+
 #[derive(Debug, Clone)]
 struct CompletionMeta {
     finished_phase: Phase,
@@ -120,6 +331,34 @@ struct CompletionMeta {
     notify_enabled: bool,
     sound_enabled: bool,
     locale: String,
+    started_at_ms: Option<i64>,
+    ended_at_ms: Option<i64>,
+    duration_ms: i64,
+    clock_jumps: Vec<ClockJump>,
+    labels: SessionLabels,
+    time_scale: u32,
+}
+
+impl CompletionMeta {
+    fn session_record(&self, profile_id: String) -> Option<SessionRecord> {
+        Some(SessionRecord {
+            phase: self.finished_phase,
+            started_at_ms: self.started_at_ms?,
+            ended_at_ms: self.ended_at_ms?,
+            outcome: SessionOutcome::Completed,
+            profile_id: Some(profile_id),
+            clock_jumps: self.clock_jumps.clone(),
+            utc_offset_minutes: self.ended_at_ms.map(local_offset_minutes),
+            duration_ms: Some(self.duration_ms),
+            task: self.labels.task.clone(),
+            project: self.labels.project.clone(),
+            closed_long_cycle: self.closes_long_cycle(),
+        })
+    }
+
+    fn closes_long_cycle(&self) -> bool {
+        self.finished_phase == Phase::Focus && self.next_phase == Phase::LongBreak
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -129,14 +368,13 @@ struct TimerState {
     cycle_count: u32,
     end_at_ms: Option<i64>,
     remaining_ms: i64,
+    started_at_ms: Option<i64>,
+    clock_jumps: Vec<ClockJump>,
+    labels: SessionLabels,
     settings: Settings,
 }
 
 impl TimerState {
-    fn from_storage(settings: Settings, runtime_state: Option<RuntimeState>) -> Self {
-        Self::from_storage_at(settings, runtime_state, now_ms())
-    }
-
     fn from_storage_at(settings: Settings, runtime_state: Option<RuntimeState>, now: i64) -> Self {
         let mut state = if let Some(runtime) = runtime_state {
             Self {
@@ -145,6 +383,9 @@ impl TimerState {
                 cycle_count: runtime.cycle_count,
                 end_at_ms: runtime.end_at_ms,
                 remaining_ms: runtime.remaining_ms,
+                started_at_ms: runtime.started_at_ms,
+                clock_jumps: runtime.clock_jumps,
+                labels: runtime.labels,
                 settings,
             }
         } else {
@@ -154,6 +395,9 @@ impl TimerState {
                 cycle_count: 0,
                 end_at_ms: None,
                 remaining_ms: settings.focus_ms,
+                started_at_ms: None,
+                clock_jumps: Vec::new(),
+                labels: SessionLabels::default(),
                 settings,
             }
         };
@@ -172,6 +416,8 @@ impl TimerState {
                 }
             } else {
                 state.is_running = false;
+                state.started_at_ms = None;
+                state.clock_jumps.clear();
                 state.remaining_ms = phase_duration_ms(state.phase, &state.settings);
             }
         }
@@ -183,10 +429,22 @@ impl TimerState {
         state
     }
 
+    /// Countdown time runs `dev_time_scale` times faster than the real
+    /// clock; `end_at_ms` and every other timestamp stay in real time.
+    fn time_scale(&self) -> u32 {
+        self.settings.dev_time_scale.max(1)
+    }
+
+    /// The real time at which `remaining_ms` of countdown time runs out.
+    fn deadline_at(&self, now: i64, remaining_ms: i64) -> i64 {
+        let scale = i64::from(self.time_scale());
+        now + (remaining_ms + scale - 1) / scale
+    }
+
     fn current_remaining_ms(&self, now: i64) -> i64 {
         if self.is_running {
             self.end_at_ms
-                .map(|end_at| (end_at - now).max(0))
+                .map(|end_at| ((end_at - now) * i64::from(self.time_scale())).max(0))
                 .unwrap_or_else(|| self.remaining_ms.max(0))
         } else {
             self.remaining_ms.max(0)
@@ -200,6 +458,7 @@ impl TimerState {
             cycle_count: self.cycle_count,
             end_at_ms: self.end_at_ms,
             remaining_ms: self.current_remaining_ms(now),
+            labels: self.labels.clone(),
             settings: self.settings.clone(),
         }
     }
@@ -211,11 +470,18 @@ impl TimerState {
             cycle_count: self.cycle_count,
             end_at_ms: self.end_at_ms,
             remaining_ms: self.current_remaining_ms(now),
+            started_at_ms: self.started_at_ms,
+            clock_jumps: self.clock_jumps.clone(),
+            labels: self.labels.clone(),
         }
     }
 
     fn complete_current_phase(&mut self) -> CompletionMeta {
         let finished_phase = self.phase;
+        let started_at_ms = self.started_at_ms.take();
+        let ended_at_ms = self.end_at_ms;
+        let clock_jumps = std::mem::take(&mut self.clock_jumps);
+        let duration_ms = phase_duration_ms(finished_phase, &self.settings);
 
         if finished_phase == Phase::Focus {
             self.cycle_count += 1;
@@ -247,46 +513,157 @@ impl TimerState {
             notify_enabled: self.settings.notify_enabled,
             sound_enabled: self.settings.sound_enabled,
             locale: self.settings.locale.clone(),
+            started_at_ms,
+            ended_at_ms,
+            duration_ms,
+            clock_jumps,
+            labels: self.labels.clone(),
+            time_scale: self.settings.dev_time_scale,
+        }
+    }
+
+    /// The session to log when a running focus phase is cut short at
+    /// `ended_at_ms`. Breaks and sped-up runs are not part of the session log.
+    fn abandoned_session(&self, ended_at_ms: i64, profile_id: String) -> Option<SessionRecord> {
+        if !self.is_running || self.phase != Phase::Focus || self.time_scale() > 1 {
+            return None;
+        }
+
+        self.started_at_ms.map(|started_at_ms| SessionRecord {
+            phase: self.phase,
+            started_at_ms,
+            ended_at_ms,
+            outcome: SessionOutcome::Abandoned,
+            profile_id: Some(profile_id),
+            clock_jumps: self.clock_jumps.clone(),
+            utc_offset_minutes: Some(local_offset_minutes(ended_at_ms)),
+            duration_ms: None,
+            task: self.labels.task.clone(),
+            project: self.labels.project.clone(),
+            closed_long_cycle: false,
+        })
+    }
+
+    /// Applies `policy` to a running phase after a clock jump and returns the
+    /// session to log if the phase was abandoned.
+    fn apply_clock_jump(&mut self, jump: ClockJump, profile_id: String) -> Option<SessionRecord> {
+        self.clock_jumps.push(jump);
+
+        match jump.policy {
+            ClockJumpPolicy::HonorWallClock => None,
+            ClockJumpPolicy::ExtendBySuspension => {
+                self.end_at_ms = self.end_at_ms.map(|end_at| end_at + jump.jump_ms);
+                None
+            }
+            ClockJumpPolicy::Abandon => {
+                let last_seen_ms = jump.detected_at_ms - jump.jump_ms.max(0);
+                let session = self.abandoned_session(last_seen_ms, profile_id);
+                self.is_running = false;
+                self.end_at_ms = None;
+                self.started_at_ms = None;
+                self.clock_jumps.clear();
+                self.remaining_ms = phase_duration_ms(self.phase, &self.settings);
+                session
+            }
         }
     }
 }
 
+/// Why the timer state changed, sent along with every `timer_state_changed`
+/// event.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum StateChangeCause {
+    Restored,
+    Started,
+    Resumed,
+    Reset,
+    Tick,
+    PhaseCompleted,
+    SettingsUpdated,
+    ProfileSwitched,
+    ExternalEdit,
+    BackupRestored,
+    ClockJumped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimerStateChanged {
+    pub snapshot: TimerSnapshot,
+    pub cause: StateChangeCause,
+}
+
+type StateChangeSink = Arc<dyn Fn(TimerStateChanged) + Send + Sync>;
+
+struct TickOutcome {
+    completion: Option<CompletionMeta>,
+    productivity_snapshot: Option<InsightsSnapshot>,
+    streak_milestones: Vec<StreakMilestone>,
+}
+
 #[derive(Clone)]
 pub struct TimerEngine {
     state: Arc<Mutex<TimerState>>,
     analytics: Arc<Mutex<AnalyticsState>>,
-    storage: StateFileStore,
+    profiles: Arc<Mutex<ProfileState>>,
+    storage: Arc<dyn StateStorage>,
+    storage_issues: Arc<Mutex<Vec<StorageIssue>>>,
     notifier: Notifier,
+    state_sink: Arc<Mutex<Option<StateChangeSink>>>,
+    scheduler: Arc<TickScheduler>,
+    clock: Arc<dyn Clock>,
     worker_started: Arc<AtomicBool>,
 }
 
 impl TimerEngine {
-    pub fn new(storage: StateFileStore, notifier: Notifier) -> Self {
-        let settings = storage.load_settings().unwrap_or_default().sanitized();
-        let runtime_state = storage.load_runtime_state();
-        let analytics_state = storage
-            .load_analytics_state()
+    pub fn new(storage: Arc<dyn StateStorage>, notifier: Notifier, clock: Arc<dyn Clock>) -> Self {
+        let mut settings = loaded("settings", storage.load_settings())
+            .unwrap_or_default()
+            .sanitized();
+        settings.storage_backend = storage.backend();
+        // A developer time scale only lasts for the run that set it.
+        settings.dev_time_scale = DEFAULT_DEV_TIME_SCALE;
+        let today = settings.day_boundary().today(clock.now_ms());
+        if let Err(error) =
+            storage.ensure_daily_backup(today, settings.daily_backup_count, clock.now_ms())
+        {
+            eprintln!("failed to create daily backup: {error}");
+        }
+        let runtime_state = loaded("runtime state", storage.load_runtime_state());
+        let mut analytics_state = loaded("analytics state", storage.load_analytics_state())
             .unwrap_or_default()
             .sanitized();
-        let timer_state = TimerState::from_storage(settings, runtime_state);
+        reattribute_days(&mut analytics_state, &settings.day_boundary());
+        let profile_state = loaded("settings profiles", storage.load_profiles())
+            .unwrap_or_else(|| ProfileState::with_default(settings.clone()))
+            .sanitized(&settings);
+        let timer_state = TimerState::from_storage_at(settings, runtime_state, clock.now_ms());
+        let storage_issues = storage.take_issues();
 
         let engine = Self {
             state: Arc::new(Mutex::new(timer_state)),
             analytics: Arc::new(Mutex::new(analytics_state)),
+            profiles: Arc::new(Mutex::new(profile_state)),
             storage,
+            storage_issues: Arc::new(Mutex::new(storage_issues)),
             notifier,
+            state_sink: Arc::new(Mutex::new(None)),
+            scheduler: Arc::new(TickScheduler::default()),
+            clock,
             worker_started: Arc::new(AtomicBool::new(false)),
         };
 
-        let snapshot = engine.get_state();
-        let _ = engine.storage.save_settings(&snapshot.settings);
-        let _ = engine.persist_runtime_state(&RuntimeState {
-            phase: snapshot.phase,
-            is_running: snapshot.is_running,
-            cycle_count: snapshot.cycle_count,
-            end_at_ms: snapshot.end_at_ms,
-            remaining_ms: snapshot.remaining_ms,
-        });
+        let (settings_snapshot, runtime_snapshot) = {
+            let now = engine.now_ms();
+            let state = engine
+                .state
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            (state.settings.clone(), state.to_runtime_state(now))
+        };
+        let _ = engine.storage.save_settings(&settings_snapshot);
+        let _ = engine.persist_runtime_state(&runtime_snapshot);
         let analytics_snapshot = {
             let analytics = engine
                 .analytics
@@ -295,6 +672,7 @@ impl TimerEngine {
             analytics.clone()
         };
         let _ = engine.persist_analytics_state(&analytics_snapshot);
+        let _ = engine.persist_profiles(&engine.get_profiles());
 
         engine
     }
@@ -304,15 +682,61 @@ impl TimerEngine {
             return;
         }
 
+        let storage_issues = self.storage_issues();
+        if !storage_issues.is_empty() {
+            let _ = app_handle.emit("storage_issues_detected", storage_issues);
+        }
+
         let engine = self.clone();
         thread::spawn(move || loop {
-            thread::sleep(Duration::from_secs(1));
-            engine.handle_tick(&app_handle);
+            let seen = engine.scheduler.generation();
+            let delay = engine.tick_delay(engine.now_ms());
+            let probe = ClockProbe::capture(engine.now_ms());
+            let reached = engine.scheduler.wait(seen, delay);
+
+            // Only a phase that was running across the wait can be affected.
+            if delay.is_some() {
+                if let Some(jump_ms) = probe.jump_ms(engine.now_ms()) {
+                    if let Err(error) = engine.handle_clock_jump(jump_ms, engine.now_ms()) {
+                        eprintln!("failed to apply clock jump: {error}");
+                    }
+                }
+            }
+
+            if reached {
+                engine.handle_tick(&app_handle);
+            }
+        });
+    }
+
+    /// Sends every state change to `sink`, starting with the state restored
+    /// at startup.
+    pub fn on_state_changed(&self, sink: impl Fn(TimerStateChanged) + Send + Sync + 'static) {
+        {
+            let mut state_sink = self
+                .state_sink
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            *state_sink = Some(Arc::new(sink));
+        }
+
+        self.publish(self.get_state(), StateChangeCause::Restored);
+    }
+
+    /// Applies external edits to the JSON state files while the app runs.
+    pub fn start_file_watcher(&self, app_handle: AppHandle, base_dir: PathBuf) {
+        let engine = self.clone();
+        let watching = spawn_state_file_watcher(base_dir, move |change| {
+            engine.handle_external_change(&app_handle, change);
         });
+
+        if let Err(error) = watching {
+            eprintln!("failed to watch state files: {error}");
+        }
     }
 
     pub fn get_state(&self) -> TimerSnapshot {
-        let now = now_ms();
+        let now = self.now_ms();
         let state = self
             .state
             .lock()
@@ -320,6 +744,76 @@ impl TimerEngine {
         state.snapshot(now)
     }
 
+    /// Unreadable state files found while loading, most recent last.
+    pub fn storage_issues(&self) -> Vec<StorageIssue> {
+        let issues = self
+            .storage_issues
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        issues.clone()
+    }
+
+    pub fn get_profiles(&self) -> ProfileState {
+        let profiles = self
+            .profiles
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        profiles.clone()
+    }
+
+    /// New profiles start from the settings currently in use.
+    pub fn create_profile(&self, name: String) -> Result<ProfileState, AppError> {
+        let settings = {
+            let state = self
+                .state
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            state.settings.clone()
+        };
+
+        self.edit_profiles(|profiles| profiles.create(&name, settings, self.now_ms()).map(|_| ()))
+    }
+
+    pub fn rename_profile(&self, id: String, name: String) -> Result<ProfileState, AppError> {
+        self.edit_profiles(|profiles| profiles.rename(&id, &name))
+    }
+
+    pub fn delete_profile(&self, id: String) -> Result<ProfileState, AppError> {
+        self.edit_profiles(|profiles| profiles.delete(&id))
+    }
+
+    /// Applies the profile's timer settings with the same remaining-time rules
+    /// as `update_settings`.
+    pub fn switch_profile(&self, id: String) -> Result<TimerSnapshot, AppError> {
+        let current = {
+            let state = self
+                .state
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            state.settings.clone()
+        };
+
+        let (next_settings, previous_id) = {
+            let mut profiles = self
+                .profiles
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            let profile = profiles.find(&id).ok_or_else(|| profile::not_found(&id))?;
+            let next_settings = apply_profile(&current, &profile.settings);
+            let previous_id = std::mem::replace(&mut profiles.active_profile_id, id);
+            (next_settings, previous_id)
+        };
+
+        self.apply_settings(next_settings, StateChangeCause::ProfileSwitched)
+            .inspect_err(|_| {
+                let mut profiles = self
+                    .profiles
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner());
+                profiles.active_profile_id = previous_id;
+            })
+    }
+
     pub fn get_insights(&self) -> InsightsSnapshot {
         let locale = {
             let state = self
@@ -337,10 +831,27 @@ impl TimerEngine {
             analytics.clone()
         };
 
-        build_insights_snapshot(&analytics_state, &locale, current_local_date())
+        build_insights_snapshot(&analytics_state, &locale, self.today())
+    }
+
+    pub fn get_focus_distribution(
+        &self,
+        request: FocusDistributionRequest,
+    ) -> Result<FocusDistribution, AppError> {
+        let (start, end) = export_range(&request.from, &request.to)?;
+
+        let analytics_state = {
+            let analytics = self
+                .analytics
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            analytics.clone()
+        };
+
+        Ok(build_focus_distribution(&analytics_state, start, end))
     }
 
-    pub fn update_goals(&self, goals: GoalSettings) -> Result<InsightsSnapshot, String> {
+    pub fn update_goals(&self, goals: GoalSettings) -> Result<InsightsSnapshot, AppError> {
         let locale = {
             let state = self
                 .state
@@ -360,165 +871,389 @@ impl TimerEngine {
 
         self.persist_analytics_state(&snapshot)?;
 
-        Ok(build_insights_snapshot(
-            &snapshot,
-            &locale,
-            current_local_date(),
-        ))
-    }
-
-    pub fn start(&self) -> Result<TimerSnapshot, String> {
-        self.run_or_resume()
+        Ok(build_insights_snapshot(&snapshot, &locale, self.today()))
     }
 
-    pub fn resume(&self) -> Result<TimerSnapshot, String> {
-        self.run_or_resume()
-    }
+    pub fn export_sessions_ics(
+        &self,
+        request: SessionExportRequest,
+    ) -> Result<ExportSummary, AppError> {
+        let (start, end) = export_range(&request.from, &request.to)?;
 
-    pub fn reset(&self) -> Result<TimerSnapshot, String> {
-        let now = now_ms();
-        let (snapshot, runtime_state) = {
-            let mut state = self
+        let locale = {
+            let state = self
                 .state
                 .lock()
                 .unwrap_or_else(|poisoned| poisoned.into_inner());
+            state.settings.locale.clone()
+        };
 
-            state.phase = Phase::Focus;
-            state.is_running = false;
-            state.cycle_count = 0;
-            state.end_at_ms = None;
-            state.remaining_ms = state.settings.focus_ms;
-
-            (state.snapshot(now), state.to_runtime_state(now))
+        let analytics_state = {
+            let analytics = self
+                .analytics
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            analytics.clone()
         };
 
-        self.persist_runtime_state(&runtime_state)?;
+        let sessions = sessions_in_range(
+            &analytics_state.sessions,
+            &analytics_state.day_boundary(),
+            start,
+            end,
+            request.include_abandoned,
+        );
+        let calendar = render_sessions_ics(&sessions, &locale, self.now_ms());
 
-        Ok(snapshot)
+        fs::write(&request.output_path, calendar)
+            .map_err(|err| AppError::from_error(Failure::WriteExport, &err))?;
+
+        Ok(ExportSummary {
+            output_path: request.output_path,
+            exported: sessions.len(),
+        })
     }
 
-    pub fn update_settings(&self, settings: Settings) -> Result<TimerSnapshot, String> {
-        let now = now_ms();
-        let (snapshot, runtime_state, settings_to_persist) = {
-            let mut state = self
-                .state
+    pub fn export_history(&self, request: HistoryExportRequest) -> Result<ExportSummary, AppError> {
+        let (start, end) = export_range(&request.from, &request.to)?;
+
+        let analytics_state = {
+            let analytics = self
+                .analytics
                 .lock()
                 .unwrap_or_else(|poisoned| poisoned.into_inner());
+            analytics.clone()
+        };
 
-            let next_settings = settings.sanitized();
-            state.settings = next_settings;
-
-            if state.is_running {
-                let remaining = state.current_remaining_ms(now);
-                state.end_at_ms = Some(now + remaining);
-                state.remaining_ms = remaining;
-            } else {
-                state.remaining_ms = phase_duration_ms(state.phase, &state.settings);
-                state.end_at_ms = None;
+        let (contents, exported) = match request.format {
+            HistoryExportFormat::Csv => (
+                render_history_csv(&analytics_state, start, end),
+                (end - start).num_days() as usize + 1,
+            ),
+            HistoryExportFormat::Json => {
+                let export = build_history_export(&analytics_state, start, end);
+                let json = serde_json::to_string_pretty(&export)
+                    .map_err(|err| AppError::from_error(Failure::WriteExport, &err))?;
+                (json, export.daily.len())
             }
-
-            (
-                state.snapshot(now),
-                state.to_runtime_state(now),
-                state.settings.clone(),
-            )
         };
 
-        self.persist_settings(&settings_to_persist)?;
-        self.persist_runtime_state(&runtime_state)?;
+        fs::write(&request.output_path, contents)
+            .map_err(|err| AppError::from_error(Failure::WriteExport, &err))?;
 
-        Ok(snapshot)
+        Ok(ExportSummary {
+            output_path: request.output_path,
+            exported,
+        })
     }
 
-    fn run_or_resume(&self) -> Result<TimerSnapshot, String> {
-        let now = now_ms();
-        let (snapshot, runtime_state) = {
-            let mut state = self
-                .state
+    pub fn import_history(
+        &self,
+        app: &AppHandle,
+        request: HistoryImportRequest,
+    ) -> Result<ImportPreview, AppError> {
+        let (preview, snapshot) = self.merge_import(request)?;
+        if let Some(snapshot) = snapshot {
+            let _ = app.emit("productivity_updated", snapshot);
+        }
+
+        Ok(preview)
+    }
+
+    /// Returns the refreshed insights when the import was written.
+    fn merge_import(
+        &self,
+        request: HistoryImportRequest,
+    ) -> Result<(ImportPreview, Option<InsightsSnapshot>), AppError> {
+        let text = fs::read_to_string(&request.input_path)
+            .map_err(|err| AppError::from_error(Failure::ReadImport, &err))?;
+        let today = self.today();
+        let retention_days = self.retention_days();
+        let parsed = parse_import_csv(&text, &request.mapping, today, retention_days)
+            .map_err(|err| AppError::new(Failure::InvalidImport).caused_by(err))?;
+
+        let (preview, next_snapshot) = {
+            let mut analytics = self
+                .analytics
                 .lock()
                 .unwrap_or_else(|poisoned| poisoned.into_inner());
 
-            if state.is_running {
-                return Ok(state.snapshot(now));
-            }
-
-            let remaining = if state.remaining_ms > 0 {
-                state.remaining_ms
+            if request.dry_run {
+                let mut preview_state = analytics.clone();
+                (
+                    merge_imported_history(
+                        &mut preview_state,
+                        &parsed,
+                        today,
+                        retention_days,
+                        true,
+                    ),
+                    None,
+                )
             } else {
-                phase_duration_ms(state.phase, &state.settings)
-            };
-
-            state.remaining_ms = remaining;
-            state.end_at_ms = Some(now + remaining);
-            state.is_running = true;
+                let preview =
+                    merge_imported_history(&mut analytics, &parsed, today, retention_days, false);
+                (preview, Some(analytics.clone()))
+            }
+        };
 
-            (state.snapshot(now), state.to_runtime_state(now))
+        let Some(snapshot) = next_snapshot else {
+            return Ok((preview, None));
         };
+        self.persist_analytics_state(&snapshot)?;
 
-        self.persist_runtime_state(&runtime_state)?;
+        let locale = {
+            let state = self
+                .state
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            state.settings.locale.clone()
+        };
+        Ok((
+            preview,
+            Some(build_insights_snapshot(&snapshot, &locale, today)),
+        ))
+    }
 
-        Ok(snapshot)
+    pub fn create_backup(&self, output_path: String) -> Result<(), AppError> {
+        self.storage
+            .create_backup(Path::new(&output_path), self.now_ms())
+            .map_err(|err| AppError::from_error(Failure::CreateBackup, &err))
     }
 
-    fn handle_tick(&self, app: &AppHandle) {
-        let now = now_ms();
-        let (tick_snapshot, completed, runtime_state) = {
+    pub fn restore_backup(&self, input_path: String) -> Result<TimerSnapshot, AppError> {
+        let archive = read_backup(Path::new(&input_path))
+            .map_err(|err| backup_error(Failure::ReadBackup, err))?;
+        self.storage
+            .restore_backup(&archive, self.now_ms())
+            .map_err(|err| backup_error(Failure::RestoreBackup, err))?;
+
+        let mut settings = loaded("settings", self.storage.load_settings())
+            .unwrap_or_default()
+            .sanitized();
+        settings.storage_backend = self.storage.backend();
+        let runtime_state = loaded("runtime state", self.storage.load_runtime_state());
+        let mut analytics_state = loaded("analytics state", self.storage.load_analytics_state())
+            .unwrap_or_default()
+            .sanitized();
+        reattribute_days(&mut analytics_state, &settings.day_boundary());
+        let profile_state = loaded("settings profiles", self.storage.load_profiles())
+            .unwrap_or_else(|| ProfileState::with_default(settings.clone()))
+            .sanitized(&settings);
+
+        {
+            let mut issues = self
+                .storage_issues
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            issues.extend(self.storage.take_issues());
+        }
+
+        {
             let mut state = self
                 .state
                 .lock()
                 .unwrap_or_else(|poisoned| poisoned.into_inner());
+            // The time scale belongs to this run, not to the backup.
+            settings.dev_time_scale = state.settings.dev_time_scale;
+            *state = TimerState::from_storage_at(settings, runtime_state, self.now_ms());
+        }
 
-            if !state.is_running {
-                return;
-            }
-
-            let remaining = state.current_remaining_ms(now);
-
-            if remaining > 0 {
+        {
+            let mut analytics = self
+                .analytics
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            *analytics = analytics_state.clone();
+        }
+        self.persist_analytics_state(&analytics_state)?;
+
+        {
+            let mut profiles = self
+                .profiles
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            *profiles = profile_state;
+        }
+
+        let snapshot = self.get_state();
+        self.publish(snapshot.clone(), StateChangeCause::BackupRestored);
+        Ok(snapshot)
+    }
+
+    pub fn start(&self) -> Result<TimerSnapshot, AppError> {
+        self.start_with_labels(SessionLabels::default())
+    }
+
+    /// Starts the current phase for the given task and project. A phase that
+    /// is already running keeps the labels it was started with.
+    pub fn start_with_labels(&self, labels: SessionLabels) -> Result<TimerSnapshot, AppError> {
+        self.run_or_resume(StateChangeCause::Started, Some(labels.sanitized()))
+    }
+
+    /// Resumes with the labels the phase was started with.
+    pub fn resume(&self) -> Result<TimerSnapshot, AppError> {
+        self.run_or_resume(StateChangeCause::Resumed, None)
+    }
+
+    pub fn reset(&self) -> Result<TimerSnapshot, AppError> {
+        let now = self.now_ms();
+        let profile_id = self.active_profile_id();
+        let (snapshot, runtime_state, abandoned_session) = {
+            let mut state = self
+                .state
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+            let abandoned_session = state.abandoned_session(now, profile_id);
+
+            state.phase = Phase::Focus;
+            state.is_running = false;
+            state.cycle_count = 0;
+            state.end_at_ms = None;
+            state.started_at_ms = None;
+            state.clock_jumps.clear();
+            state.labels = SessionLabels::default();
+            state.remaining_ms = state.settings.focus_ms;
+
+            (
+                state.snapshot(now),
+                state.to_runtime_state(now),
+                abandoned_session,
+            )
+        };
+
+        self.persist_runtime_state(&runtime_state)?;
+
+        if let Some(session) = abandoned_session {
+            self.record_abandoned_session(session)?;
+        }
+
+        self.publish(snapshot.clone(), StateChangeCause::Reset);
+        Ok(snapshot)
+    }
+
+    /// Rejects out-of-range values with field-level errors instead of
+    /// silently replacing them.
+    pub fn update_settings(&self, settings: Settings) -> Result<TimerSnapshot, AppError> {
+        validation::validate(&settings)?;
+        self.apply_settings(settings, StateChangeCause::SettingsUpdated)
+    }
+
+    /// Errors returned by engine operations, in the current locale.
+    pub fn localize(&self, error: AppError) -> CommandError {
+        let locale = {
+            let state = self
+                .state
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            state.settings.locale.clone()
+        };
+
+        error.localize(&locale)
+    }
+
+    fn apply_settings(
+        &self,
+        settings: Settings,
+        cause: StateChangeCause,
+    ) -> Result<TimerSnapshot, AppError> {
+        let now = self.now_ms();
+        let next_settings = settings.sanitized();
+
+        if next_settings.storage_backend != self.storage.backend() {
+            self.switch_storage_backend(next_settings.storage_backend, now)?;
+        }
+
+        let (snapshot, runtime_state, settings_to_persist) = {
+            let mut state = self
+                .state
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+            let remaining = state.current_remaining_ms(now);
+            state.settings = next_settings;
+
+            if state.is_running {
+                state.end_at_ms = Some(state.deadline_at(now, remaining));
                 state.remaining_ms = remaining;
-                (state.snapshot(now), None, None)
             } else {
-                let completion = state.complete_current_phase();
-                (
-                    state.snapshot(now),
-                    Some(completion),
-                    Some(state.to_runtime_state(now)),
-                )
+                state.remaining_ms = phase_duration_ms(state.phase, &state.settings);
+                state.end_at_ms = None;
             }
+
+            (
+                state.snapshot(now),
+                state.to_runtime_state(now),
+                state.settings.clone(),
+            )
         };
 
-        if let Some(runtime_state) = runtime_state.as_ref() {
-            if self.persist_runtime_state(runtime_state).is_err() {
-                return;
-            }
-        }
+        self.persist_settings(&settings_to_persist)?;
+        self.persist_runtime_state(&runtime_state)?;
+        self.sync_analytics(&settings_to_persist)?;
 
-        let _ = app.emit("timer_tick", tick_snapshot);
+        let profiles_snapshot = {
+            let mut profiles = self
+                .profiles
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            profiles.update_active_settings(&settings_to_persist);
+            profiles.clone()
+        };
+        self.persist_profiles(&profiles_snapshot)?;
 
-        if let Some(completion) = completed {
-            let mut productivity_snapshot = None;
-            if completion.finished_phase == Phase::Focus {
-                let today = current_local_date();
-                let completed_long_cycle = completion.next_phase == Phase::LongBreak;
+        self.publish(snapshot.clone(), cause);
+        Ok(snapshot)
+    }
 
-                let next_snapshot = {
-                    let mut analytics = self
-                        .analytics
-                        .lock()
-                        .unwrap_or_else(|poisoned| poisoned.into_inner());
-                    record_focus_completion(&mut analytics, today, completed_long_cycle);
-                    analytics.clone()
-                };
+    fn run_or_resume(
+        &self,
+        cause: StateChangeCause,
+        labels: Option<SessionLabels>,
+    ) -> Result<TimerSnapshot, AppError> {
+        let now = self.now_ms();
+        let (snapshot, runtime_state) = {
+            let mut state = self
+                .state
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
 
-                if self.persist_analytics_state(&next_snapshot).is_ok() {
-                    productivity_snapshot = Some(build_insights_snapshot(
-                        &next_snapshot,
-                        &completion.locale,
-                        today,
-                    ));
-                }
+            if state.is_running {
+                return Ok(state.snapshot(now));
+            }
+
+            let remaining = if state.remaining_ms > 0 {
+                state.remaining_ms
+            } else {
+                phase_duration_ms(state.phase, &state.settings)
+            };
+
+            state.remaining_ms = remaining;
+            state.end_at_ms = Some(state.deadline_at(now, remaining));
+            state.is_running = true;
+            if state.started_at_ms.is_none() {
+                state.started_at_ms = Some(now);
+            }
+            if let Some(labels) = labels {
+                state.labels = labels;
             }
 
+            (state.snapshot(now), state.to_runtime_state(now))
+        };
+
+        self.persist_runtime_state(&runtime_state)?;
+
+        self.publish(snapshot.clone(), cause);
+        Ok(snapshot)
+    }
+
+    fn handle_tick(&self, app: &AppHandle) {
+        let Some(outcome) = self.process_tick(self.now_ms()) else {
+            return;
+        };
+
+        if let Some(completion) = outcome.completion {
             if completion.notify_enabled {
                 self.notifier.notify_phase_transition(
                     app,
@@ -536,32 +1271,450 @@ impl TimerEngine {
                     sound_enabled: completion.sound_enabled,
                 },
             );
+        }
+
+        if let Some(snapshot) = outcome.productivity_snapshot {
+            let _ = app.emit("productivity_updated", snapshot);
+        }
+
+        for milestone in outcome.streak_milestones {
+            let _ = app.emit("streak_milestone_reached", milestone);
+        }
+    }
+
+    /// Advances the timer to `now` and records any completed focus session.
+    /// Returns `None` when nothing is running or the new phase could not be saved.
+    fn process_tick(&self, now: i64) -> Option<TickOutcome> {
+        let (tick_snapshot, completion, runtime_state) = {
+            let mut state = self
+                .state
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+            if !state.is_running {
+                return None;
+            }
+
+            let remaining = state.current_remaining_ms(now);
+
+            if remaining > 0 {
+                state.remaining_ms = remaining;
+                (state.snapshot(now), None, None)
+            } else {
+                let completion = state.complete_current_phase();
+                (
+                    state.snapshot(now),
+                    Some(completion),
+                    Some(state.to_runtime_state(now)),
+                )
+            }
+        };
+
+        if let Some(runtime_state) = runtime_state.as_ref() {
+            self.persist_runtime_state(runtime_state).ok()?;
+        }
+
+        let cause = if completion.is_some() {
+            StateChangeCause::PhaseCompleted
+        } else {
+            StateChangeCause::Tick
+        };
+        self.publish(tick_snapshot, cause);
+
+        let mut productivity_snapshot = None;
+        let mut streak_milestones = Vec::new();
+        // Sped-up runs are for trying the app out and stay out of analytics.
+        if let Some(completion) = completion.as_ref().filter(|completion| {
+            completion.finished_phase == Phase::Focus && completion.time_scale == 1
+        }) {
+            let boundary = self.day_boundary();
+            let session = completion.session_record(self.active_profile_id());
+            let credit = session
+                .as_ref()
+                .and_then(|session| boundary.credit(session, completion.duration_ms))
+                .unwrap_or_else(|| DayCredit {
+                    completed_on: boundary.today(self.now_ms()),
+                    minutes: Vec::new(),
+                });
+            let today = credit.completed_on;
+            let completed_long_cycle = completion.closes_long_cycle();
+
+            let (next_snapshot, streaks_before) = {
+                let mut analytics = self
+                    .analytics
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner());
+                let streaks_before = compute_streaks(&analytics, today);
+                record_focus_completion(
+                    &mut analytics,
+                    &credit,
+                    completed_long_cycle,
+                    self.retention_days(),
+                );
+                if let Some(session) = session {
+                    record_session(&mut analytics, session);
+                }
+                (analytics.clone(), streaks_before)
+            };
+
+            if self.persist_analytics_state(&next_snapshot).is_ok() {
+                self.ensure_daily_backup(today);
+                let snapshot = build_insights_snapshot(&next_snapshot, &completion.locale, today);
+                streak_milestones = reached_milestones(&streaks_before, &snapshot.streaks);
+                productivity_snapshot = Some(snapshot);
+            }
+        }
+
+        Some(TickOutcome {
+            completion,
+            productivity_snapshot,
+            streak_milestones,
+        })
+    }
+
+    fn handle_external_change(&self, app: &AppHandle, change: ExternalChange) {
+        // With SQLite the JSON files are only a leftover copy.
+        if self.storage.backend() != StorageBackend::Json {
+            return;
+        }
+
+        match change {
+            ExternalChange::Settings(settings) => {
+                let applied = settings
+                    .map_err(|err| AppError::from_error(Failure::ReadStateFile, &err))
+                    .and_then(|settings| self.apply_external_settings(settings));
+                if let Err(error) = applied {
+                    emit_state_file_rejected(app, WatchedFile::Settings, self.localize(error));
+                }
+            }
+            ExternalChange::Goals(goals) => {
+                let applied = goals
+                    .map_err(|err| AppError::from_error(Failure::ReadStateFile, &err))
+                    .and_then(|goals| self.apply_external_goals(goals));
+                match applied {
+                    Ok(Some(snapshot)) => {
+                        let _ = app.emit("productivity_updated", snapshot);
+                    }
+                    Ok(None) => {}
+                    Err(error) => {
+                        emit_state_file_rejected(app, WatchedFile::Analytics, self.localize(error))
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns `None` when the file already matches the running settings,
+    /// which is also how our own writes come back from the watcher.
+    fn apply_external_settings(
+        &self,
+        settings: Settings,
+    ) -> Result<Option<TimerSnapshot>, AppError> {
+        let unchanged = {
+            let state = self
+                .state
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            settings.clone().sanitized() == state.settings
+        };
+
+        if unchanged {
+            return Ok(None);
+        }
+
+        validation::validate(&settings)?;
+        self.apply_settings(settings, StateChangeCause::ExternalEdit)
+            .map(Some)
+    }
+
+    fn apply_external_goals(
+        &self,
+        goals: GoalSettings,
+    ) -> Result<Option<InsightsSnapshot>, AppError> {
+        let unchanged = {
+            let analytics = self
+                .analytics
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            goals.clone().sanitized() == analytics.goals
+        };
+
+        if unchanged {
+            return Ok(None);
+        }
+
+        self.update_goals(goals).map(Some)
+    }
+
+    /// Applies the configured clock jump policy to the running phase and logs
+    /// the jump with its session.
+    fn handle_clock_jump(&self, jump_ms: i64, now: i64) -> Result<(), AppError> {
+        let profile_id = self.active_profile_id();
+        let (snapshot, runtime_state, abandoned_session) = {
+            let mut state = self
+                .state
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+            if !state.is_running {
+                return Ok(());
+            }
+
+            let jump = ClockJump {
+                detected_at_ms: now,
+                jump_ms,
+                policy: state.settings.clock_jump_policy,
+            };
+            let abandoned_session = state.apply_clock_jump(jump, profile_id);
+
+            (
+                state.snapshot(now),
+                state.to_runtime_state(now),
+                abandoned_session,
+            )
+        };
+
+        self.persist_runtime_state(&runtime_state)?;
+
+        if let Some(session) = abandoned_session {
+            self.record_abandoned_session(session)?;
+        }
+
+        self.publish(snapshot, StateChangeCause::ClockJumped);
+        Ok(())
+    }
+
+    fn record_abandoned_session(&self, session: SessionRecord) -> Result<(), AppError> {
+        let next_snapshot = {
+            let mut analytics = self
+                .analytics
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            record_session(&mut analytics, session);
+            analytics.clone()
+        };
+        self.persist_analytics_state(&next_snapshot)
+    }
+
+    fn now_ms(&self) -> i64 {
+        self.clock.now_ms()
+    }
+
+    fn day_boundary(&self) -> DayBoundary {
+        let state = self
+            .state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        state.settings.day_boundary()
+    }
+
+    /// The day that completions count towards right now.
+    fn today(&self) -> NaiveDate {
+        self.day_boundary().today(self.now_ms())
+    }
+
+    fn retention_days(&self) -> Option<u32> {
+        let state = self
+            .state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        state.settings.history_retention_days
+    }
 
-            if let Some(snapshot) = productivity_snapshot {
-                let _ = app.emit("productivity_updated", snapshot);
+    /// Moves existing day keys over when the zone or start hour changes and
+    /// rolls up days that fall out of a shortened retention window.
+    fn sync_analytics(&self, settings: &Settings) -> Result<(), AppError> {
+        let boundary = settings.day_boundary();
+        let today = boundary.today(self.now_ms());
+        let next_snapshot = {
+            let mut analytics = self
+                .analytics
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            let kept = (analytics.daily.len(), analytics.sessions.len());
+            let reattributed = analytics.day_boundary() != boundary;
+            reattribute_days(&mut analytics, &boundary);
+            prune_history(&mut analytics, today, settings.history_retention_days);
+            if !reattributed && kept == (analytics.daily.len(), analytics.sessions.len()) {
+                return Ok(());
             }
+            analytics.clone()
+        };
+        self.persist_analytics_state(&next_snapshot)
+    }
+
+    /// Real time until the worker should wake next, or `None` while stopped.
+    fn tick_delay(&self, now: i64) -> Option<Duration> {
+        let time_scale = {
+            let state = self
+                .state
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            state.time_scale()
+        };
+        next_tick_delay(self.running_remaining_ms(now), time_scale)
+    }
+
+    /// Remaining time of a running phase, or `None` while stopped.
+    fn running_remaining_ms(&self, now: i64) -> Option<i64> {
+        let state = self
+            .state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        state.is_running.then(|| state.current_remaining_ms(now))
+    }
+
+    fn publish(&self, snapshot: TimerSnapshot, cause: StateChangeCause) {
+        // Changes made outside the worker move its deadline.
+        if !matches!(
+            cause,
+            StateChangeCause::Tick
+                | StateChangeCause::PhaseCompleted
+                | StateChangeCause::ClockJumped
+        ) {
+            self.scheduler.wake();
+        }
+
+        let sink = {
+            let state_sink = self
+                .state_sink
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            state_sink.clone()
+        };
+
+        if let Some(sink) = sink {
+            sink(TimerStateChanged { snapshot, cause });
+        }
+    }
+
+    fn active_profile_id(&self) -> String {
+        let profiles = self
+            .profiles
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        profiles.active_profile_id.clone()
+    }
+
+    /// Applies `edit` to a copy and only keeps it once it has been saved.
+    fn edit_profiles(
+        &self,
+        edit: impl FnOnce(&mut ProfileState) -> Result<(), AppError>,
+    ) -> Result<ProfileState, AppError> {
+        let mut profiles = self
+            .profiles
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut next = profiles.clone();
+        edit(&mut next)?;
+
+        self.persist_profiles(&next)?;
+        *profiles = next.clone();
+        Ok(next)
+    }
+
+    fn switch_storage_backend(&self, backend: StorageBackend, now: i64) -> Result<(), AppError> {
+        let (settings, runtime_state) = {
+            let state = self
+                .state
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            (state.settings.clone(), state.to_runtime_state(now))
+        };
+
+        let analytics_state = {
+            let analytics = self
+                .analytics
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            analytics.clone()
+        };
+
+        self.storage
+            .switch_backend(backend, &settings, &runtime_state, &analytics_state)
+            .map_err(|err| AppError::from_error(Failure::SwitchStorageBackend, &err))
+    }
+
+    fn ensure_daily_backup(&self, today: NaiveDate) {
+        let keep = {
+            let state = self
+                .state
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            state.settings.daily_backup_count
+        };
+
+        if let Err(error) = self.storage.ensure_daily_backup(today, keep, self.now_ms()) {
+            eprintln!("failed to create daily backup: {error}");
         }
     }
 
-    fn persist_settings(&self, settings: &Settings) -> Result<(), String> {
+    fn persist_settings(&self, settings: &Settings) -> Result<(), AppError> {
         self.storage
             .save_settings(settings)
-            .map_err(|err| format!("failed to save settings: {err}"))
+            .map_err(|err| AppError::from_error(Failure::SaveSettings, &err))
     }
 
-    fn persist_runtime_state(&self, runtime_state: &RuntimeState) -> Result<(), String> {
+    fn persist_runtime_state(&self, runtime_state: &RuntimeState) -> Result<(), AppError> {
         self.storage
             .save_runtime_state(runtime_state)
-            .map_err(|err| format!("failed to save runtime state: {err}"))
+            .map_err(|err| AppError::from_error(Failure::SaveRuntimeState, &err))
+    }
+
+    fn persist_profiles(&self, profiles: &ProfileState) -> Result<(), AppError> {
+        self.storage
+            .save_profiles(profiles)
+            .map_err(|err| AppError::from_error(Failure::SaveProfiles, &err))
     }
 
-    fn persist_analytics_state(&self, analytics_state: &AnalyticsState) -> Result<(), String> {
+    fn persist_analytics_state(&self, analytics_state: &AnalyticsState) -> Result<(), AppError> {
         self.storage
             .save_analytics_state(analytics_state)
-            .map_err(|err| format!("failed to save analytics state: {err}"))
+            .map_err(|err| AppError::from_error(Failure::SaveAnalytics, &err))
     }
 }
 
+fn emit_state_file_rejected(app: &AppHandle, file: WatchedFile, error: CommandError) {
+    eprintln!(
+        "ignored external edit to {}: {} {:?}",
+        file.file_name(),
+        error.message,
+        error.causes
+    );
+    let _ = app.emit(
+        "state_file_rejected",
+        StateFileRejectedPayload {
+            file: file.file_name(),
+            error,
+        },
+    );
+}
+
+fn export_range(from: &str, to: &str) -> Result<(NaiveDate, NaiveDate), AppError> {
+    parse_export_range(from, to)
+        .map_err(|err| AppError::new(Failure::InvalidDateRange).caused_by(err))
+}
+
+/// Archives that are malformed or too new are bad input rather than I/O
+/// failures.
+fn backup_error(failure: Failure, error: io::Error) -> AppError {
+    let failure = match error.kind() {
+        io::ErrorKind::InvalidData | io::ErrorKind::Unsupported => Failure::InvalidBackup,
+        _ => failure,
+    };
+    AppError::from_error(failure, &error)
+}
+
+/// A file that fails to load is treated as missing. Corrupt files have
+/// already been moved aside by the store, and files written by a newer
+/// version are reported as read-only and never overwritten.
+fn loaded<T>(label: &str, result: io::Result<Option<T>>) -> Option<T> {
+    result.unwrap_or_else(|error| {
+        eprintln!("failed to load {label}: {error}");
+        None
+    })
+}
+
 fn phase_duration_ms(phase: Phase, settings: &Settings) -> i64 {
     match phase {
         Phase::Focus => settings.focus_ms,
@@ -578,16 +1731,22 @@ fn sanitize_ms(ms: i64, fallback: i64) -> i64 {
     }
 }
 
-fn now_ms() -> i64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|duration| duration.as_millis() as i64)
-        .unwrap_or(0)
+fn sanitize_field<T: Copy + Into<i64>>(field: SettingsField, value: T, fallback: T) -> T {
+    if field.contains(value.into()) {
+        value
+    } else {
+        fallback
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::analytics::service::day_key;
+    use crate::error::ErrorCode;
+    use crate::storage::memory::MemoryStore;
+    use crate::timer::clock::{ManualClock, SystemClock};
+    use crate::timer::profile::DEFAULT_PROFILE_ID;
 
     const TEST_NOW_MS: i64 = 1_700_000_000_000;
 
@@ -600,6 +1759,14 @@ mod tests {
             notify_enabled: true,
             sound_enabled: true,
             locale: DEFAULT_LOCALE.to_string(),
+            daily_backup_count: 7,
+            storage_backend: StorageBackend::Json,
+            clock_jump_policy: ClockJumpPolicy::HonorWallClock,
+            dev_time_scale: 1,
+            day_attribution: DayAttribution::CompletionZone,
+            home_time_zone: None,
+            day_start_hour: 0,
+            history_retention_days: Some(400),
         }
     }
 
@@ -612,6 +1779,9 @@ mod tests {
             cycle_count: 1,
             end_at_ms: Some(TEST_NOW_MS + 90_000),
             remaining_ms: 12_345,
+            started_at_ms: None,
+            clock_jumps: Vec::new(),
+            labels: SessionLabels::default(),
         };
 
         let state = TimerState::from_storage_at(settings, Some(runtime), TEST_NOW_MS);
@@ -632,6 +1802,9 @@ mod tests {
             cycle_count: 2,
             end_at_ms: None,
             remaining_ms: 30_000,
+            started_at_ms: None,
+            clock_jumps: Vec::new(),
+            labels: SessionLabels::default(),
         };
 
         let state = TimerState::from_storage_at(settings.clone(), Some(runtime), TEST_NOW_MS);
@@ -652,6 +1825,9 @@ mod tests {
             cycle_count: 0,
             end_at_ms: Some(TEST_NOW_MS - 1),
             remaining_ms: 1_000,
+            started_at_ms: None,
+            clock_jumps: Vec::new(),
+            labels: SessionLabels::default(),
         };
 
         let state = TimerState::from_storage_at(settings.clone(), Some(runtime), TEST_NOW_MS);
@@ -672,6 +1848,9 @@ mod tests {
             cycle_count: 3,
             end_at_ms: Some(TEST_NOW_MS - 5),
             remaining_ms: 1_000,
+            started_at_ms: None,
+            clock_jumps: Vec::new(),
+            labels: SessionLabels::default(),
         };
 
         let state = TimerState::from_storage_at(settings.clone(), Some(runtime), TEST_NOW_MS);
@@ -692,6 +1871,9 @@ mod tests {
             cycle_count: 2,
             end_at_ms: Some(TEST_NOW_MS - 10),
             remaining_ms: 2_000,
+            started_at_ms: None,
+            clock_jumps: Vec::new(),
+            labels: SessionLabels::default(),
         };
 
         let state = TimerState::from_storage_at(settings.clone(), Some(runtime), TEST_NOW_MS);
@@ -712,6 +1894,9 @@ mod tests {
             cycle_count: 5,
             end_at_ms: None,
             remaining_ms: 0,
+            started_at_ms: None,
+            clock_jumps: Vec::new(),
+            labels: SessionLabels::default(),
         };
 
         let state = TimerState::from_storage_at(settings.clone(), Some(runtime), TEST_NOW_MS);
@@ -732,6 +1917,9 @@ mod tests {
             cycle_count: 3,
             end_at_ms: None,
             remaining_ms: settings.focus_ms,
+            started_at_ms: None,
+            clock_jumps: Vec::new(),
+            labels: SessionLabels::default(),
         };
 
         let mut state = TimerState::from_storage_at(settings.clone(), Some(runtime), TEST_NOW_MS);
@@ -753,6 +1941,9 @@ mod tests {
             cycle_count: 1,
             end_at_ms: None,
             remaining_ms: settings.focus_ms,
+            started_at_ms: None,
+            clock_jumps: Vec::new(),
+            labels: SessionLabels::default(),
         };
 
         let mut state = TimerState::from_storage_at(settings.clone(), Some(runtime), TEST_NOW_MS);
@@ -765,6 +1956,36 @@ mod tests {
         assert_eq!(state.remaining_ms, settings.short_break_ms);
     }
 
+    #[test]
+    fn complete_running_focus_reports_session_bounds() {
+        let settings = sample_settings();
+        let runtime = RuntimeState {
+            phase: Phase::Focus,
+            is_running: true,
+            cycle_count: 0,
+            end_at_ms: Some(TEST_NOW_MS + 1_000),
+            remaining_ms: 1_000,
+            started_at_ms: Some(TEST_NOW_MS - settings.focus_ms + 1_000),
+            clock_jumps: Vec::new(),
+            labels: SessionLabels::default(),
+        };
+
+        let mut state = TimerState::from_storage_at(settings.clone(), Some(runtime), TEST_NOW_MS);
+        let session = state
+            .complete_current_phase()
+            .session_record("default".to_string())
+            .unwrap();
+
+        assert_eq!(session.phase, Phase::Focus);
+        assert_eq!(
+            session.started_at_ms,
+            TEST_NOW_MS - settings.focus_ms + 1_000
+        );
+        assert_eq!(session.ended_at_ms, TEST_NOW_MS + 1_000);
+        assert_eq!(session.outcome, SessionOutcome::Completed);
+        assert_eq!(state.started_at_ms, None);
+    }
+
     #[test]
     fn normalize_locale_supports_common_aliases() {
         assert_eq!(normalize_locale("zh-CN"), ZH_CN_LOCALE);
@@ -789,4 +2010,629 @@ mod tests {
 
         assert_eq!(settings.locale, DEFAULT_LOCALE);
     }
+
+    fn manual_engine() -> (TimerEngine, Arc<MemoryStore>, Arc<ManualClock>) {
+        let store = Arc::new(MemoryStore::default());
+        let clock = Arc::new(ManualClock::at(TEST_NOW_MS));
+        let engine = TimerEngine::new(store.clone(), Notifier, clock.clone());
+        (engine, store, clock)
+    }
+
+    fn memory_engine() -> (TimerEngine, Arc<MemoryStore>) {
+        let (engine, store, _clock) = manual_engine();
+        (engine, store)
+    }
+
+    fn finish_current_phase(engine: &TimerEngine) -> TickOutcome {
+        let end_at_ms = engine.get_state().end_at_ms.unwrap();
+        engine.process_tick(end_at_ms + 1).unwrap()
+    }
+
+    #[test]
+    fn new_engine_persists_defaults_to_empty_storage() {
+        let (_engine, store) = memory_engine();
+
+        assert_eq!(
+            store.load_settings().unwrap().unwrap().focus_ms,
+            DEFAULT_FOCUS_MS
+        );
+        assert!(!store.load_runtime_state().unwrap().unwrap().is_running);
+        assert!(store.load_analytics_state().unwrap().is_some());
+    }
+
+    #[test]
+    fn start_and_reset_persist_runtime_state_and_record_abandoned_focus() {
+        let (engine, store) = memory_engine();
+
+        engine.start().unwrap();
+        let runtime = store.load_runtime_state().unwrap().unwrap();
+        assert!(runtime.is_running);
+        assert!(runtime.started_at_ms.is_some());
+
+        engine.reset().unwrap();
+        let runtime = store.load_runtime_state().unwrap().unwrap();
+        assert!(!runtime.is_running);
+        assert_eq!(runtime.started_at_ms, None);
+
+        let sessions = store.load_analytics_state().unwrap().unwrap().sessions;
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].outcome, SessionOutcome::Abandoned);
+    }
+
+    #[test]
+    fn update_settings_while_idle_resets_remaining_to_new_duration() {
+        let (engine, store) = memory_engine();
+        let mut settings = sample_settings();
+        settings.focus_ms = 10 * 60_000;
+
+        let snapshot = engine.update_settings(settings).unwrap();
+
+        assert_eq!(snapshot.remaining_ms, 10 * 60_000);
+        assert_eq!(
+            store.load_settings().unwrap().unwrap().focus_ms,
+            10 * 60_000
+        );
+        assert_eq!(
+            store.load_runtime_state().unwrap().unwrap().remaining_ms,
+            10 * 60_000
+        );
+    }
+
+    #[test]
+    fn update_settings_rejects_out_of_range_values_without_applying_them() {
+        let (engine, store) = memory_engine();
+        let mut settings = sample_settings();
+        settings.focus_ms = 30 * 24 * 60 * 60_000;
+
+        let error = engine.update_settings(settings).unwrap_err();
+
+        assert_eq!(error.failure, Failure::InvalidSettings);
+        assert_eq!(error.fields.len(), 1);
+        assert_eq!(engine.get_state().settings.focus_ms, DEFAULT_FOCUS_MS);
+        assert_eq!(
+            store.load_settings().unwrap().unwrap().focus_ms,
+            DEFAULT_FOCUS_MS
+        );
+    }
+
+    #[test]
+    fn stored_out_of_range_settings_are_sanitized_on_load() {
+        let store = Arc::new(MemoryStore::default());
+        let mut legacy = sample_settings();
+        legacy.focus_ms = 30 * 24 * 60 * 60_000;
+        legacy.short_break_ms = 2_000;
+        store.save_settings(&legacy).unwrap();
+
+        let engine = TimerEngine::new(store, Notifier, Arc::new(SystemClock));
+
+        let settings = engine.get_state().settings;
+        assert_eq!(settings.focus_ms, DEFAULT_FOCUS_MS);
+        assert_eq!(settings.short_break_ms, DEFAULT_SHORT_BREAK_MS);
+    }
+
+    #[test]
+    fn completed_focus_records_analytics_and_session() {
+        let (engine, store) = memory_engine();
+        engine.start().unwrap();
+
+        let outcome = finish_current_phase(&engine);
+
+        assert_eq!(engine.get_state().phase, Phase::ShortBreak);
+        assert!(outcome.productivity_snapshot.is_some());
+        let analytics = store.load_analytics_state().unwrap().unwrap();
+        let today = analytics.daily.get(&day_key(engine.today())).unwrap();
+        assert_eq!(today.focus_completed, 1);
+        assert_eq!(analytics.sessions.len(), 1);
+        assert_eq!(analytics.sessions[0].outcome, SessionOutcome::Completed);
+    }
+
+    #[test]
+    fn switching_to_a_home_zone_moves_existing_day_keys() {
+        let (engine, store, _clock) = manual_engine();
+        engine.start().unwrap();
+        finish_current_phase(&engine);
+
+        let home = DayBoundary {
+            zone: DayZone::Home("Asia/Tokyo".to_string()),
+            start_hour: 0,
+        };
+        engine
+            .update_settings(Settings {
+                day_attribution: DayAttribution::HomeZone,
+                home_time_zone: Some("Asia/Tokyo".to_string()),
+                ..sample_settings()
+            })
+            .unwrap();
+
+        let analytics = store.load_analytics_state().unwrap().unwrap();
+        let session_day = home.session_date(&analytics.sessions[0]).unwrap();
+        assert_eq!(analytics.day_boundary(), home);
+        assert_eq!(analytics.daily.len(), 1);
+        assert_eq!(analytics.daily[&day_key(session_day)].focus_completed, 1);
+    }
+
+    #[test]
+    fn late_sessions_count_towards_the_day_before_the_start_hour() {
+        let (engine, store, _clock) = manual_engine();
+        engine
+            .update_settings(Settings {
+                day_attribution: DayAttribution::HomeZone,
+                home_time_zone: Some("UTC".to_string()),
+                day_start_hour: 23,
+                ..sample_settings()
+            })
+            .unwrap();
+        engine.start().unwrap();
+
+        // Ends at 22:38 UTC on 2023-11-14, before that day starts at 23:00.
+        let outcome = finish_current_phase(&engine);
+
+        let snapshot = outcome.productivity_snapshot.unwrap();
+        assert_eq!(snapshot.summaries.daily.focus_completed, 1);
+        assert_eq!(snapshot.heatmap.last().unwrap().date, "2023-11-13");
+        let analytics = store.load_analytics_state().unwrap().unwrap();
+        assert_eq!(analytics.daily["2023-11-13"].focus_completed, 1);
+    }
+
+    #[test]
+    fn every_mutation_publishes_a_state_change() {
+        let (engine, _store) = memory_engine();
+        let causes = Arc::new(Mutex::new(Vec::new()));
+        let sink = causes.clone();
+        engine.on_state_changed(move |change| sink.lock().unwrap().push(change.cause));
+
+        engine.start().unwrap();
+        engine.start().unwrap();
+        finish_current_phase(&engine);
+        engine.update_settings(sample_settings()).unwrap();
+        engine.reset().unwrap();
+        assert!(engine
+            .update_settings(Settings {
+                long_break_every: 0,
+                ..sample_settings()
+            })
+            .is_err());
+
+        assert_eq!(
+            *causes.lock().unwrap(),
+            vec![
+                StateChangeCause::Restored,
+                StateChangeCause::Started,
+                StateChangeCause::PhaseCompleted,
+                StateChangeCause::SettingsUpdated,
+                StateChangeCause::Reset,
+            ]
+        );
+    }
+
+    #[test]
+    fn tick_worker_sleeps_while_stopped_and_wakes_on_changes() {
+        let (engine, _store) = memory_engine();
+        assert_eq!(engine.running_remaining_ms(engine.now_ms()), None);
+
+        let seen = engine.scheduler.generation();
+        engine.start().unwrap();
+        assert_ne!(engine.scheduler.generation(), seen);
+        let end_at_ms = engine.get_state().end_at_ms.unwrap();
+        assert_eq!(engine.running_remaining_ms(end_at_ms - 250), Some(250));
+
+        let seen = engine.scheduler.generation();
+        engine.process_tick(end_at_ms - 250);
+        assert_eq!(engine.scheduler.generation(), seen);
+        engine.reset().unwrap();
+        assert_ne!(engine.scheduler.generation(), seen);
+    }
+
+    fn running_engine_with_policy(policy: ClockJumpPolicy) -> (TimerEngine, Arc<MemoryStore>) {
+        let (engine, store) = memory_engine();
+        engine
+            .update_settings(Settings {
+                clock_jump_policy: policy,
+                ..sample_settings()
+            })
+            .unwrap();
+        engine.start().unwrap();
+        (engine, store)
+    }
+
+    #[test]
+    fn honored_clock_jump_is_logged_with_the_completed_session() {
+        let (engine, store) = running_engine_with_policy(ClockJumpPolicy::HonorWallClock);
+        let end_at_ms = engine.get_state().end_at_ms;
+
+        engine.handle_clock_jump(-5_000, engine.now_ms()).unwrap();
+        assert_eq!(engine.get_state().end_at_ms, end_at_ms);
+        finish_current_phase(&engine);
+
+        let sessions = store.load_analytics_state().unwrap().unwrap().sessions;
+        assert_eq!(sessions[0].outcome, SessionOutcome::Completed);
+        assert_eq!(sessions[0].clock_jumps[0].jump_ms, -5_000);
+        assert!(engine.get_state().phase != Phase::Focus);
+    }
+
+    #[test]
+    fn extend_policy_moves_the_deadline_by_the_suspension() {
+        let (engine, store) = running_engine_with_policy(ClockJumpPolicy::ExtendBySuspension);
+        let end_at_ms = engine.get_state().end_at_ms.unwrap();
+
+        engine
+            .handle_clock_jump(3_600_000, engine.now_ms())
+            .unwrap();
+
+        assert_eq!(engine.get_state().end_at_ms, Some(end_at_ms + 3_600_000));
+        let runtime = store.load_runtime_state().unwrap().unwrap();
+        assert_eq!(
+            runtime.clock_jumps[0].policy,
+            ClockJumpPolicy::ExtendBySuspension
+        );
+    }
+
+    #[test]
+    fn abandon_policy_stops_the_phase_and_logs_an_abandoned_session() {
+        let (engine, store) = running_engine_with_policy(ClockJumpPolicy::Abandon);
+        let detected_at_ms = engine.now_ms() + 3_600_000;
+
+        engine.handle_clock_jump(3_600_000, detected_at_ms).unwrap();
+
+        let snapshot = engine.get_state();
+        assert!(!snapshot.is_running);
+        assert_eq!(snapshot.phase, Phase::Focus);
+        assert_eq!(snapshot.remaining_ms, snapshot.settings.focus_ms);
+        let sessions = store.load_analytics_state().unwrap().unwrap().sessions;
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].outcome, SessionOutcome::Abandoned);
+        assert_eq!(sessions[0].ended_at_ms, detected_at_ms - 3_600_000);
+        assert_eq!(sessions[0].clock_jumps.len(), 1);
+    }
+
+    #[test]
+    fn manual_clock_drives_a_full_focus_and_break_end_to_end() {
+        let (engine, store, clock) = manual_engine();
+        engine.start().unwrap();
+
+        clock.advance(DEFAULT_FOCUS_MS - 1_000);
+        engine.process_tick(engine.now_ms());
+        assert_eq!(engine.get_state().remaining_ms, 1_000);
+
+        clock.advance(1_000);
+        engine.process_tick(engine.now_ms());
+        engine.start().unwrap();
+        clock.advance(DEFAULT_SHORT_BREAK_MS);
+        engine.process_tick(engine.now_ms());
+
+        let snapshot = engine.get_state();
+        assert_eq!(snapshot.phase, Phase::Focus);
+        assert_eq!(snapshot.cycle_count, 1);
+        let sessions = store.load_analytics_state().unwrap().unwrap().sessions;
+        assert_eq!(sessions[0].started_at_ms, TEST_NOW_MS);
+        assert_eq!(sessions[0].ended_at_ms, TEST_NOW_MS + DEFAULT_FOCUS_MS);
+    }
+
+    #[test]
+    fn dev_time_scale_runs_the_timer_faster() {
+        let (engine, store, clock) = manual_engine();
+        engine
+            .update_settings(Settings {
+                dev_time_scale: 60,
+                ..sample_settings()
+            })
+            .unwrap();
+        engine.start().unwrap();
+
+        assert_eq!(
+            engine.get_state().end_at_ms,
+            Some(TEST_NOW_MS + DEFAULT_FOCUS_MS / 60)
+        );
+        clock.advance(DEFAULT_FOCUS_MS / 60);
+        let outcome = engine.process_tick(engine.now_ms()).unwrap();
+
+        assert_eq!(engine.get_state().phase, Phase::ShortBreak);
+        assert!(outcome.productivity_snapshot.is_none());
+        assert!(store
+            .load_analytics_state()
+            .unwrap()
+            .unwrap()
+            .sessions
+            .is_empty());
+    }
+
+    #[test]
+    fn session_labels_survive_a_restart_and_name_the_exported_event() {
+        let (engine, store, clock) = manual_engine();
+        engine
+            .start_with_labels(SessionLabels {
+                task: Some(" Write report ".to_string()),
+                project: Some("Thesis".to_string()),
+            })
+            .unwrap();
+
+        let restarted = TimerEngine::new(store.clone(), Notifier, clock.clone());
+        assert_eq!(
+            restarted.get_state().labels.task.as_deref(),
+            Some("Write report")
+        );
+        finish_current_phase(&restarted);
+
+        let output_path = std::env::temp_dir().join(format!(
+            "pomoduo-labels-{}-{}.ics",
+            std::process::id(),
+            SystemClock.now_ms()
+        ));
+        let today = day_key(restarted.today());
+        restarted
+            .export_sessions_ics(SessionExportRequest {
+                from: today.clone(),
+                to: today,
+                include_abandoned: false,
+                output_path: output_path.to_string_lossy().into_owned(),
+            })
+            .unwrap();
+        let calendar = fs::read_to_string(&output_path)
+            .unwrap()
+            .replace("\r\n ", "");
+        assert!(calendar.contains("SUMMARY:Pomoduo: Focus - Write report - Thesis\r\n"));
+        let _ = fs::remove_file(output_path);
+    }
+
+    #[test]
+    fn dev_time_scale_keeps_timestamps_on_the_real_clock() {
+        let (engine, store, clock) = manual_engine();
+        let scaled = Settings {
+            dev_time_scale: 60,
+            ..sample_settings()
+        };
+        engine.update_settings(scaled.clone()).unwrap();
+        engine.start().unwrap();
+        clock.advance(10_000);
+
+        let state = engine.update_settings(sample_settings()).unwrap();
+        assert_eq!(state.remaining_ms, DEFAULT_FOCUS_MS - 600_000);
+        assert_eq!(
+            state.end_at_ms,
+            Some(TEST_NOW_MS + 10_000 + DEFAULT_FOCUS_MS - 600_000)
+        );
+        assert_eq!(engine.now_ms(), clock.now_ms());
+
+        engine.update_settings(scaled).unwrap();
+        let restarted = TimerEngine::new(store.clone(), Notifier, clock.clone());
+        let state = restarted.get_state();
+        assert_eq!(state.settings.dev_time_scale, 1);
+        assert_eq!(
+            state.end_at_ms,
+            store.load_runtime_state().unwrap().unwrap().end_at_ms
+        );
+        assert_eq!(state.remaining_ms, (DEFAULT_FOCUS_MS - 600_000) / 60);
+    }
+
+    #[test]
+    fn analytics_save_failure_still_advances_timer() {
+        let (engine, store) = memory_engine();
+        engine.start().unwrap();
+        store.fail_analytics_state_writes(true);
+
+        let outcome = finish_current_phase(&engine);
+
+        assert!(outcome.productivity_snapshot.is_none());
+        assert_eq!(
+            store.load_runtime_state().unwrap().unwrap().phase,
+            Phase::ShortBreak
+        );
+        assert_eq!(engine.get_state().phase, Phase::ShortBreak);
+    }
+
+    #[test]
+    fn runtime_save_failure_is_reported_to_caller() {
+        let (engine, store) = memory_engine();
+        store.fail_runtime_state_writes(true);
+
+        let error = engine.localize(engine.start().unwrap_err());
+
+        assert_eq!(error.code, ErrorCode::StorageIo);
+        assert_eq!(error.message, "Could not save the timer state.");
+        assert_eq!(error.causes, vec!["simulated write failure"]);
+        assert!(!store.load_runtime_state().unwrap().unwrap().is_running);
+    }
+
+    #[test]
+    fn settings_save_failure_is_reported_to_caller() {
+        let (engine, store) = memory_engine();
+        store.fail_settings_writes(true);
+
+        assert!(engine.update_settings(sample_settings()).is_err());
+    }
+
+    #[test]
+    fn corrupt_analytics_file_is_quarantined_and_daily_history_salvaged() {
+        use crate::storage::store::StateStore;
+
+        let base_dir = std::env::temp_dir().join(format!(
+            "pomoduo-corrupt-{}-{}",
+            std::process::id(),
+            SystemClock.now_ms()
+        ));
+        fs::create_dir_all(&base_dir).unwrap();
+        fs::write(
+            base_dir.join("analytics_state.json"),
+            r#"{"version":1,"daily":{"2026-02-14":{"focusCompleted":5,"longCycleCompleted":1}},"goals":"broken"}"#,
+        )
+        .unwrap();
+
+        let engine = TimerEngine::new(
+            Arc::new(StateStore::from_base_dir(base_dir.clone())),
+            Notifier,
+            Arc::new(SystemClock),
+        );
+
+        let issues = engine.storage_issues();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].file, "analytics_state.json");
+        assert_eq!(issues[0].recovered, vec!["daily"]);
+        assert!(Path::new(issues[0].quarantined_path.as_ref().unwrap()).exists());
+
+        let analytics = engine.analytics.lock().unwrap().clone();
+        assert_eq!(
+            analytics.daily.get("2026-02-14").unwrap().focus_completed,
+            5
+        );
+        let saved = fs::read_to_string(base_dir.join("analytics_state.json")).unwrap();
+        assert!(saved.contains("2026-02-14"));
+        let _ = fs::remove_dir_all(base_dir);
+    }
+
+    #[test]
+    fn newer_settings_file_is_reported_read_only_and_kept() {
+        use crate::storage::store::StateStore;
+
+        let base_dir = std::env::temp_dir().join(format!(
+            "pomoduo-newer-{}-{}",
+            std::process::id(),
+            SystemClock.now_ms()
+        ));
+        fs::create_dir_all(&base_dir).unwrap();
+        let newer = format!(
+            r#"{{"version":{},"focusMs":1}}"#,
+            crate::storage::migrate::SETTINGS_VERSION + 1
+        );
+        fs::write(base_dir.join("settings.json"), &newer).unwrap();
+
+        let engine = TimerEngine::new(
+            Arc::new(StateStore::from_base_dir(base_dir.clone())),
+            Notifier,
+            Arc::new(SystemClock),
+        );
+
+        let issues = engine.storage_issues();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].file, "settings.json");
+        assert!(issues[0].read_only);
+        assert!(engine.update_settings(sample_settings()).is_err());
+        assert_eq!(
+            fs::read_to_string(base_dir.join("settings.json")).unwrap(),
+            newer
+        );
+        let _ = fs::remove_dir_all(base_dir);
+    }
+
+    #[test]
+    fn external_settings_edit_is_applied_once() {
+        let (engine, store) = memory_engine();
+        let mut settings = engine.get_state().settings;
+
+        assert!(engine
+            .apply_external_settings(settings.clone())
+            .unwrap()
+            .is_none());
+
+        settings.focus_ms = 50 * 60_000;
+        let snapshot = engine
+            .apply_external_settings(settings.clone())
+            .unwrap()
+            .unwrap();
+        assert_eq!(snapshot.remaining_ms, 50 * 60_000);
+        assert_eq!(
+            store.load_settings().unwrap().unwrap().focus_ms,
+            50 * 60_000
+        );
+        assert!(engine.apply_external_settings(settings).unwrap().is_none());
+    }
+
+    #[test]
+    fn external_goals_edit_updates_insights() {
+        let (engine, store) = memory_engine();
+        let mut goals = GoalSettings::default();
+        goals.daily.focus_target = 12;
+
+        assert!(engine
+            .apply_external_goals(goals.clone())
+            .unwrap()
+            .is_some());
+        assert_eq!(
+            store
+                .load_analytics_state()
+                .unwrap()
+                .unwrap()
+                .goals
+                .daily
+                .focus_target,
+            12
+        );
+        assert!(engine.apply_external_goals(goals).unwrap().is_none());
+    }
+
+    #[test]
+    fn real_import_refreshes_insights_but_dry_run_does_not() {
+        let (engine, store) = memory_engine();
+        let input_path = std::env::temp_dir().join(format!(
+            "pomoduo-import-{}-{}.csv",
+            std::process::id(),
+            SystemClock.now_ms()
+        ));
+        fs::write(
+            &input_path,
+            format!("day,pomodoros\n{},6\n", day_key(engine.today())),
+        )
+        .unwrap();
+        let request = |dry_run| HistoryImportRequest {
+            input_path: input_path.to_string_lossy().into_owned(),
+            mapping: crate::analytics::model::ImportColumnMapping {
+                date_column: "day".to_string(),
+                date_format: None,
+                count_column: Some("pomodoros".to_string()),
+                long_cycle_column: None,
+                minutes_column: None,
+                filter_column: None,
+                filter_value: None,
+                id_column: None,
+                delimiter: None,
+            },
+            dry_run,
+        };
+
+        let (_, preview_snapshot) = engine.merge_import(request(true)).unwrap();
+        let (preview, snapshot) = engine.merge_import(request(false)).unwrap();
+
+        assert!(preview_snapshot.is_none());
+        assert_eq!(preview.focus_completed_added, 6);
+        assert_eq!(snapshot.unwrap().summaries.daily.focus_completed, 6);
+        assert_eq!(
+            store.load_analytics_state().unwrap().unwrap().daily.len(),
+            1
+        );
+        let _ = fs::remove_file(input_path);
+    }
+
+    #[test]
+    fn switching_profile_while_running_keeps_remaining_and_tags_sessions() {
+        let (engine, store) = memory_engine();
+        let profiles = engine.create_profile("Deep work".to_string()).unwrap();
+        let deep_work = profiles.profiles[1].id.clone();
+        let running = engine.start().unwrap();
+
+        let snapshot = engine.switch_profile(deep_work.clone()).unwrap();
+        assert!(snapshot.remaining_ms <= running.remaining_ms);
+
+        let mut settings = snapshot.settings.clone();
+        settings.focus_ms = 50 * 60_000;
+        engine.update_settings(settings).unwrap();
+        let stored = store.load_profiles().unwrap().unwrap();
+        assert_eq!(stored.active_profile_id, deep_work);
+        assert_eq!(
+            stored.find(&deep_work).unwrap().settings.focus_ms,
+            50 * 60_000
+        );
+        assert_eq!(
+            stored.find(DEFAULT_PROFILE_ID).unwrap().settings.focus_ms,
+            DEFAULT_FOCUS_MS
+        );
+
+        finish_current_phase(&engine);
+        let sessions = store.load_analytics_state().unwrap().unwrap().sessions;
+        assert_eq!(sessions[0].profile_id.as_deref(), Some(deep_work.as_str()));
+
+        let snapshot = engine
+            .switch_profile(DEFAULT_PROFILE_ID.to_string())
+            .unwrap();
+        assert_eq!(snapshot.settings.focus_ms, DEFAULT_FOCUS_MS);
+        assert!(engine.switch_profile("missing".to_string()).is_err());
+    }
 }
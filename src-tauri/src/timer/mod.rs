@@ -1 +1,5 @@
+pub mod clock;
 pub mod engine;
+pub mod profile;
+pub mod scheduler;
+pub mod validation;
@@ -0,0 +1,228 @@
+use super::engine::Settings;
+use crate::error::{AppError, Failure};
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_PROFILE_ID: &str = "default";
+
+const DEFAULT_PROFILE_NAME: &str = "Default";
+const MAX_PROFILE_NAME_CHARS: usize = 40;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsProfile {
+    pub id: String,
+    pub name: String,
+    pub settings: Settings,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileState {
+    pub active_profile_id: String,
+    pub profiles: Vec<SettingsProfile>,
+}
+
+impl ProfileState {
+    /// A single default profile holding the given settings, used the first
+    /// time profiles are loaded.
+    pub fn with_default(settings: Settings) -> Self {
+        Self {
+            active_profile_id: DEFAULT_PROFILE_ID.to_string(),
+            profiles: vec![SettingsProfile {
+                id: DEFAULT_PROFILE_ID.to_string(),
+                name: DEFAULT_PROFILE_NAME.to_string(),
+                settings,
+            }],
+        }
+    }
+
+    /// Guarantees at least one profile and an active id that exists. The
+    /// active profile always mirrors the running settings.
+    pub fn sanitized(mut self, current: &Settings) -> Self {
+        if self.profiles.is_empty() {
+            return Self::with_default(current.clone());
+        }
+
+        if self.find(&self.active_profile_id).is_none() {
+            self.active_profile_id = self.profiles[0].id.clone();
+        }
+        self.update_active_settings(current);
+        self
+    }
+
+    pub fn find(&self, id: &str) -> Option<&SettingsProfile> {
+        self.profiles.iter().find(|profile| profile.id == id)
+    }
+
+    pub fn update_active_settings(&mut self, settings: &Settings) {
+        let active_id = self.active_profile_id.clone();
+        if let Some(profile) = self.find_mut(&active_id) {
+            profile.settings = settings.clone();
+        }
+    }
+
+    pub fn create(
+        &mut self,
+        name: &str,
+        settings: Settings,
+        now_ms: i64,
+    ) -> Result<String, AppError> {
+        let name = self.validate_name(name, None)?;
+
+        let mut id = format!("profile-{now_ms}");
+        let mut suffix = 1;
+        while self.find(&id).is_some() {
+            suffix += 1;
+            id = format!("profile-{now_ms}-{suffix}");
+        }
+
+        self.profiles.push(SettingsProfile {
+            id: id.clone(),
+            name,
+            settings,
+        });
+        Ok(id)
+    }
+
+    pub fn rename(&mut self, id: &str, name: &str) -> Result<(), AppError> {
+        let name = self.validate_name(name, Some(id))?;
+        let profile = self.find_mut(id).ok_or_else(|| not_found(id))?;
+        profile.name = name;
+        Ok(())
+    }
+
+    pub fn delete(&mut self, id: &str) -> Result<(), AppError> {
+        if self.find(id).is_none() {
+            return Err(not_found(id));
+        }
+        if id == self.active_profile_id {
+            return Err(AppError::new(Failure::DeleteActiveProfile).caused_by(id));
+        }
+
+        self.profiles.retain(|profile| profile.id != id);
+        Ok(())
+    }
+
+    fn find_mut(&mut self, id: &str) -> Option<&mut SettingsProfile> {
+        self.profiles.iter_mut().find(|profile| profile.id == id)
+    }
+
+    fn validate_name(&self, name: &str, renaming: Option<&str>) -> Result<String, AppError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(AppError::new(Failure::ProfileNameEmpty));
+        }
+        if name.chars().count() > MAX_PROFILE_NAME_CHARS {
+            return Err(
+                AppError::new(Failure::ProfileNameTooLong).caused_by(format!(
+                    "profile name has more than {MAX_PROFILE_NAME_CHARS} characters"
+                )),
+            );
+        }
+
+        let taken = self.profiles.iter().any(|profile| {
+            Some(profile.id.as_str()) != renaming
+                && profile.name.to_lowercase() == name.to_lowercase()
+        });
+        if taken {
+            return Err(AppError::new(Failure::ProfileNameTaken)
+                .caused_by(format!("a profile named \"{name}\" already exists")));
+        }
+
+        Ok(name.to_string())
+    }
+}
+
+pub fn not_found(id: &str) -> AppError {
+    AppError::new(Failure::ProfileNotFound).caused_by(format!("profile not found: {id}"))
+}
+
+/// Settings after switching to `profile`. Only the timer behaviour comes from
+/// the profile; language, backups and the storage backend are app-wide.
+pub fn apply_profile(current: &Settings, profile: &Settings) -> Settings {
+    Settings {
+        focus_ms: profile.focus_ms,
+        short_break_ms: profile.short_break_ms,
+        long_break_ms: profile.long_break_ms,
+        long_break_every: profile.long_break_every,
+        notify_enabled: profile.notify_enabled,
+        sound_enabled: profile.sound_enabled,
+        ..current.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_names_are_trimmed_and_unique() {
+        let mut state = ProfileState::with_default(Settings::default());
+
+        let id = state
+            .create("  Deep work ", Settings::default(), 1_000)
+            .unwrap();
+        assert_eq!(state.find(&id).unwrap().name, "Deep work");
+        assert_eq!(
+            state
+                .create("deep WORK", Settings::default(), 2_000)
+                .unwrap_err()
+                .failure,
+            Failure::ProfileNameTaken
+        );
+        assert!(state.create("   ", Settings::default(), 3_000).is_err());
+
+        assert!(state.rename(&id, "Default").is_err());
+        state.rename(&id, "DEEP WORK").unwrap();
+        assert_eq!(state.find(&id).unwrap().name, "DEEP WORK");
+    }
+
+    #[test]
+    fn active_profile_cannot_be_deleted() {
+        let mut state = ProfileState::with_default(Settings::default());
+        let id = state.create("Study", Settings::default(), 1_000).unwrap();
+
+        assert_eq!(
+            state.delete(DEFAULT_PROFILE_ID).unwrap_err().failure.code(),
+            crate::error::ErrorCode::InvalidStateTransition
+        );
+        state.delete(&id).unwrap();
+        assert!(state.find(&id).is_none());
+        assert!(state.delete(&id).is_err());
+    }
+
+    #[test]
+    fn apply_profile_keeps_app_wide_settings() {
+        let current = Settings {
+            locale: "zh-CN".to_string(),
+            daily_backup_count: 3,
+            ..Settings::default()
+        };
+        let profile = Settings {
+            focus_ms: 50 * 60_000,
+            locale: "en-US".to_string(),
+            ..Settings::default()
+        };
+
+        let next = apply_profile(&current, &profile);
+
+        assert_eq!(next.focus_ms, 50 * 60_000);
+        assert_eq!(next.locale, "zh-CN");
+        assert_eq!(next.daily_backup_count, 3);
+    }
+
+    #[test]
+    fn sanitized_repairs_missing_active_profile() {
+        let mut state = ProfileState::with_default(Settings::default());
+        state.active_profile_id = "gone".to_string();
+        let current = Settings {
+            focus_ms: 30 * 60_000,
+            ..Settings::default()
+        };
+
+        let state = state.sanitized(&current);
+
+        assert_eq!(state.active_profile_id, DEFAULT_PROFILE_ID);
+        assert_eq!(state.profiles[0].settings.focus_ms, 30 * 60_000);
+    }
+}
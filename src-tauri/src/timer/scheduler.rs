@@ -0,0 +1,162 @@
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+const DISPLAY_TICK_MS: i64 = 1_000;
+const CLOCK_JUMP_THRESHOLD_MS: i64 = 2_000;
+
+/// Parks the tick worker until its next deadline or until the timer state
+/// changes from outside the worker.
+#[derive(Default)]
+pub struct TickScheduler {
+    generation: Mutex<u64>,
+    changed: Condvar,
+}
+
+impl TickScheduler {
+    /// Interrupts the current wait so the worker recomputes its deadline.
+    pub fn wake(&self) {
+        let mut generation = self
+            .generation
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        *generation = generation.wrapping_add(1);
+        self.changed.notify_all();
+    }
+
+    pub fn generation(&self) -> u64 {
+        *self
+            .generation
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Waits for `timeout`, or forever when it is `None`. Returns `true` when
+    /// the deadline was reached and `false` when woken since `seen`.
+    pub fn wait(&self, seen: u64, timeout: Option<Duration>) -> bool {
+        let generation = self
+            .generation
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        match timeout {
+            Some(timeout) => {
+                let (generation, result) = self
+                    .changed
+                    .wait_timeout_while(generation, timeout, |generation| *generation == seen)
+                    .unwrap_or_else(|poisoned| poisoned.into_inner());
+                *generation == seen && result.timed_out()
+            }
+            None => {
+                let _generation = self
+                    .changed
+                    .wait_while(generation, |generation| *generation == seen)
+                    .unwrap_or_else(|poisoned| poisoned.into_inner());
+                false
+            }
+        }
+    }
+}
+
+/// Real time until the display should refresh or the phase ends, whichever
+/// is first. `remaining_ms` is countdown time running at `speed`×; the
+/// display still refreshes about once per real second. `None` while stopped.
+pub fn next_tick_delay(remaining_ms: Option<i64>, speed: u32) -> Option<Duration> {
+    let speed = i64::from(speed.max(1));
+    let remaining_ms = remaining_ms?.max(0);
+    let display_tick_ms = DISPLAY_TICK_MS * speed;
+    let until_display_tick = match remaining_ms % display_tick_ms {
+        0 => display_tick_ms,
+        partial => partial,
+    };
+
+    // Rounded up so the worker never wakes just before the deadline.
+    let real_ms = (remaining_ms.min(until_display_tick) + speed - 1) / speed;
+    Some(Duration::from_millis(real_ms as u64))
+}
+
+/// Pairs a wall-clock reading with a monotonic one so a later reading can
+/// tell whether the wall clock moved on its own. Where the monotonic clock
+/// stops during sleep (Linux, macOS) this also catches suspend.
+pub struct ClockProbe {
+    instant: Instant,
+    wall_ms: i64,
+}
+
+impl ClockProbe {
+    pub fn capture(wall_ms: i64) -> Self {
+        Self {
+            instant: Instant::now(),
+            wall_ms,
+        }
+    }
+
+    /// The wall-clock jump, or `None` when there was none.
+    pub fn jump_ms(&self, wall_ms: i64) -> Option<i64> {
+        let monotonic_elapsed_ms =
+            i64::try_from(self.instant.elapsed().as_millis()).unwrap_or(i64::MAX);
+        clock_jump_ms(monotonic_elapsed_ms, wall_ms - self.wall_ms)
+    }
+}
+
+/// How far the wall clock moved beyond monotonic time, ignoring drift below
+/// the detection threshold.
+pub fn clock_jump_ms(monotonic_elapsed_ms: i64, wall_elapsed_ms: i64) -> Option<i64> {
+    let jump_ms = wall_elapsed_ms.saturating_sub(monotonic_elapsed_ms);
+    (jump_ms.abs() >= CLOCK_JUMP_THRESHOLD_MS).then_some(jump_ms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn next_tick_delay_lands_on_second_boundaries_and_the_deadline() {
+        assert_eq!(next_tick_delay(None, 1), None);
+        assert_eq!(
+            next_tick_delay(Some(1_500_250), 1),
+            Some(Duration::from_millis(250))
+        );
+        assert_eq!(
+            next_tick_delay(Some(3_000), 1),
+            Some(Duration::from_millis(1_000))
+        );
+        assert_eq!(
+            next_tick_delay(Some(400), 1),
+            Some(Duration::from_millis(400))
+        );
+        assert_eq!(next_tick_delay(Some(-5), 1), Some(Duration::ZERO));
+        assert_eq!(
+            next_tick_delay(Some(90_000), 60),
+            Some(Duration::from_millis(500))
+        );
+        assert_eq!(
+            next_tick_delay(Some(60_001), 60),
+            Some(Duration::from_millis(1))
+        );
+    }
+
+    #[test]
+    fn clock_jump_ms_ignores_drift_and_reports_both_directions() {
+        assert_eq!(clock_jump_ms(1_000, 1_150), None);
+        assert_eq!(clock_jump_ms(1_000, 3_601_000), Some(3_600_000));
+        assert_eq!(clock_jump_ms(1_000, -59_000), Some(-60_000));
+    }
+
+    #[test]
+    fn wake_interrupts_an_unbounded_wait() {
+        let scheduler = Arc::new(TickScheduler::default());
+        let seen = scheduler.generation();
+
+        let waiter = {
+            let scheduler = scheduler.clone();
+            thread::spawn(move || scheduler.wait(seen, None))
+        };
+        scheduler.wake();
+
+        assert!(!waiter.join().unwrap());
+        assert!(scheduler.wait(scheduler.generation(), Some(Duration::from_millis(1))));
+        assert!(!scheduler.wait(seen, Some(Duration::from_secs(60))));
+    }
+}
@@ -0,0 +1,249 @@
+use super::engine::{normalize_locale, DayAttribution, Settings, ZH_CN_LOCALE};
+use crate::analytics::zone::parse_time_zone;
+use crate::error::{AppError, Failure};
+use serde::Serialize;
+
+const MS_PER_MINUTE: i64 = 60_000;
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SettingsField {
+    FocusMs,
+    ShortBreakMs,
+    LongBreakMs,
+    LongBreakEvery,
+    DailyBackupCount,
+    DevTimeScale,
+    HomeTimeZone,
+    DayStartHour,
+    HistoryRetentionDays,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum FieldErrorCode {
+    BelowMinimum,
+    AboveMaximum,
+    UnknownTimeZone,
+}
+
+/// One rejected field. `min` and `max` are in the field's own unit
+/// (milliseconds for durations) and only set for range errors.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldError {
+    pub field: SettingsField,
+    pub code: FieldErrorCode,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<i64>,
+    pub message: String,
+}
+
+impl SettingsField {
+    const NUMERIC: [Self; 8] = [
+        Self::FocusMs,
+        Self::ShortBreakMs,
+        Self::LongBreakMs,
+        Self::LongBreakEvery,
+        Self::DailyBackupCount,
+        Self::DevTimeScale,
+        Self::DayStartHour,
+        Self::HistoryRetentionDays,
+    ];
+
+    /// Inclusive bounds in the field's own unit, for numeric fields.
+    pub fn bounds(self) -> Option<(i64, i64)> {
+        match self {
+            Self::FocusMs => Some((MS_PER_MINUTE, 4 * 60 * MS_PER_MINUTE)),
+            Self::ShortBreakMs => Some((MS_PER_MINUTE, 60 * MS_PER_MINUTE)),
+            Self::LongBreakMs => Some((MS_PER_MINUTE, 2 * 60 * MS_PER_MINUTE)),
+            Self::LongBreakEvery => Some((1, 12)),
+            Self::DailyBackupCount => Some((0, 90)),
+            Self::DevTimeScale => Some((1, 600)),
+            Self::HomeTimeZone => None,
+            Self::DayStartHour => Some((0, 23)),
+            Self::HistoryRetentionDays => Some((31, 3650)),
+        }
+    }
+
+    pub fn contains(self, value: i64) -> bool {
+        self.bounds()
+            .is_some_and(|(min, max)| (min..=max).contains(&value))
+    }
+
+    fn value(self, settings: &Settings) -> Option<i64> {
+        match self {
+            Self::FocusMs => Some(settings.focus_ms),
+            Self::ShortBreakMs => Some(settings.short_break_ms),
+            Self::LongBreakMs => Some(settings.long_break_ms),
+            Self::LongBreakEvery => Some(i64::from(settings.long_break_every)),
+            Self::DailyBackupCount => Some(i64::from(settings.daily_backup_count)),
+            Self::DevTimeScale => Some(i64::from(settings.dev_time_scale)),
+            Self::HomeTimeZone => None,
+            Self::DayStartHour => Some(i64::from(settings.day_start_hour)),
+            Self::HistoryRetentionDays => settings.history_retention_days.map(i64::from),
+        }
+    }
+
+    fn is_duration(self) -> bool {
+        matches!(self, Self::FocusMs | Self::ShortBreakMs | Self::LongBreakMs)
+    }
+
+    fn label(self, locale: &str) -> &'static str {
+        if normalize_locale(locale) == ZH_CN_LOCALE {
+            match self {
+                Self::FocusMs => "\u{4E13}\u{6CE8}\u{65F6}\u{957F}",
+                Self::ShortBreakMs => "\u{77ED}\u{4F11}\u{606F}\u{65F6}\u{957F}",
+                Self::LongBreakMs => "\u{957F}\u{4F11}\u{606F}\u{65F6}\u{957F}",
+                Self::LongBreakEvery => "\u{957F}\u{4F11}\u{606F}\u{95F4}\u{9694}",
+                Self::DailyBackupCount => "\u{6BCF}\u{65E5}\u{5907}\u{4EFD}\u{6570}\u{91CF}",
+                Self::DevTimeScale => "\u{5F00}\u{53D1}\u{8005}\u{65F6}\u{95F4}\u{500D}\u{901F}",
+                Self::HomeTimeZone => "\u{5E38}\u{7528}\u{65F6}\u{533A}",
+                Self::DayStartHour => "\u{6BCF}\u{5929}\u{5F00}\u{59CB}\u{65F6}\u{95F4}",
+                Self::HistoryRetentionDays => "\u{5386}\u{53F2}\u{4FDD}\u{7559}\u{5929}\u{6570}",
+            }
+        } else {
+            match self {
+                Self::FocusMs => "Focus length",
+                Self::ShortBreakMs => "Short break length",
+                Self::LongBreakMs => "Long break length",
+                Self::LongBreakEvery => "Long break interval",
+                Self::DailyBackupCount => "Daily backup count",
+                Self::DevTimeScale => "Time scale",
+                Self::HomeTimeZone => "Home time zone",
+                Self::DayStartHour => "Day start hour",
+                Self::HistoryRetentionDays => "History retention",
+            }
+        }
+    }
+
+    fn range_message(self, (min, max): (i64, i64), locale: &str) -> String {
+        let label = self.label(locale);
+        let zh = normalize_locale(locale) == ZH_CN_LOCALE;
+
+        if self.is_duration() {
+            let (min, max) = (min / MS_PER_MINUTE, max / MS_PER_MINUTE);
+            if zh {
+                format!("{label}\u{5FC5}\u{987B}\u{5728} {min} \u{5230} {max} \u{5206}\u{949F}\u{4E4B}\u{95F4}\u{3002}")
+            } else {
+                format!("{label} must be between {min} and {max} minutes.")
+            }
+        } else if zh {
+            format!("{label}\u{5FC5}\u{987B}\u{5728} {min} \u{5230} {max} \u{4E4B}\u{95F4}\u{3002}")
+        } else {
+            format!("{label} must be between {min} and {max}.")
+        }
+    }
+
+    fn unknown_zone_message(self, name: &str, locale: &str) -> String {
+        let label = self.label(locale);
+        if normalize_locale(locale) == ZH_CN_LOCALE {
+            format!("{label}\u{201C}{name}\u{201D}\u{65E0}\u{6CD5}\u{8BC6}\u{522B}\u{3002}")
+        } else {
+            format!("{label} \"{name}\" is not a recognized time zone.")
+        }
+    }
+}
+
+/// Checks every field and reports all violations at once. Messages
+/// use the locale of the submitted settings.
+pub fn validate(settings: &Settings) -> Result<(), AppError> {
+    let mut fields: Vec<FieldError> = SettingsField::NUMERIC
+        .into_iter()
+        .filter_map(|field| {
+            let value = field.value(settings)?;
+            let (min, max) = field.bounds()?;
+            let code = if value < min {
+                FieldErrorCode::BelowMinimum
+            } else if value > max {
+                FieldErrorCode::AboveMaximum
+            } else {
+                return None;
+            };
+
+            Some(FieldError {
+                field,
+                code,
+                min: Some(min),
+                max: Some(max),
+                message: field.range_message((min, max), &settings.locale),
+            })
+        })
+        .collect();
+
+    let unknown_zone = settings
+        .home_time_zone
+        .as_deref()
+        .filter(|name| parse_time_zone(name).is_none());
+    if let (DayAttribution::HomeZone, Some(name)) = (settings.day_attribution, unknown_zone) {
+        fields.push(FieldError {
+            field: SettingsField::HomeTimeZone,
+            code: FieldErrorCode::UnknownTimeZone,
+            min: None,
+            max: None,
+            message: SettingsField::HomeTimeZone.unknown_zone_message(name, &settings.locale),
+        });
+    }
+
+    if fields.is_empty() {
+        Ok(())
+    } else {
+        Err(AppError::new(Failure::InvalidSettings).with_fields(fields))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_reports_every_out_of_range_field() {
+        let settings = Settings {
+            focus_ms: 30 * 24 * 60 * MS_PER_MINUTE,
+            short_break_ms: 500,
+            long_break_every: 0,
+            ..Settings::default()
+        };
+
+        let error = validate(&settings).unwrap_err();
+        assert_eq!(error.failure, Failure::InvalidSettings);
+        let fields = error.fields;
+
+        let summary: Vec<(SettingsField, FieldErrorCode)> = fields
+            .iter()
+            .map(|error| (error.field, error.code))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (SettingsField::FocusMs, FieldErrorCode::AboveMaximum),
+                (SettingsField::ShortBreakMs, FieldErrorCode::BelowMinimum),
+                (SettingsField::LongBreakEvery, FieldErrorCode::BelowMinimum),
+            ]
+        );
+        assert_eq!(
+            fields[0].message,
+            "Focus length must be between 1 and 240 minutes."
+        );
+    }
+
+    #[test]
+    fn validate_accepts_defaults_and_localizes_messages() {
+        assert_eq!(validate(&Settings::default()), Ok(()));
+
+        let settings = Settings {
+            long_break_every: 20,
+            locale: ZH_CN_LOCALE.to_string(),
+            ..Settings::default()
+        };
+        let error = validate(&settings).unwrap_err();
+
+        assert!(error.fields[0].message.contains("1 \u{5230} 12"));
+        assert_eq!(
+            serde_json::to_value(error.localize(ZH_CN_LOCALE)).unwrap()["fields"][0]["field"],
+            "longBreakEvery"
+        );
+    }
+}
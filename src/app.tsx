@@ -5,24 +5,37 @@ import {
 } from '@tauri-apps/plugin-notification';
 import {
   listenProductivityUpdated,
+  listenStreakMilestoneReached,
+  timerGetFocusDistribution,
   timerGetInsights,
   timerUpdateGoals,
 } from './features/insights/insightsEvents';
-import type { GoalSettings, InsightsSnapshot } from './features/insights/types';
+import type {
+  GoalSettings,
+  InsightsSnapshot,
+  StreakMilestone,
+} from './features/insights/types';
 import InsightsView from './features/insights/InsightsView';
 import SettingsView from './features/settings/SettingsView';
 import TimerView from './features/timer/TimerView';
 import { detectPreferredLocale, I18nProvider, useI18n } from './i18n';
 import {
   listenPhaseCompleted,
-  listenTimerTick,
+  listenStateFileRejected,
+  listenTimerStateChanged,
   timerGetState,
   timerReset,
   timerResume,
   timerStart,
   timerUpdateSettings,
 } from './features/timer/timerEvents';
-import type { Settings, TimerSnapshot } from './features/timer/types';
+import {
+  isCommandError,
+  type SessionLabels,
+  type Settings,
+  type SettingsFieldError,
+  type TimerSnapshot,
+} from './features/timer/types';
 
 type ActiveTab = 'timer' | 'settings' | 'insights';
 
@@ -70,8 +83,10 @@ interface AppContentProps {
   isGoalBusy: boolean;
   errorMessage: string | null;
   insightsErrorMessage: string | null;
+  streakMilestone: StreakMilestone | null;
+  settingsFieldErrors: SettingsFieldError[];
   onSwitchTab: (tab: ActiveTab) => void;
-  onStart: () => Promise<void>;
+  onStart: (labels: SessionLabels) => Promise<void>;
   onResume: () => Promise<void>;
   onReset: () => Promise<void>;
   onSaveSettings: (settings: Settings) => Promise<void>;
@@ -87,7 +102,12 @@ function TimerInfoPopover({ snapshot, insightsSnapshot }: TimerInfoPopoverProps)
   const { messages } = useI18n();
   const [isOpen, setIsOpen] = useState(false);
   const popoverRef = useRef<HTMLDivElement | null>(null);
-  const dailyFocusCompleted = insightsSnapshot?.summaries.daily.focusCompleted ?? 0;
+  const dailySummary = insightsSnapshot?.summaries.daily;
+  const dailyFocusUnit = dailySummary?.focusUnit ?? 'pomodoros';
+  const dailyFocusCompleted =
+    (dailyFocusUnit === 'minutes'
+      ? dailySummary?.focusMinutes
+      : dailySummary?.focusCompleted) ?? 0;
   const dailyFocusTarget = Math.max(insightsSnapshot?.goals.daily.focusTarget ?? 1, 1);
   const dailyGoalPercent = Math.min(
     100,
@@ -139,7 +159,11 @@ function TimerInfoPopover({ snapshot, insightsSnapshot }: TimerInfoPopoverProps)
       </button>
       <div className="info-popover-panel" role="tooltip">
         <strong>
-          {messages.timer.dailyGoalProgress(dailyFocusCompleted, dailyFocusTarget)}
+          {messages.timer.dailyGoalProgress(
+            dailyFocusCompleted,
+            dailyFocusTarget,
+            dailyFocusUnit,
+          )}
         </strong>
         <div
           className="info-progress"
@@ -147,6 +171,7 @@ function TimerInfoPopover({ snapshot, insightsSnapshot }: TimerInfoPopoverProps)
           aria-label={messages.timer.dailyGoalProgress(
             dailyFocusCompleted,
             dailyFocusTarget,
+            dailyFocusUnit,
           )}
           aria-valuemin={0}
           aria-valuemax={100}
@@ -176,6 +201,8 @@ function AppContent({
   isGoalBusy,
   errorMessage,
   insightsErrorMessage,
+  streakMilestone,
+  settingsFieldErrors,
   onSwitchTab,
   onStart,
   onResume,
@@ -231,6 +258,11 @@ function AppContent({
           {errorMessage}
         </p>
       ) : null}
+      {streakMilestone ? (
+        <p className="app-notice" role="status">
+          {messages.insights.streakMilestone(streakMilestone.kind, streakMilestone.days)}
+        </p>
+      ) : null}
 
       {tab === 'timer' ? (
         <TimerView
@@ -247,12 +279,17 @@ function AppContent({
           goals={insightsSnapshot?.goals ?? null}
           isBusy={isBusy}
           isGoalBusy={isGoalBusy}
+          fieldErrors={settingsFieldErrors}
           onSave={onSaveSettings}
           onSaveGoals={onSaveGoals}
         />
       ) : null}
       {tab === 'insights' ? (
-        <InsightsView snapshot={insightsSnapshot} errorMessage={insightsErrorMessage} />
+        <InsightsView
+          snapshot={insightsSnapshot}
+          errorMessage={insightsErrorMessage}
+          onLoadDistribution={timerGetFocusDistribution}
+        />
       ) : null}
     </main>
   );
@@ -271,6 +308,9 @@ function formatErrorMessage(error: unknown): string {
   if (typeof error === 'string' && error) {
     return error;
   }
+  if (isCommandError(error)) {
+    return error.message;
+  }
   return 'Operation failed';
 }
 
@@ -284,6 +324,10 @@ export default function App() {
   const [isGoalBusy, setIsGoalBusy] = useState(false);
   const [errorMessage, setErrorMessage] = useState<string | null>(null);
   const [insightsErrorMessage, setInsightsErrorMessage] = useState<string | null>(null);
+  const [streakMilestone, setStreakMilestone] = useState<StreakMilestone | null>(null);
+  const [settingsFieldErrors, setSettingsFieldErrors] = useState<
+    SettingsFieldError[]
+  >([]);
 
   useEffect(() => {
     let mounted = true;
@@ -300,12 +344,15 @@ export default function App() {
           setInsightsSnapshot(initialInsights);
         }
 
-        const unlistenTick = await listenTimerTick((nextSnapshot) => {
+        const unlistenStateChanged = await listenTimerStateChanged((payload) => {
           if (mounted) {
-            setSnapshot(nextSnapshot);
+            setSnapshot(payload.snapshot);
+            if (payload.cause === 'started') {
+              setStreakMilestone(null);
+            }
           }
         });
-        cleanups.push(unlistenTick);
+        cleanups.push(unlistenStateChanged);
 
         const unlistenCompleted = await listenPhaseCompleted((payload) => {
           if (payload.soundEnabled) {
@@ -323,6 +370,20 @@ export default function App() {
           },
         );
         cleanups.push(unlistenProductivity);
+
+        const unlistenMilestone = await listenStreakMilestoneReached((milestone) => {
+          if (mounted) {
+            setStreakMilestone(milestone);
+          }
+        });
+        cleanups.push(unlistenMilestone);
+
+        const unlistenRejected = await listenStateFileRejected((payload) => {
+          if (mounted) {
+            setErrorMessage(`${payload.file}: ${payload.error.message}`);
+          }
+        });
+        cleanups.push(unlistenRejected);
       } catch (error) {
         if (mounted) {
           const message = formatErrorMessage(error);
@@ -367,13 +428,13 @@ export default function App() {
     [isBusy],
   );
 
-  const handleStart = useCallback(async () => {
+  const handleStart = useCallback(async (labels: SessionLabels) => {
     await executeSnapshotAction(async () => {
       const warningMessage =
         snapshot?.settings.notifyEnabled && !(await ensureNotificationPermission())
           ? 'Notification permission not granted. System notifications might not appear.'
           : null;
-      const nextSnapshot = await timerStart();
+      const nextSnapshot = await timerStart(labels);
       return { snapshot: nextSnapshot, warningMessage };
     });
   }, [executeSnapshotAction, snapshot?.settings.notifyEnabled]);
@@ -409,7 +470,16 @@ export default function App() {
             'Notification permission not granted. Notifications remain disabled.';
         }
 
-        const nextSnapshot = await timerUpdateSettings(settingsToSave);
+        let nextSnapshot: TimerSnapshot;
+        try {
+          nextSnapshot = await timerUpdateSettings(settingsToSave);
+          setSettingsFieldErrors([]);
+        } catch (error) {
+          if (isCommandError(error) && error.code === 'validation') {
+            setSettingsFieldErrors(error.fields ?? []);
+          }
+          throw error;
+        }
         const nextInsights = await timerGetInsights();
         return {
           snapshot: nextSnapshot,
@@ -455,6 +525,8 @@ export default function App() {
         isGoalBusy={isGoalBusy}
         errorMessage={errorMessage}
         insightsErrorMessage={insightsErrorMessage}
+        streakMilestone={streakMilestone}
+        settingsFieldErrors={settingsFieldErrors}
         onSwitchTab={setTab}
         onStart={handleStart}
         onResume={handleResume}
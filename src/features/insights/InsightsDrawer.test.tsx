@@ -23,6 +23,7 @@ function buildHeatmap(length = 371): HeatmapDay[] {
     date: dayKeyFromDate(addDays(start, index)),
     focusCompleted: index % 9,
     longCycleCompleted: index % 3,
+    focusMinutes: (index % 9) * 25,
   }));
 }
 
@@ -33,6 +34,8 @@ function buildSnapshot(): InsightsSnapshot {
       daily: {
         focusCompleted: 6,
         longCycleCompleted: 1,
+        focusMinutes: 150,
+        focusUnit: 'pomodoros',
         focusTarget: 8,
         longCycleTarget: 2,
         focusRate: 0.75,
@@ -42,6 +45,8 @@ function buildSnapshot(): InsightsSnapshot {
       weekly: {
         focusCompleted: 38,
         longCycleCompleted: 8,
+        focusMinutes: 950,
+        focusUnit: 'pomodoros',
         focusTarget: 40,
         longCycleTarget: 10,
         focusRate: 0.95,
@@ -51,6 +56,8 @@ function buildSnapshot(): InsightsSnapshot {
       monthly: {
         focusCompleted: 142,
         longCycleCompleted: 35,
+        focusMinutes: 3550,
+        focusUnit: 'pomodoros',
         focusTarget: 160,
         longCycleTarget: 40,
         focusRate: 0.89,
@@ -59,10 +66,17 @@ function buildSnapshot(): InsightsSnapshot {
       },
     },
     goals: {
-      daily: { focusTarget: 8, longCycleTarget: 2 },
-      weekly: { focusTarget: 40, longCycleTarget: 10 },
-      monthly: { focusTarget: 160, longCycleTarget: 40 },
+      daily: { focusTarget: 8, longCycleTarget: 2, focusUnit: 'pomodoros' },
+      weekly: { focusTarget: 40, longCycleTarget: 10, focusUnit: 'pomodoros' },
+      monthly: { focusTarget: 160, longCycleTarget: 40, focusUnit: 'pomodoros' },
+      restDays: [],
     },
+    monthlyTrend: [],
+    streaks: {
+      goalMet: { current: 3, longest: 5 },
+      active: { current: 4, longest: 9 },
+    },
+    unmovedByDayChange: null,
   };
 }
 
@@ -248,7 +248,12 @@ export default function InsightsDrawer({
                     <div
                       key={day.date}
                       className={`insights-heatmap-cell ${levelClass(day.focusCompleted)}`}
-                      title={`${day.date}: ${day.focusCompleted} / ${day.longCycleCompleted}`}
+                      title={messages.insights.heatmapCellTitle(
+                        day.date,
+                        day.focusCompleted,
+                        day.longCycleCompleted,
+                        day.focusMinutes,
+                      )}
                     />
                   ))}
                 </div>
@@ -1,7 +1,7 @@
 import { fireEvent, render, screen, waitFor } from '@testing-library/react';
-import { describe, expect, it } from 'vitest';
+import { describe, expect, it, vi } from 'vitest';
 import { I18nProvider } from '../../i18n';
-import type { HeatmapDay, InsightsSnapshot } from './types';
+import type { FocusDistribution, HeatmapDay, InsightsSnapshot } from './types';
 import InsightsView from './InsightsView';
 
 function dayKeyFromDate(date: Date): string {
@@ -23,6 +23,7 @@ function buildHeatmap(length = 371): HeatmapDay[] {
     date: dayKeyFromDate(addDays(start, index)),
     focusCompleted: index % 9,
     longCycleCompleted: index % 3,
+    focusMinutes: (index % 9) * 25,
   }));
 }
 
@@ -33,6 +34,8 @@ function buildSnapshot(): InsightsSnapshot {
       daily: {
         focusCompleted: 6,
         longCycleCompleted: 1,
+        focusMinutes: 150,
+        focusUnit: 'pomodoros',
         focusTarget: 8,
         longCycleTarget: 2,
         focusRate: 0.75,
@@ -42,6 +45,8 @@ function buildSnapshot(): InsightsSnapshot {
       weekly: {
         focusCompleted: 38,
         longCycleCompleted: 8,
+        focusMinutes: 950,
+        focusUnit: 'pomodoros',
         focusTarget: 40,
         longCycleTarget: 10,
         focusRate: 0.95,
@@ -51,6 +56,8 @@ function buildSnapshot(): InsightsSnapshot {
       monthly: {
         focusCompleted: 142,
         longCycleCompleted: 35,
+        focusMinutes: 3550,
+        focusUnit: 'pomodoros',
         focusTarget: 160,
         longCycleTarget: 40,
         focusRate: 0.89,
@@ -59,10 +66,31 @@ function buildSnapshot(): InsightsSnapshot {
       },
     },
     goals: {
-      daily: { focusTarget: 8, longCycleTarget: 2 },
-      weekly: { focusTarget: 40, longCycleTarget: 10 },
-      monthly: { focusTarget: 160, longCycleTarget: 40 },
+      daily: { focusTarget: 8, longCycleTarget: 2, focusUnit: 'pomodoros' },
+      weekly: { focusTarget: 40, longCycleTarget: 10, focusUnit: 'pomodoros' },
+      monthly: { focusTarget: 160, longCycleTarget: 40, focusUnit: 'pomodoros' },
+      restDays: [],
     },
+    monthlyTrend: [],
+    streaks: {
+      goalMet: { current: 3, longest: 5 },
+      active: { current: 4, longest: 9 },
+    },
+    unmovedByDayChange: null,
+  };
+}
+
+function buildDistribution(): FocusDistribution {
+  const minutes = Array.from({ length: 7 }, () => Array.from({ length: 24 }, () => 0));
+  minutes[1][9] = 50;
+  minutes[3][14] = 25;
+  return {
+    from: '2026-02-04',
+    to: '2026-03-05',
+    minutes,
+    totalMinutes: 75,
+    bestHour: 9,
+    bestWeekday: 'tuesday',
   };
 }
 
@@ -70,7 +98,11 @@ describe('InsightsView', () => {
   it('renders as page and supports dimension switching', async () => {
     const { container } = render(
       <I18nProvider locale="en-US">
-        <InsightsView snapshot={buildSnapshot()} errorMessage={null} />
+        <InsightsView
+          snapshot={buildSnapshot()}
+          errorMessage={null}
+          onLoadDistribution={vi.fn(async () => buildDistribution())}
+        />
       </I18nProvider>,
     );
 
@@ -78,6 +110,7 @@ describe('InsightsView', () => {
     expect(
       container.querySelectorAll('.insights-heatmap-grid .insights-heatmap-cell'),
     ).toHaveLength(371);
+    expect(screen.getByText('3 days (longest 5)')).toBeInTheDocument();
 
     const weeklyTab = screen.getByRole('tab', { name: 'Weekly' });
     expect(weeklyTab).toHaveAttribute('aria-selected', 'true');
@@ -88,4 +121,51 @@ describe('InsightsView', () => {
       expect(container.querySelectorAll('circle[data-series="focus"]')).toHaveLength(30);
     });
   });
+
+  it('shows the focus distribution for the selected range', async () => {
+    const onLoadDistribution = vi.fn(async () => buildDistribution());
+    const { container } = render(
+      <I18nProvider locale="en-US">
+        <InsightsView
+          snapshot={buildSnapshot()}
+          errorMessage={null}
+          onLoadDistribution={onLoadDistribution}
+        />
+      </I18nProvider>,
+    );
+
+    expect(
+      await screen.findByText('Best hour: 09:00 · Best day: Tue'),
+    ).toBeInTheDocument();
+    expect(
+      container.querySelectorAll('.insights-distribution-grid .insights-heatmap-cell'),
+    ).toHaveLength(7 * 24);
+
+    fireEvent.click(screen.getByRole('tab', { name: '1 year' }));
+    await waitFor(() => {
+      expect(onLoadDistribution).toHaveBeenLastCalledWith(
+        expect.objectContaining({ to: expect.any(String) }),
+      );
+      expect(onLoadDistribution).toHaveBeenCalledTimes(2);
+    });
+  });
+
+  it('reports counts a day boundary change left in place', () => {
+    render(
+      <I18nProvider locale="en-US">
+        <InsightsView
+          snapshot={{
+            ...buildSnapshot(),
+            unmovedByDayChange: { focusCompleted: 2, longCycleCompleted: 1 },
+          }}
+          errorMessage={null}
+          onLoadDistribution={vi.fn(async () => buildDistribution())}
+        />
+      </I18nProvider>,
+    );
+
+    expect(
+      screen.getByText(/2 focus sessions and 1 long cycles have no session record/),
+    ).toBeInTheDocument();
+  });
 });
@@ -1,14 +1,36 @@
 import { useEffect, useMemo, useState } from 'react';
 import { useI18n } from '../../i18n';
-import { buildTrendSeries } from './trends';
-import type { ChartDimension, InsightsSnapshot, TrendPoint } from './types';
+import { buildTrendSeries, buildYearOverYear, distributionRequest } from './trends';
+import type {
+  ChartDimension,
+  DistributionRange,
+  FocusDistribution,
+  FocusDistributionRequest,
+  InsightsSnapshot,
+  StreakKind,
+  TrendPoint,
+} from './types';
 
 interface InsightsViewProps {
   snapshot: InsightsSnapshot | null;
   errorMessage: string | null;
+  onLoadDistribution: (request: FocusDistributionRequest) => Promise<FocusDistribution>;
 }
 
 const DIMENSION_ORDER: ChartDimension[] = ['daily', 'weekly', 'monthly'];
+const STREAK_ORDER: StreakKind[] = ['goalMet', 'active'];
+const DISTRIBUTION_RANGES: DistributionRange[] = ['week', 'month', 'quarter', 'year'];
+const WEEKDAY_ORDER = [
+  'monday',
+  'tuesday',
+  'wednesday',
+  'thursday',
+  'friday',
+  'saturday',
+  'sunday',
+] as const;
+
+const formatHour = (hour: number) => `${String(hour).padStart(2, '0')}:00`;
 
 function levelClass(focusCompleted: number): string {
   if (focusCompleted <= 0) {
@@ -178,7 +200,118 @@ function TrendLineChart({ points, dimension }: TrendLineChartProps) {
   );
 }
 
-export default function InsightsView({ snapshot, errorMessage }: InsightsViewProps) {
+function distributionLevelClass(minutes: number, maxMinutes: number): string {
+  if (minutes <= 0) {
+    return 'insights-heatmap-cell--0';
+  }
+  const level = Math.min(4, Math.ceil((minutes / maxMinutes) * 4));
+  return `insights-heatmap-cell--${level}`;
+}
+
+interface FocusDistributionCardProps {
+  snapshot: InsightsSnapshot;
+  onLoadDistribution: (request: FocusDistributionRequest) => Promise<FocusDistribution>;
+}
+
+function FocusDistributionCard({ snapshot, onLoadDistribution }: FocusDistributionCardProps) {
+  const { messages } = useI18n();
+  const [range, setRange] = useState<DistributionRange>('month');
+  const [distribution, setDistribution] = useState<FocusDistribution | null>(null);
+
+  // Reloads with each snapshot so completions show up without switching ranges.
+  useEffect(() => {
+    let active = true;
+    onLoadDistribution(distributionRequest(range))
+      .then((next) => {
+        if (active) {
+          setDistribution(next);
+        }
+      })
+      .catch(() => {
+        if (active) {
+          setDistribution(null);
+        }
+      });
+    return () => {
+      active = false;
+    };
+  }, [range, snapshot, onLoadDistribution]);
+
+  const maxMinutes = distribution
+    ? Math.max(1, ...distribution.minutes.flatMap((hours) => hours))
+    : 1;
+
+  return (
+    <div className="insights-distribution">
+      <div className="insights-chart-head">
+        <h4>{messages.insights.distributionTitle}</h4>
+        <div
+          className="insights-dimension-switch"
+          role="tablist"
+          aria-label={messages.insights.distributionTitle}
+        >
+          {DISTRIBUTION_RANGES.map((item) => (
+            <button
+              key={item}
+              type="button"
+              role="tab"
+              aria-selected={range === item}
+              className={range === item ? 'is-active' : ''}
+              onClick={() => setRange(item)}
+            >
+              {messages.insights.distributionRanges[item]}
+            </button>
+          ))}
+        </div>
+      </div>
+      {distribution ? (
+        <>
+          <div
+            className="insights-distribution-grid"
+            role="img"
+            aria-label={messages.insights.distributionTitle}
+          >
+            {WEEKDAY_ORDER.map((weekday, dayIndex) => (
+              <div key={weekday} className="insights-distribution-row">
+                <span className="insights-distribution-label">
+                  {messages.settings.weekdays[weekday]}
+                </span>
+                {distribution.minutes[dayIndex]?.map((minutes, hour) => (
+                  <span
+                    key={hour}
+                    className={`insights-heatmap-cell ${distributionLevelClass(minutes, maxMinutes)}`}
+                    data-minutes={minutes}
+                    title={messages.insights.distributionCellTitle(
+                      messages.settings.weekdays[weekday],
+                      formatHour(hour),
+                      minutes,
+                    )}
+                  />
+                ))}
+              </div>
+            ))}
+          </div>
+          <p className="insights-distribution-best">
+            {distribution.bestHour !== null && distribution.bestWeekday !== null
+              ? messages.insights.distributionBest(
+                  formatHour(distribution.bestHour),
+                  messages.settings.weekdays[distribution.bestWeekday],
+                )
+              : messages.insights.distributionEmpty}
+          </p>
+        </>
+      ) : (
+        <p className="insights-chart-empty">{messages.insights.loading}</p>
+      )}
+    </div>
+  );
+}
+
+export default function InsightsView({
+  snapshot,
+  errorMessage,
+  onLoadDistribution,
+}: InsightsViewProps) {
   const { locale, messages } = useI18n();
   const [dimension, setDimension] = useState<ChartDimension>('weekly');
 
@@ -186,6 +319,10 @@ export default function InsightsView({ snapshot, errorMessage }: InsightsViewPro
     () => (snapshot ? buildTrendSeries(snapshot.heatmap, dimension, locale) : []),
     [snapshot, dimension, locale],
   );
+  const yearOverYear = useMemo(
+    () => (snapshot ? buildYearOverYear(snapshot.monthlyTrend) : null),
+    [snapshot],
+  );
 
   return (
     <section className="insights-page">
@@ -218,7 +355,12 @@ export default function InsightsView({ snapshot, errorMessage }: InsightsViewPro
                   <div
                     key={day.date}
                     className={`insights-heatmap-cell ${levelClass(day.focusCompleted)}`}
-                    title={`${day.date}: ${day.focusCompleted} / ${day.longCycleCompleted}`}
+                    title={messages.insights.heatmapCellTitle(
+                      day.date,
+                      day.focusCompleted,
+                      day.longCycleCompleted,
+                      day.focusMinutes,
+                    )}
                   />
                 ))}
               </div>
@@ -257,6 +399,50 @@ export default function InsightsView({ snapshot, errorMessage }: InsightsViewPro
               </div>
               <TrendLineChart points={trendSeries} dimension={dimension} />
             </div>
+
+            {snapshot.unmovedByDayChange ? (
+              <p className="insights-day-change-note" role="status">
+                {messages.insights.unmovedByDayChange(
+                  snapshot.unmovedByDayChange.focusCompleted,
+                  snapshot.unmovedByDayChange.longCycleCompleted,
+                )}
+              </p>
+            ) : null}
+
+            <div className="insights-streaks">
+              <h4>{messages.insights.streaksTitle}</h4>
+              <dl>
+                {STREAK_ORDER.map((kind) => (
+                  <div key={kind} data-streak={kind}>
+                    <dt>{messages.insights.streakKinds[kind]}</dt>
+                    <dd>
+                      {messages.insights.streakValue(
+                        snapshot.streaks[kind].current,
+                        snapshot.streaks[kind].longest,
+                      )}
+                    </dd>
+                  </div>
+                ))}
+              </dl>
+            </div>
+
+            <FocusDistributionCard
+              snapshot={snapshot}
+              onLoadDistribution={onLoadDistribution}
+            />
+
+            {yearOverYear ? (
+              <div className="insights-year-over-year">
+                <h4>{messages.insights.yearOverYearTitle}</h4>
+                <p>
+                  {messages.insights.yearOverYear(
+                    yearOverYear.month,
+                    yearOverYear.current.focusCompleted,
+                    yearOverYear.previousYear?.focusCompleted ?? null,
+                  )}
+                </p>
+              </div>
+            ) : null}
           </section>
         </section>
       )}
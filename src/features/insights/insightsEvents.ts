@@ -1,6 +1,17 @@
 import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
-import type { GoalSettings, InsightsSnapshot } from './types';
+import type {
+  ExportSummary,
+  FocusDistribution,
+  FocusDistributionRequest,
+  GoalSettings,
+  HistoryExportRequest,
+  HistoryImportRequest,
+  ImportPreview,
+  InsightsSnapshot,
+  SessionExportRequest,
+  StreakMilestone,
+} from './types';
 
 export const timerGetInsights = (): Promise<InsightsSnapshot> =>
   invoke('timer_get_insights');
@@ -9,9 +20,32 @@ export const timerUpdateGoals = (
   goals: GoalSettings,
 ): Promise<InsightsSnapshot> => invoke('timer_update_goals', { goals });
 
+export const timerGetFocusDistribution = (
+  request: FocusDistributionRequest,
+): Promise<FocusDistribution> => invoke('timer_get_focus_distribution', { request });
+
+export const timerExportSessionsIcs = (
+  request: SessionExportRequest,
+): Promise<ExportSummary> => invoke('timer_export_sessions_ics', { request });
+
+export const timerExportHistory = (
+  request: HistoryExportRequest,
+): Promise<ExportSummary> => invoke('timer_export_history', { request });
+
+export const timerImportHistory = (
+  request: HistoryImportRequest,
+): Promise<ImportPreview> => invoke('timer_import_history', { request });
+
 export const listenProductivityUpdated = (
   handler: (snapshot: InsightsSnapshot) => void,
 ): Promise<() => void> =>
   listen<InsightsSnapshot>('productivity_updated', (event) => {
     handler(event.payload);
   });
+
+export const listenStreakMilestoneReached = (
+  handler: (milestone: StreakMilestone) => void,
+): Promise<() => void> =>
+  listen<StreakMilestone>('streak_milestone_reached', (event) => {
+    handler(event.payload);
+  });
@@ -1,6 +1,6 @@
 import { describe, expect, it } from 'vitest';
-import type { HeatmapDay } from './types';
-import { buildTrendSeries } from './trends';
+import type { HeatmapDay, MonthTotal } from './types';
+import { buildTrendSeries, buildYearOverYear, distributionRequest } from './trends';
 
 function dayKeyFromDate(date: Date): string {
   const year = date.getUTCFullYear();
@@ -21,6 +21,7 @@ function buildSequentialHeatmap(length: number): HeatmapDay[] {
     date: dayKeyFromDate(addDays(start, index)),
     focusCompleted: index % 5,
     longCycleCompleted: index % 2,
+    focusMinutes: (index % 5) * 25,
   }));
 }
 
@@ -37,8 +38,8 @@ describe('buildTrendSeries', () => {
 
   it('aggregates weekly with locale-aware week start', () => {
     const heatmap: HeatmapDay[] = [
-      { date: '2026-02-08', focusCompleted: 2, longCycleCompleted: 0 },
-      { date: '2026-02-09', focusCompleted: 3, longCycleCompleted: 1 },
+      { date: '2026-02-08', focusCompleted: 2, longCycleCompleted: 0, focusMinutes: 50 },
+      { date: '2026-02-09', focusCompleted: 3, longCycleCompleted: 1, focusMinutes: 75 },
     ];
 
     const enSeries = buildTrendSeries(heatmap, 'weekly', 'en-US');
@@ -55,9 +56,9 @@ describe('buildTrendSeries', () => {
 
   it('aggregates monthly across year boundary', () => {
     const heatmap: HeatmapDay[] = [
-      { date: '2026-12-30', focusCompleted: 2, longCycleCompleted: 1 },
-      { date: '2026-12-31', focusCompleted: 1, longCycleCompleted: 0 },
-      { date: '2027-01-01', focusCompleted: 4, longCycleCompleted: 1 },
+      { date: '2026-12-30', focusCompleted: 2, longCycleCompleted: 1, focusMinutes: 50 },
+      { date: '2026-12-31', focusCompleted: 1, longCycleCompleted: 0, focusMinutes: 25 },
+      { date: '2027-01-01', focusCompleted: 4, longCycleCompleted: 1, focusMinutes: 100 },
     ];
 
     const series = buildTrendSeries(heatmap, 'monthly', 'en-US');
@@ -72,9 +73,9 @@ describe('buildTrendSeries', () => {
 
   it('aggregates both focus and long-cycle in the same bucket', () => {
     const heatmap: HeatmapDay[] = [
-      { date: '2026-01-05', focusCompleted: 1, longCycleCompleted: 1 },
-      { date: '2026-01-06', focusCompleted: 3, longCycleCompleted: 0 },
-      { date: '2026-01-07', focusCompleted: 2, longCycleCompleted: 1 },
+      { date: '2026-01-05', focusCompleted: 1, longCycleCompleted: 1, focusMinutes: 25 },
+      { date: '2026-01-06', focusCompleted: 3, longCycleCompleted: 0, focusMinutes: 75 },
+      { date: '2026-01-07', focusCompleted: 2, longCycleCompleted: 1, focusMinutes: 50 },
     ];
 
     const series = buildTrendSeries(heatmap, 'weekly', 'en-US');
@@ -84,3 +85,41 @@ describe('buildTrendSeries', () => {
     expect(series[0]?.longCycleCompleted).toBe(2);
   });
 });
+
+describe('buildYearOverYear', () => {
+  const month = (key: string, focusCompleted: number): MonthTotal => ({
+    month: key,
+    focusCompleted,
+    longCycleCompleted: 0,
+    focusMinutes: focusCompleted * 25,
+  });
+
+  it('pairs the latest month with the same month a year earlier', () => {
+    const comparison = buildYearOverYear([
+      month('2025-03', 40),
+      month('2025-04', 12),
+      month('2026-03', 55),
+    ]);
+
+    expect(comparison?.month).toBe('2026-03');
+    expect(comparison?.current.focusCompleted).toBe(55);
+    expect(comparison?.previousYear?.focusCompleted).toBe(40);
+  });
+
+  it('reports a missing previous year', () => {
+    expect(buildYearOverYear([month('2026-03', 5)])?.previousYear).toBeNull();
+    expect(buildYearOverYear([])).toBeNull();
+  });
+});
+
+describe('distributionRequest', () => {
+  it('covers the selected number of days ending today', () => {
+    const today = new Date(2026, 2, 5, 15, 30);
+
+    expect(distributionRequest('week', today)).toEqual({
+      from: '2026-02-27',
+      to: '2026-03-05',
+    });
+    expect(distributionRequest('year', today).from).toBe('2025-03-06');
+  });
+});
@@ -1,5 +1,13 @@
 import type { LocaleCode } from '../../i18n';
-import type { ChartDimension, HeatmapDay, TrendPoint } from './types';
+import type {
+  ChartDimension,
+  DistributionRange,
+  FocusDistributionRequest,
+  HeatmapDay,
+  MonthTotal,
+  TrendPoint,
+  YearOverYear,
+} from './types';
 
 const DAILY_WINDOW = 30;
 const WEEKLY_WINDOW = 12;
@@ -34,6 +42,44 @@ export function buildTrendSeries(
   }
 }
 
+const DISTRIBUTION_RANGE_DAYS: Record<DistributionRange, number> = {
+  week: 7,
+  month: 30,
+  quarter: 90,
+  year: 365,
+};
+
+/** The last days of `range`, ending today in local time. */
+export function distributionRequest(
+  range: DistributionRange,
+  today: Date = new Date(),
+): FocusDistributionRequest {
+  const from = new Date(today.getFullYear(), today.getMonth(), today.getDate());
+  from.setDate(from.getDate() - DISTRIBUTION_RANGE_DAYS[range] + 1);
+  return { from: localDayKey(from), to: localDayKey(today) };
+}
+
+function localDayKey(date: Date): string {
+  const month = String(date.getMonth() + 1).padStart(2, '0');
+  const day = String(date.getDate()).padStart(2, '0');
+  return `${date.getFullYear()}-${month}-${day}`;
+}
+
+/** Compares the latest month with the same month one year earlier. */
+export function buildYearOverYear(monthlyTrend: MonthTotal[]): YearOverYear | null {
+  const current = monthlyTrend[monthlyTrend.length - 1];
+  if (!current) {
+    return null;
+  }
+  const [year, month] = current.month.split('-');
+  const previousKey = `${Number(year) - 1}-${month}`;
+  return {
+    month: current.month,
+    current,
+    previousYear: monthlyTrend.find((item) => item.month === previousKey) ?? null,
+  };
+}
+
 export function aggregateDaily(
   heatmap: HeatmapDay[],
   window = DAILY_WINDOW,
@@ -1,26 +1,42 @@
 export type PeriodKey = 'daily' | 'weekly' | 'monthly';
 export type ChartDimension = PeriodKey;
 
+export type GoalUnit = 'pomodoros' | 'minutes';
+
 export interface GoalPair {
   focusTarget: number;
   longCycleTarget: number;
+  focusUnit: GoalUnit;
 }
 
+export type DayOfWeek =
+  | 'monday'
+  | 'tuesday'
+  | 'wednesday'
+  | 'thursday'
+  | 'friday'
+  | 'saturday'
+  | 'sunday';
+
 export interface GoalSettings {
   daily: GoalPair;
   weekly: GoalPair;
   monthly: GoalPair;
+  restDays: DayOfWeek[];
 }
 
 export interface HeatmapDay {
   date: string;
   focusCompleted: number;
   longCycleCompleted: number;
+  focusMinutes: number;
 }
 
 export interface PeriodSummary {
   focusCompleted: number;
   longCycleCompleted: number;
+  focusMinutes: number;
+  focusUnit: GoalUnit;
   focusTarget: number;
   longCycleTarget: number;
   focusRate: number;
@@ -38,6 +54,44 @@ export interface InsightsSnapshot {
   heatmap: HeatmapDay[];
   summaries: PeriodSummaries;
   goals: GoalSettings;
+  monthlyTrend: MonthTotal[];
+  streaks: Streaks;
+  unmovedByDayChange: UnmovedDayCounts | null;
+}
+
+export interface UnmovedDayCounts {
+  focusCompleted: number;
+  longCycleCompleted: number;
+}
+
+export interface Streak {
+  current: number;
+  longest: number;
+}
+
+export interface Streaks {
+  goalMet: Streak;
+  active: Streak;
+}
+
+export type StreakKind = keyof Streaks;
+
+export interface StreakMilestone {
+  kind: StreakKind;
+  days: number;
+}
+
+export interface MonthTotal {
+  month: string;
+  focusCompleted: number;
+  longCycleCompleted: number;
+  focusMinutes: number;
+}
+
+export interface YearOverYear {
+  month: string;
+  current: MonthTotal;
+  previousYear: MonthTotal | null;
 }
 
 export interface TrendPoint {
@@ -46,3 +100,77 @@ export interface TrendPoint {
   focusCompleted: number;
   longCycleCompleted: number;
 }
+
+export interface FocusDistributionRequest {
+  from: string;
+  to: string;
+}
+
+export interface FocusDistribution {
+  from: string;
+  to: string;
+  /** Focus minutes by weekday, Monday first, then by hour of day. */
+  minutes: number[][];
+  totalMinutes: number;
+  bestHour: number | null;
+  bestWeekday: DayOfWeek | null;
+}
+
+export type DistributionRange = 'week' | 'month' | 'quarter' | 'year';
+
+export interface SessionExportRequest {
+  from: string;
+  to: string;
+  includeAbandoned: boolean;
+  outputPath: string;
+}
+
+export type HistoryExportFormat = 'csv' | 'json';
+
+export interface HistoryExportRequest {
+  format: HistoryExportFormat;
+  from: string;
+  to: string;
+  outputPath: string;
+}
+
+export interface ImportColumnMapping {
+  dateColumn: string;
+  dateFormat?: string | null;
+  countColumn?: string | null;
+  longCycleColumn?: string | null;
+  minutesColumn?: string | null;
+  filterColumn?: string | null;
+  filterValue?: string | null;
+  idColumn?: string | null;
+  delimiter?: string | null;
+}
+
+export interface HistoryImportRequest {
+  inputPath: string;
+  mapping: ImportColumnMapping;
+  dryRun: boolean;
+}
+
+export interface ImportPreview {
+  dryRun: boolean;
+  rowsRead: number;
+  rowsImported: number;
+  duplicateRows: number;
+  skippedRows: number;
+  outOfRetentionRows: number;
+  daysAdded: number;
+  daysUpdated: number;
+  monthsUpdated: number;
+  overlapDays: string[];
+  overlapMonths: string[];
+  focusCompletedAdded: number;
+  longCycleCompletedAdded: number;
+  firstDay: string | null;
+  lastDay: string | null;
+}
+
+export interface ExportSummary {
+  outputPath: string;
+  exported: number;
+}
@@ -12,12 +12,20 @@ const sampleSettings = {
   notifyEnabled: true,
   soundEnabled: true,
   locale: 'en-US' as const,
+  dailyBackupCount: 7,
+  storageBackend: 'json' as const,
+  clockJumpPolicy: 'honorWallClock' as const,
+  devTimeScale: 1,
+  dayAttribution: 'completionZone' as const,
+  dayStartHour: 0,
+  historyRetentionDays: 400,
 };
 
 const sampleGoals: GoalSettings = {
-  daily: { focusTarget: 8, longCycleTarget: 2 },
-  weekly: { focusTarget: 40, longCycleTarget: 10 },
-  monthly: { focusTarget: 160, longCycleTarget: 40 },
+  daily: { focusTarget: 8, longCycleTarget: 2, focusUnit: 'pomodoros' },
+  weekly: { focusTarget: 40, longCycleTarget: 10, focusUnit: 'pomodoros' },
+  monthly: { focusTarget: 160, longCycleTarget: 40, focusUnit: 'pomodoros' },
+  restDays: [],
 };
 
 describe('SettingsView', () => {
@@ -45,6 +53,7 @@ describe('SettingsView', () => {
     fireEvent.change(dailyGoalInput, {
       target: { value: '9' },
     });
+    fireEvent.click(screen.getByLabelText('Sun'));
     fireEvent.click(screen.getByRole('button', { name: 'Save Changes' }));
 
     await waitFor(() => {
@@ -56,10 +65,75 @@ describe('SettingsView', () => {
 
     expect(onSaveGoals).toHaveBeenCalledWith(
       expect.objectContaining({
-        daily: expect.objectContaining({ focusTarget: 9, longCycleTarget: 2 }),
+        daily: expect.objectContaining({
+          focusTarget: 9,
+          longCycleTarget: 2,
+          focusUnit: 'pomodoros',
+        }),
         weekly: sampleGoals.weekly,
         monthly: sampleGoals.monthly,
+        restDays: ['sunday'],
       }),
     );
   });
+
+  it('converts the daily goal when switching it to focus minutes', async () => {
+    const onSaveGoals = vi.fn(async () => {});
+
+    render(
+      <I18nProvider locale="en-US">
+        <SettingsView
+          settings={sampleSettings}
+          goals={sampleGoals}
+          isBusy={false}
+          isGoalBusy={false}
+          onSave={vi.fn(async () => {})}
+          onSaveGoals={onSaveGoals}
+        />
+      </I18nProvider>,
+    );
+
+    fireEvent.change(screen.getByLabelText('Daily Goal Unit'), {
+      target: { value: 'minutes' },
+    });
+    expect(screen.getByLabelText('Daily Focus Minutes Target')).toHaveValue(200);
+
+    fireEvent.click(screen.getByRole('button', { name: 'Save Changes' }));
+
+    await waitFor(() => {
+      expect(onSaveGoals).toHaveBeenCalledWith(
+        expect.objectContaining({
+          daily: expect.objectContaining({ focusTarget: 200, focusUnit: 'minutes' }),
+        }),
+      );
+    });
+  });
+
+  it('shows field-level validation errors from the backend', () => {
+    render(
+      <I18nProvider locale="en-US">
+        <SettingsView
+          settings={sampleSettings}
+          goals={sampleGoals}
+          isBusy={false}
+          isGoalBusy={false}
+          fieldErrors={[
+            {
+              field: 'focusMs',
+              code: 'aboveMaximum',
+              min: 60_000,
+              max: 240 * 60_000,
+              message: 'Focus length must be between 1 and 240 minutes.',
+            },
+          ]}
+          onSave={vi.fn(async () => {})}
+          onSaveGoals={vi.fn(async () => {})}
+        />
+      </I18nProvider>,
+    );
+
+    expect(screen.getByRole('alert')).toHaveTextContent(
+      'Focus length must be between 1 and 240 minutes.',
+    );
+  });
 });
@@ -1,13 +1,20 @@
 import { useEffect, useState } from 'react';
 import { normalizeLocale, useI18n, type LocaleCode } from '../../i18n';
-import type { GoalSettings } from '../insights/types';
-import type { Settings } from '../timer/types';
+import type { DayOfWeek, GoalSettings, GoalUnit } from '../insights/types';
+import type {
+  ClockJumpPolicy,
+  DayAttribution,
+  Settings,
+  SettingsField,
+  SettingsFieldError,
+} from '../timer/types';
 
 interface SettingsViewProps {
   settings: Settings;
   goals: GoalSettings | null;
   isBusy: boolean;
   isGoalBusy: boolean;
+  fieldErrors?: SettingsFieldError[];
   onSave: (settings: Settings) => Promise<void>;
   onSaveGoals: (goals: GoalSettings) => Promise<void>;
 }
@@ -20,10 +27,35 @@ interface FormState {
   notifyEnabled: boolean;
   soundEnabled: boolean;
   locale: LocaleCode;
+  clockJumpPolicy: ClockJumpPolicy;
+  devTimeScale: number;
+  dayAttribution: DayAttribution;
+  homeTimeZone: string;
+  dayStartHour: number;
+  keepHistoryForever: boolean;
+  historyRetentionDays: number;
 }
 
 const MS_PER_MINUTE = 60_000;
 
+const systemTimeZone = () => Intl.DateTimeFormat().resolvedOptions().timeZone;
+
+const DAY_START_HOURS = Array.from({ length: 24 }, (_, hour) => hour);
+
+const DEFAULT_HISTORY_RETENTION_DAYS = 400;
+
+const DAYS_OF_WEEK: DayOfWeek[] = [
+  'monday',
+  'tuesday',
+  'wednesday',
+  'thursday',
+  'friday',
+  'saturday',
+  'sunday',
+];
+
+const formatHour = (hour: number) => `${String(hour).padStart(2, '0')}:00`;
+
 function settingsToFormState(settings: Settings): FormState {
   return {
     focusMinutes: Math.max(1, Math.round(settings.focusMs / MS_PER_MINUTE)),
@@ -36,6 +68,13 @@ function settingsToFormState(settings: Settings): FormState {
     notifyEnabled: settings.notifyEnabled,
     soundEnabled: settings.soundEnabled,
     locale: normalizeLocale(settings.locale),
+    clockJumpPolicy: settings.clockJumpPolicy,
+    devTimeScale: settings.devTimeScale,
+    dayAttribution: settings.dayAttribution,
+    homeTimeZone: settings.homeTimeZone ?? systemTimeZone(),
+    dayStartHour: settings.dayStartHour,
+    keepHistoryForever: settings.historyRetentionDays === null,
+    historyRetentionDays: settings.historyRetentionDays ?? DEFAULT_HISTORY_RETENTION_DAYS,
   };
 }
 
@@ -60,6 +99,7 @@ function formStateToSettings(form: FormState, fallback: Settings): Settings {
     Math.round(fallback.longBreakMs / MS_PER_MINUTE),
   );
   return {
+    ...fallback,
     focusMs:
       sanitizePositiveInteger(form.focusMinutes, fallbackFocusMinutes) *
       MS_PER_MINUTE,
@@ -76,11 +116,25 @@ function formStateToSettings(form: FormState, fallback: Settings): Settings {
     notifyEnabled: form.notifyEnabled,
     soundEnabled: form.soundEnabled,
     locale: form.locale,
+    clockJumpPolicy: form.clockJumpPolicy,
+    devTimeScale: sanitizePositiveInteger(form.devTimeScale, fallback.devTimeScale),
+    dayAttribution: form.dayAttribution,
+    homeTimeZone:
+      form.dayAttribution === 'homeZone' ? form.homeTimeZone.trim() : fallback.homeTimeZone,
+    dayStartHour: form.dayStartHour,
+    historyRetentionDays: form.keepHistoryForever
+      ? null
+      : sanitizePositiveInteger(
+          form.historyRetentionDays,
+          fallback.historyRetentionDays ?? DEFAULT_HISTORY_RETENTION_DAYS,
+        ),
   };
 }
 
 function mergeDailyGoalSettings(
   dailyFocusTarget: number,
+  dailyFocusUnit: GoalUnit,
+  restDays: DayOfWeek[],
   fallback: GoalSettings,
 ): GoalSettings {
   return {
@@ -90,9 +144,11 @@ function mergeDailyGoalSettings(
         fallback.daily.focusTarget,
       ),
       longCycleTarget: fallback.daily.longCycleTarget,
+      focusUnit: dailyFocusUnit,
     },
     weekly: { ...fallback.weekly },
     monthly: { ...fallback.monthly },
+    restDays: DAYS_OF_WEEK.filter((day) => restDays.includes(day)),
   };
 }
 
@@ -101,6 +157,7 @@ export default function SettingsView({
   goals,
   isBusy,
   isGoalBusy,
+  fieldErrors = [],
   onSave,
   onSaveGoals,
 }: SettingsViewProps) {
@@ -109,6 +166,10 @@ export default function SettingsView({
   const [dailyGoalTarget, setDailyGoalTarget] = useState<number | null>(
     goals ? goals.daily.focusTarget : null,
   );
+  const [dailyGoalUnit, setDailyGoalUnit] = useState<GoalUnit>(
+    goals?.daily.focusUnit ?? 'pomodoros',
+  );
+  const [restDays, setRestDays] = useState<DayOfWeek[]>(goals?.restDays ?? []);
 
   useEffect(() => {
     setForm(settingsToFormState(settings));
@@ -120,14 +181,50 @@ export default function SettingsView({
     settings.notifyEnabled,
     settings.soundEnabled,
     settings.locale,
+    settings.clockJumpPolicy,
+    settings.devTimeScale,
+    settings.dayAttribution,
+    settings.homeTimeZone,
+    settings.dayStartHour,
+    settings.historyRetentionDays,
   ]);
 
   useEffect(() => {
     setDailyGoalTarget(goals ? goals.daily.focusTarget : null);
+    setDailyGoalUnit(goals?.daily.focusUnit ?? 'pomodoros');
+    setRestDays(goals?.restDays ?? []);
   }, [goals]);
 
   const isSaving = isBusy || isGoalBusy;
 
+  const errorFor = (field: SettingsField) =>
+    fieldErrors.find((fieldError) => fieldError.field === field)?.message ?? null;
+
+  const renderFieldError = (field: SettingsField) => {
+    const message = errorFor(field);
+    return message ? (
+      <span className="settings-field-error" role="alert">
+        {message}
+      </span>
+    ) : null;
+  };
+
+  // Switching units keeps the goal the same size, counted in focus-length pomodoros.
+  const handleDailyGoalUnitChange = (unit: GoalUnit) => {
+    if (unit === dailyGoalUnit) {
+      return;
+    }
+    const pomodoroMinutes = Math.max(1, form.focusMinutes || 1);
+    setDailyGoalTarget((prev) =>
+      prev === null
+        ? prev
+        : unit === 'minutes'
+          ? prev * pomodoroMinutes
+          : Math.max(1, Math.round(prev / pomodoroMinutes)),
+    );
+    setDailyGoalUnit(unit);
+  };
+
   const handleSaveAll = async () => {
     await onSave(formStateToSettings(form, settings));
 
@@ -135,7 +232,9 @@ export default function SettingsView({
       return;
     }
 
-    await onSaveGoals(mergeDailyGoalSettings(dailyGoalTarget, goals));
+    await onSaveGoals(
+      mergeDailyGoalSettings(dailyGoalTarget, dailyGoalUnit, restDays, goals),
+    );
   };
 
   return (
@@ -152,6 +251,7 @@ export default function SettingsView({
                 step={1}
                 disabled={isSaving}
                 value={form.focusMinutes}
+                aria-invalid={errorFor('focusMs') !== null}
                 onChange={(event) =>
                   setForm((prev) => ({
                     ...prev,
@@ -159,6 +259,7 @@ export default function SettingsView({
                   }))
                 }
               />
+              {renderFieldError('focusMs')}
             </label>
             <label>
               {messages.settings.shortBreakMinutes}
@@ -168,6 +269,7 @@ export default function SettingsView({
                 step={1}
                 disabled={isSaving}
                 value={form.shortBreakMinutes}
+                aria-invalid={errorFor('shortBreakMs') !== null}
                 onChange={(event) =>
                   setForm((prev) => ({
                     ...prev,
@@ -175,6 +277,7 @@ export default function SettingsView({
                   }))
                 }
               />
+              {renderFieldError('shortBreakMs')}
             </label>
             <label>
               {messages.settings.longBreakMinutes}
@@ -184,6 +287,7 @@ export default function SettingsView({
                 step={1}
                 disabled={isSaving}
                 value={form.longBreakMinutes}
+                aria-invalid={errorFor('longBreakMs') !== null}
                 onChange={(event) =>
                   setForm((prev) => ({
                     ...prev,
@@ -191,6 +295,7 @@ export default function SettingsView({
                   }))
                 }
               />
+              {renderFieldError('longBreakMs')}
             </label>
             <label>
               {messages.settings.longBreakEvery}
@@ -200,6 +305,7 @@ export default function SettingsView({
                 step={1}
                 disabled={isSaving}
                 value={form.longBreakEvery}
+                aria-invalid={errorFor('longBreakEvery') !== null}
                 onChange={(event) =>
                   setForm((prev) => ({
                     ...prev,
@@ -207,6 +313,7 @@ export default function SettingsView({
                   }))
                 }
               />
+              {renderFieldError('longBreakEvery')}
             </label>
             <label>
               {messages.settings.language}
@@ -228,6 +335,143 @@ export default function SettingsView({
                 </option>
               </select>
             </label>
+            <label>
+              {messages.settings.clockJumpPolicy}
+              <select
+                disabled={isSaving}
+                value={form.clockJumpPolicy}
+                onChange={(event) =>
+                  setForm((prev) => ({
+                    ...prev,
+                    clockJumpPolicy: event.target.value as ClockJumpPolicy,
+                  }))
+                }
+              >
+                <option value="honorWallClock">
+                  {messages.settings.clockJumpPolicyOptions.honorWallClock}
+                </option>
+                <option value="extendBySuspension">
+                  {messages.settings.clockJumpPolicyOptions.extendBySuspension}
+                </option>
+                <option value="abandon">
+                  {messages.settings.clockJumpPolicyOptions.abandon}
+                </option>
+              </select>
+            </label>
+            <label>
+              {messages.settings.dayAttribution}
+              <select
+                disabled={isSaving}
+                value={form.dayAttribution}
+                onChange={(event) =>
+                  setForm((prev) => ({
+                    ...prev,
+                    dayAttribution: event.target.value as DayAttribution,
+                  }))
+                }
+              >
+                <option value="completionZone">
+                  {messages.settings.dayAttributionOptions.completionZone}
+                </option>
+                <option value="homeZone">
+                  {messages.settings.dayAttributionOptions.homeZone}
+                </option>
+              </select>
+            </label>
+            {form.dayAttribution === 'homeZone' ? (
+              <label>
+                {messages.settings.homeTimeZone}
+                <input
+                  type="text"
+                  disabled={isSaving}
+                  value={form.homeTimeZone}
+                  aria-invalid={errorFor('homeTimeZone') !== null}
+                  onChange={(event) =>
+                    setForm((prev) => ({
+                      ...prev,
+                      homeTimeZone: event.target.value,
+                    }))
+                  }
+                />
+                {renderFieldError('homeTimeZone')}
+              </label>
+            ) : null}
+            <label>
+              {messages.settings.dayStartHour}
+              <select
+                disabled={isSaving}
+                value={form.dayStartHour}
+                aria-invalid={errorFor('dayStartHour') !== null}
+                onChange={(event) =>
+                  setForm((prev) => ({
+                    ...prev,
+                    dayStartHour: Number(event.target.value),
+                  }))
+                }
+              >
+                {DAY_START_HOURS.map((hour) => (
+                  <option key={hour} value={hour}>
+                    {formatHour(hour)}
+                  </option>
+                ))}
+              </select>
+              {renderFieldError('dayStartHour')}
+            </label>
+            <label className="settings-toggle">
+              <input
+                type="checkbox"
+                disabled={isSaving}
+                checked={form.keepHistoryForever}
+                onChange={(event) =>
+                  setForm((prev) => ({
+                    ...prev,
+                    keepHistoryForever: event.target.checked,
+                  }))
+                }
+              />
+              {messages.settings.keepHistoryForever}
+            </label>
+            {form.keepHistoryForever ? null : (
+              <label>
+                {messages.settings.historyRetentionDays}
+                <input
+                  type="number"
+                  min={31}
+                  max={3650}
+                  step={1}
+                  disabled={isSaving}
+                  value={form.historyRetentionDays}
+                  aria-invalid={errorFor('historyRetentionDays') !== null}
+                  onChange={(event) =>
+                    setForm((prev) => ({
+                      ...prev,
+                      historyRetentionDays: Number(event.target.value),
+                    }))
+                  }
+                />
+                {renderFieldError('historyRetentionDays')}
+              </label>
+            )}
+            {import.meta.env.DEV ? (
+              <label>
+                {messages.settings.devTimeScale}
+                <input
+                  type="number"
+                  min={1}
+                  step={1}
+                  disabled={isSaving}
+                  value={form.devTimeScale}
+                  aria-invalid={errorFor('devTimeScale') !== null}
+                  onChange={(event) =>
+                    setForm((prev) => ({
+                      ...prev,
+                      devTimeScale: event.target.valueAsNumber,
+                    }))
+                  }
+                />
+                {renderFieldError('devTimeScale')}
+              </label>
+            ) : null}
           </div>
           <label className="settings-toggle">
             <input
@@ -257,7 +501,24 @@ export default function SettingsView({
           <h3>{messages.settings.goalsTitle}</h3>
           {dailyGoalTarget !== null ? (
             <label className="settings-goal-single">
-              {messages.settings.dailyPomodoroTarget}
+              {messages.settings.dailyGoalUnit}
+              <select
+                disabled={isSaving}
+                value={dailyGoalUnit}
+                onChange={(event) =>
+                  handleDailyGoalUnitChange(event.target.value as GoalUnit)
+                }
+              >
+                <option value="pomodoros">{messages.settings.goalUnits.pomodoros}</option>
+                <option value="minutes">{messages.settings.goalUnits.minutes}</option>
+              </select>
+            </label>
+          ) : null}
+          {dailyGoalTarget !== null ? (
+            <label className="settings-goal-single">
+              {dailyGoalUnit === 'minutes'
+                ? messages.settings.dailyMinuteTarget
+                : messages.settings.dailyPomodoroTarget}
               <input
                 type="number"
                 min={1}
@@ -270,6 +531,28 @@ export default function SettingsView({
           ) : (
             <p className="settings-goals-loading">{messages.settings.goalsLoading}</p>
           )}
+          {goals ? (
+            <fieldset className="settings-rest-days">
+              <legend>{messages.settings.restDays}</legend>
+              {DAYS_OF_WEEK.map((day) => (
+                <label key={day} className="settings-toggle">
+                  <input
+                    type="checkbox"
+                    disabled={isSaving}
+                    checked={restDays.includes(day)}
+                    onChange={(event) =>
+                      setRestDays((prev) =>
+                        event.target.checked
+                          ? [...prev, day]
+                          : prev.filter((item) => item !== day),
+                      )
+                    }
+                  />
+                  {messages.settings.weekdays[day]}
+                </label>
+              ))}
+            </fieldset>
+          ) : null}
         </section>
 
         <button
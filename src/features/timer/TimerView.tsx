@@ -1,11 +1,11 @@
 import { useEffect, useState } from 'react';
 import { useI18n } from '../../i18n';
-import type { TimerSnapshot } from './types';
+import type { SessionLabels, TimerSnapshot } from './types';
 
 interface TimerViewProps {
   snapshot: TimerSnapshot;
   isBusy: boolean;
-  onStart: () => Promise<void>;
+  onStart: (labels: SessionLabels) => Promise<void>;
   onResume: () => Promise<void>;
   onReset: () => Promise<void>;
 }
@@ -39,10 +39,15 @@ export default function TimerView({
 }: TimerViewProps) {
   const { messages } = useI18n();
   const [nowMs, setNowMs] = useState<number>(() => Date.now());
+  const [task, setTask] = useState<string>(snapshot.task ?? '');
+  const [project, setProject] = useState<string>(snapshot.project ?? '');
   const phaseDuration = Math.max(phaseDurationMs(snapshot), 1);
   const liveRemainingMs =
     snapshot.isRunning && snapshot.endAtMs !== null
-      ? Math.max(snapshot.endAtMs - nowMs, 0)
+      ? Math.max(
+          (snapshot.endAtMs - nowMs) * snapshot.settings.devTimeScale,
+          0,
+        )
       : snapshot.remainingMs;
   const isFreshPhase = snapshot.remainingMs >= phaseDuration;
   const remainingRatio = Math.min(
@@ -81,7 +86,7 @@ export default function TimerView({
     }
 
     if (isFreshPhase) {
-      await onStart();
+      await onStart({ task, project });
       return;
     }
 
@@ -116,6 +121,34 @@ export default function TimerView({
           <div className="timer-countdown">{formatMs(liveRemainingMs)}</div>
         </div>
       </div>
+      {snapshot.isRunning || !isFreshPhase ? (
+        snapshot.task || snapshot.project ? (
+          <div className="timer-labels">
+            {[snapshot.task, snapshot.project].filter(Boolean).join(' · ')}
+          </div>
+        ) : null
+      ) : (
+        <div className="timer-labels timer-labels--editable">
+          <label>
+            {messages.timer.task}
+            <input
+              type="text"
+              value={task}
+              placeholder={messages.timer.taskPlaceholder}
+              onChange={(event) => setTask(event.target.value)}
+            />
+          </label>
+          <label>
+            {messages.timer.project}
+            <input
+              type="text"
+              value={project}
+              placeholder={messages.timer.projectPlaceholder}
+              onChange={(event) => setProject(event.target.value)}
+            />
+          </label>
+        </div>
+      )}
       <div className="timer-actions timer-actions--single">
         <button
           className={`timer-primary ${
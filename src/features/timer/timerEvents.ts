@@ -2,14 +2,23 @@ import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
 import type {
   PhaseCompletedPayload,
+  ProfileState,
+  SessionLabels,
   Settings,
+  StateFileRejectedPayload,
+  StorageIssue,
   TimerSnapshot,
+  TimerStateChangedPayload,
 } from './types';
 
 export const timerGetState = (): Promise<TimerSnapshot> =>
   invoke('timer_get_state');
 
-export const timerStart = (): Promise<TimerSnapshot> => invoke('timer_start');
+export const timerStart = (labels: SessionLabels = {}): Promise<TimerSnapshot> =>
+  invoke('timer_start', {
+    task: labels.task ?? null,
+    project: labels.project ?? null,
+  });
 
 export const timerResume = (): Promise<TimerSnapshot> => invoke('timer_resume');
 
@@ -19,10 +28,36 @@ export const timerUpdateSettings = (
   settings: Settings,
 ): Promise<TimerSnapshot> => invoke('timer_update_settings', { settings });
 
-export const listenTimerTick = (
-  handler: (snapshot: TimerSnapshot) => void,
+export const timerCreateBackup = (outputPath: string): Promise<void> =>
+  invoke('timer_create_backup', { outputPath });
+
+export const timerRestoreBackup = (inputPath: string): Promise<TimerSnapshot> =>
+  invoke('timer_restore_backup', { inputPath });
+
+export const timerGetStorageIssues = (): Promise<StorageIssue[]> =>
+  invoke('timer_get_storage_issues');
+
+export const timerGetProfiles = (): Promise<ProfileState> =>
+  invoke('timer_get_profiles');
+
+export const timerCreateProfile = (name: string): Promise<ProfileState> =>
+  invoke('timer_create_profile', { name });
+
+export const timerRenameProfile = (
+  id: string,
+  name: string,
+): Promise<ProfileState> => invoke('timer_rename_profile', { id, name });
+
+export const timerDeleteProfile = (id: string): Promise<ProfileState> =>
+  invoke('timer_delete_profile', { id });
+
+export const timerSwitchProfile = (id: string): Promise<TimerSnapshot> =>
+  invoke('timer_switch_profile', { id });
+
+export const listenTimerStateChanged = (
+  handler: (payload: TimerStateChangedPayload) => void,
 ): Promise<() => void> =>
-  listen<TimerSnapshot>('timer_tick', (event) => {
+  listen<TimerStateChangedPayload>('timer_state_changed', (event) => {
     handler(event.payload);
   });
 
@@ -32,3 +67,17 @@ export const listenPhaseCompleted = (
   listen<PhaseCompletedPayload>('timer_phase_completed', (event) => {
     handler(event.payload);
   });
+
+export const listenStorageIssuesDetected = (
+  handler: (issues: StorageIssue[]) => void,
+): Promise<() => void> =>
+  listen<StorageIssue[]>('storage_issues_detected', (event) => {
+    handler(event.payload);
+  });
+
+export const listenStateFileRejected = (
+  handler: (payload: StateFileRejectedPayload) => void,
+): Promise<() => void> =>
+  listen<StateFileRejectedPayload>('state_file_rejected', (event) => {
+    handler(event.payload);
+  });
@@ -2,6 +2,12 @@ import type { LocaleCode } from '../../i18n/locale';
 
 export type Phase = 'focus' | 'shortBreak' | 'longBreak';
 
+export type StorageBackend = 'json' | 'sqlite';
+
+export type ClockJumpPolicy = 'honorWallClock' | 'extendBySuspension' | 'abandon';
+
+export type DayAttribution = 'completionZone' | 'homeZone';
+
 export interface Settings {
   focusMs: number;
   shortBreakMs: number;
@@ -10,9 +16,22 @@ export interface Settings {
   notifyEnabled: boolean;
   soundEnabled: boolean;
   locale: LocaleCode;
+  dailyBackupCount: number;
+  storageBackend: StorageBackend;
+  clockJumpPolicy: ClockJumpPolicy;
+  devTimeScale: number;
+  dayAttribution: DayAttribution;
+  homeTimeZone?: string | null;
+  dayStartHour: number;
+  historyRetentionDays: number | null;
 }
 
-export interface TimerSnapshot {
+export interface SessionLabels {
+  task?: string;
+  project?: string;
+}
+
+export interface TimerSnapshot extends SessionLabels {
   phase: Phase;
   isRunning: boolean;
   cycleCount: number;
@@ -21,8 +40,94 @@ export interface TimerSnapshot {
   settings: Settings;
 }
 
+export type StateChangeCause =
+  | 'restored'
+  | 'started'
+  | 'resumed'
+  | 'reset'
+  | 'tick'
+  | 'phaseCompleted'
+  | 'settingsUpdated'
+  | 'profileSwitched'
+  | 'externalEdit'
+  | 'backupRestored'
+  | 'clockJumped';
+
+export interface TimerStateChangedPayload {
+  snapshot: TimerSnapshot;
+  cause: StateChangeCause;
+}
+
 export interface PhaseCompletedPayload {
   finishedPhase: Phase;
   nextPhase: Phase;
   soundEnabled: boolean;
 }
+
+export type SettingsField =
+  | 'focusMs'
+  | 'shortBreakMs'
+  | 'longBreakMs'
+  | 'longBreakEvery'
+  | 'dailyBackupCount'
+  | 'devTimeScale'
+  | 'homeTimeZone'
+  | 'dayStartHour'
+  | 'historyRetentionDays';
+
+export interface SettingsFieldError {
+  field: SettingsField;
+  code: 'belowMinimum' | 'aboveMaximum' | 'unknownTimeZone';
+  min?: number;
+  max?: number;
+  message: string;
+}
+
+export type ErrorCode =
+  | 'storageIo'
+  | 'validation'
+  | 'invalidStateTransition'
+  | 'notFound';
+
+/** Error returned by every command; `message` is in the current locale. */
+export interface CommandError {
+  code: ErrorCode;
+  failure: string;
+  message: string;
+  causes: string[];
+  fields?: SettingsFieldError[];
+}
+
+export function isCommandError(error: unknown): error is CommandError {
+  return (
+    typeof error === 'object' &&
+    error !== null &&
+    typeof (error as { code?: unknown }).code === 'string' &&
+    typeof (error as { message?: unknown }).message === 'string'
+  );
+}
+
+export interface SettingsProfile {
+  id: string;
+  name: string;
+  settings: Settings;
+}
+
+export interface ProfileState {
+  activeProfileId: string;
+  profiles: SettingsProfile[];
+}
+
+export interface StorageIssue {
+  file: string;
+  message: string;
+  quarantinedPath: string | null;
+  recovered: string[];
+  readOnly: boolean;
+  detectedAtMs: number;
+}
+
+export interface StateFileRejectedPayload {
+  file: string;
+  error: CommandError;
+}
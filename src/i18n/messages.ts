@@ -2,6 +2,19 @@ import type { LocaleCode } from './locale';
 
 type PhaseKey = 'focus' | 'shortBreak' | 'longBreak';
 type PeriodKey = 'daily' | 'weekly' | 'monthly';
+type ClockJumpPolicyKey = 'honorWallClock' | 'extendBySuspension' | 'abandon';
+type DayAttributionKey = 'completionZone' | 'homeZone';
+type DayOfWeekKey =
+  | 'monday'
+  | 'tuesday'
+  | 'wednesday'
+  | 'thursday'
+  | 'friday'
+  | 'saturday'
+  | 'sunday';
+type StreakKey = 'goalMet' | 'active';
+type GoalUnitKey = 'pomodoros' | 'minutes';
+type DistributionRangeKey = 'week' | 'month' | 'quarter' | 'year';
 
 export interface I18nMessages {
   loading: string;
@@ -15,9 +28,13 @@ export interface I18nMessages {
     completedFocus: (count: number) => string;
     longBreakEvery: (value: number) => string;
     progress: (completed: number, target: number) => string;
-    dailyGoalProgress: (completed: number, target: number) => string;
+    dailyGoalProgress: (completed: number, target: number, unit: GoalUnitKey) => string;
     dailyGoalPercent: (value: number) => string;
     infoButtonLabel: string;
+    task: string;
+    taskPlaceholder: string;
+    project: string;
+    projectPlaceholder: string;
     actions: {
       start: string;
       resume: string;
@@ -36,10 +53,24 @@ export interface I18nMessages {
     save: string;
     saveAll: string;
     languageOptions: Record<LocaleCode, string>;
+    clockJumpPolicy: string;
+    clockJumpPolicyOptions: Record<ClockJumpPolicyKey, string>;
+    devTimeScale: string;
+    dayAttribution: string;
+    dayAttributionOptions: Record<DayAttributionKey, string>;
+    homeTimeZone: string;
+    dayStartHour: string;
+    keepHistoryForever: string;
+    historyRetentionDays: string;
     goalsTitle: string;
     goalsLoading: string;
     dailyPomodoroTarget: string;
+    dailyMinuteTarget: string;
+    dailyGoalUnit: string;
+    goalUnits: Record<GoalUnitKey, string>;
     goalPeriods: Record<PeriodKey, string>;
+    restDays: string;
+    weekdays: Record<DayOfWeekKey, string>;
     focusTarget: string;
     longCycleTarget: string;
     saveGoals: string;
@@ -65,6 +96,12 @@ export interface I18nMessages {
     longCycleRate: string;
     goalCompleted: string;
     goalInProgress: string;
+    heatmapCellTitle: (
+      date: string,
+      focusCompleted: number,
+      longCycleCompleted: number,
+      focusMinutes: number,
+    ) => string;
     chartTitle: string;
     chartEmpty: string;
     chartHint: string;
@@ -79,6 +116,18 @@ export interface I18nMessages {
     };
     goalsTitle: string;
     saveGoals: string;
+    yearOverYearTitle: string;
+    yearOverYear: (month: string, current: number, previousYear: number | null) => string;
+    unmovedByDayChange: (focusCompleted: number, longCycleCompleted: number) => string;
+    streaksTitle: string;
+    streakKinds: Record<StreakKey, string>;
+    streakValue: (current: number, longest: number) => string;
+    streakMilestone: (kind: StreakKey, days: number) => string;
+    distributionTitle: string;
+    distributionRanges: Record<DistributionRangeKey, string>;
+    distributionCellTitle: (weekday: string, hour: string, minutes: number) => string;
+    distributionBest: (hour: string, weekday: string) => string;
+    distributionEmpty: string;
   };
 }
 
@@ -100,10 +149,14 @@ export const MESSAGES: Record<LocaleCode, I18nMessages> = {
       longBreakEvery: (value: number) => `Long Break Every: ${value}`,
       progress: (completed: number, target: number) =>
         `Progress to Long Break: ${completed}/${target}`,
-      dailyGoalProgress: (completed: number, target: number) =>
-        `Daily Goal: ${completed}/${target}`,
+      dailyGoalProgress: (completed: number, target: number, unit: GoalUnitKey) =>
+        `Daily Goal: ${completed}/${target}${unit === 'minutes' ? ' min' : ''}`,
       dailyGoalPercent: (value: number) => `Completion: ${value}%`,
       infoButtonLabel: 'Show timer progress',
+      task: 'Task',
+      taskPlaceholder: 'What are you working on?',
+      project: 'Project',
+      projectPlaceholder: 'Optional',
       actions: {
         start: 'Start',
         resume: 'Resume',
@@ -125,14 +178,46 @@ export const MESSAGES: Record<LocaleCode, I18nMessages> = {
         'en-US': 'English',
         'zh-CN': '简体中文',
       },
+      clockJumpPolicy: 'After Sleep or Clock Change',
+      clockJumpPolicyOptions: {
+        honorWallClock: 'Keep the original end time',
+        extendBySuspension: 'Pause while asleep',
+        abandon: 'Abandon the session',
+      },
+      devTimeScale: 'Time Scale (dev)',
+      dayAttribution: 'Count Sessions Towards',
+      dayAttributionOptions: {
+        completionZone: 'The local day where they finished',
+        homeZone: 'The day in my home time zone',
+      },
+      homeTimeZone: 'Home Time Zone',
+      dayStartHour: 'Day Starts At',
+      keepHistoryForever: 'Keep Daily History Forever',
+      historyRetentionDays: 'Daily History Kept (days)',
       goalsTitle: 'Goal',
       goalsLoading: 'Loading goals...',
       dailyPomodoroTarget: 'Daily Pomodoro Target',
+      dailyMinuteTarget: 'Daily Focus Minutes Target',
+      dailyGoalUnit: 'Daily Goal Unit',
+      goalUnits: {
+        pomodoros: 'Pomodoros',
+        minutes: 'Focus minutes',
+      },
       goalPeriods: {
         daily: 'Daily',
         weekly: 'Weekly',
         monthly: 'Monthly',
       },
+      restDays: "Rest Days (don't break a streak)",
+      weekdays: {
+        monday: 'Mon',
+        tuesday: 'Tue',
+        wednesday: 'Wed',
+        thursday: 'Thu',
+        friday: 'Fri',
+        saturday: 'Sat',
+        sunday: 'Sun',
+      },
       focusTarget: 'Focus Target',
       longCycleTarget: 'Long Cycle Target',
       saveGoals: 'Save Goals',
@@ -166,6 +251,18 @@ export const MESSAGES: Record<LocaleCode, I18nMessages> = {
       longCycleRate: 'Long Cycle Completion',
       goalCompleted: 'Goal Completed',
       goalInProgress: 'In Progress',
+      heatmapCellTitle: (
+        date: string,
+        focusCompleted: number,
+        longCycleCompleted: number,
+        focusMinutes: number,
+      ) => `${date}: ${focusCompleted} / ${longCycleCompleted}, ${focusMinutes} min`,
+      heatmapCellTitle: (
+        date: string,
+        focusCompleted: number,
+        longCycleCompleted: number,
+        focusMinutes: number,
+      ) => `${date}：${focusCompleted} / ${longCycleCompleted}，${focusMinutes} 分钟`,
       chartTitle: 'Trend Chart',
       chartEmpty: 'No chart data',
       chartHint: 'Hover points to view values.',
@@ -180,6 +277,36 @@ export const MESSAGES: Record<LocaleCode, I18nMessages> = {
       },
       goalsTitle: 'Goals',
       saveGoals: 'Save Goals',
+      yearOverYearTitle: 'Year over Year',
+      yearOverYear: (month: string, current: number, previousYear: number | null) =>
+        previousYear === null
+          ? `${month}: ${current} focus sessions, no data for the same month last year`
+          : `${month}: ${current} focus sessions vs ${previousYear} a year earlier`,
+      unmovedByDayChange: (focusCompleted: number, longCycleCompleted: number) =>
+        `${focusCompleted} focus sessions and ${longCycleCompleted} long cycles have no session record, so they stayed on their original days after the day boundary changed.`,
+      streaksTitle: 'Streaks',
+      streakKinds: {
+        goalMet: 'Daily goal met',
+        active: 'At least one pomodoro',
+      },
+      streakValue: (current: number, longest: number) =>
+        `${current} ${current === 1 ? 'day' : 'days'} (longest ${longest})`,
+      streakMilestone: (kind: StreakKey, days: number) =>
+        kind === 'goalMet'
+          ? `Streak: daily goal met ${days} days in a row!`
+          : `Streak: focused ${days} days in a row!`,
+      distributionTitle: 'When You Focus',
+      distributionRanges: {
+        week: '7 days',
+        month: '30 days',
+        quarter: '90 days',
+        year: '1 year',
+      },
+      distributionCellTitle: (weekday: string, hour: string, minutes: number) =>
+        `${weekday} ${hour}: ${minutes} min`,
+      distributionBest: (hour: string, weekday: string) =>
+        `Best hour: ${hour} · Best day: ${weekday}`,
+      distributionEmpty: 'No completed focus in this range yet.',
     },
   },
   'zh-CN': {
@@ -199,10 +326,14 @@ export const MESSAGES: Record<LocaleCode, I18nMessages> = {
       longBreakEvery: (value: number) => `长休息间隔：${value}`,
       progress: (completed: number, target: number) =>
         `距离长休息进度：${completed}/${target}`,
-      dailyGoalProgress: (completed: number, target: number) =>
-        `每日目标：${completed}/${target}`,
+      dailyGoalProgress: (completed: number, target: number, unit: GoalUnitKey) =>
+        `每日目标：${completed}/${target}${unit === 'minutes' ? ' 分钟' : ''}`,
       dailyGoalPercent: (value: number) => `完成度：${value}%`,
       infoButtonLabel: '查看计时进度',
+      task: '任务',
+      taskPlaceholder: '正在做什么？',
+      project: '项目',
+      projectPlaceholder: '可选',
       actions: {
         start: '开始',
         resume: '继续',
@@ -224,17 +355,79 @@ export const MESSAGES: Record<LocaleCode, I18nMessages> = {
         'en-US': 'English',
         'zh-CN': '简体中文',
       },
+      clockJumpPolicy: '睡眠或时钟变化后',
+      clockJumpPolicyOptions: {
+        honorWallClock: '保持原结束时间',
+        extendBySuspension: '睡眠期间暂停计时',
+        abandon: '放弃本次专注',
+      },
+      devTimeScale: '时间倍速（开发）',
+      dayAttribution: '专注计入日期',
+      dayAttributionOptions: {
+        completionZone: '完成时所在地的日期',
+        homeZone: '常用时区的日期',
+      },
+      homeTimeZone: '常用时区',
+      dayStartHour: '每天开始时间',
+      keepHistoryForever: '永久保留每日记录',
+      historyRetentionDays: '每日记录保留天数',
       goalsTitle: '目标',
       goalsLoading: '正在加载目标...',
       dailyPomodoroTarget: '每日番茄目标',
+      dailyMinuteTarget: '每日专注分钟目标',
+      dailyGoalUnit: '每日目标单位',
+      goalUnits: {
+        pomodoros: '番茄数',
+        minutes: '专注分钟',
+      },
       goalPeriods: {
         daily: '每日',
         weekly: '每周',
         monthly: '每月',
       },
+      restDays: '休息日（不中断连续记录）',
+      weekdays: {
+        monday: '周一',
+        tuesday: '周二',
+        wednesday: '周三',
+        thursday: '周四',
+        friday: '周五',
+        saturday: '周六',
+        sunday: '周日',
+      },
       focusTarget: '专注目标',
       longCycleTarget: '长循环目标',
       saveGoals: '保存目标',
+      yearOverYearTitle: '同比',
+      yearOverYear: (month: string, current: number, previousYear: number | null) =>
+        previousYear === null
+          ? `${month}：专注 ${current} 次，去年同月暂无数据`
+          : `${month}：专注 ${current} 次，去年同月 ${previousYear} 次`,
+      unmovedByDayChange: (focusCompleted: number, longCycleCompleted: number) =>
+        `有 ${focusCompleted} 次专注和 ${longCycleCompleted} 次长循环没有会话记录，日期划分变更后仍保留在原来的日期。`,
+      streaksTitle: '连续记录',
+      streakKinds: {
+        goalMet: '达成每日目标',
+        active: '至少完成一个番茄',
+      },
+      streakValue: (current: number, longest: number) =>
+        `${current} 天（最长 ${longest} 天）`,
+      streakMilestone: (kind: StreakKey, days: number) =>
+        kind === 'goalMet'
+          ? `连续 ${days} 天达成每日目标！`
+          : `连续 ${days} 天专注！`,
+      distributionTitle: '专注时段分布',
+      distributionRanges: {
+        week: '7 天',
+        month: '30 天',
+        quarter: '90 天',
+        year: '1 年',
+      },
+      distributionCellTitle: (weekday: string, hour: string, minutes: number) =>
+        `${weekday} ${hour}：${minutes} 分钟`,
+      distributionBest: (hour: string, weekday: string) =>
+        `最佳时段：${hour} · 最佳日期：${weekday}`,
+      distributionEmpty: '该时间范围内还没有完成的专注。',
     },
     insights: {
       triggerLabel: '统计',